@@ -0,0 +1,4 @@
+DROP TRIGGER IF EXISTS nfe_audit_log_immutable ON nfe_audit_log;
+DROP FUNCTION IF EXISTS nfe_audit_log_reject_modification();
+DROP INDEX IF EXISTS idx_nfe_audit_log_document;
+DROP TABLE IF EXISTS nfe_audit_log;
@@ -0,0 +1,24 @@
+-- Append-only audit trail of NFe status changes
+CREATE TABLE nfe_audit_log (
+    id SERIAL PRIMARY KEY,
+    tenant_id VARCHAR(36) NOT NULL,
+    nfe_id VARCHAR(50) NOT NULL, -- NFe access key
+    from_status VARCHAR(20) NOT NULL,
+    to_status VARCHAR(20) NOT NULL,
+    changed_by VARCHAR(255) NOT NULL,
+    reason TEXT,
+    changed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_nfe_audit_log_document ON nfe_audit_log(tenant_id, nfe_id, changed_at);
+
+-- Audit rows are immutable once written
+CREATE OR REPLACE FUNCTION nfe_audit_log_reject_modification() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'nfe_audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER nfe_audit_log_immutable
+    BEFORE UPDATE OR DELETE ON nfe_audit_log
+    FOR EACH ROW EXECUTE FUNCTION nfe_audit_log_reject_modification();
@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS nfe_documents_version ON nfe_documents;
+DROP FUNCTION IF EXISTS nfe_documents_bump_version();
+ALTER TABLE nfe_documents DROP COLUMN IF EXISTS version;
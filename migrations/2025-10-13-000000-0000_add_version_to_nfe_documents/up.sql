@@ -0,0 +1,14 @@
+-- Optimistic-locking counter, bumped on every update
+ALTER TABLE nfe_documents ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+
+-- Bump in the database so no UPDATE path can skip it
+CREATE OR REPLACE FUNCTION nfe_documents_bump_version() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.version := OLD.version + 1;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER nfe_documents_version
+    BEFORE UPDATE ON nfe_documents
+    FOR EACH ROW EXECUTE FUNCTION nfe_documents_bump_version();
@@ -0,0 +1,10 @@
+-- Correction letters (CC-e) amending authorized NFe documents
+CREATE TABLE nfe_correction_events (
+    id SERIAL PRIMARY KEY,
+    tenant_id VARCHAR(36) NOT NULL,
+    nfe_id VARCHAR(50) NOT NULL, -- NFe access key
+    sequencia INTEGER NOT NULL, -- nSeqEvento, increasing per document
+    texto_correcao TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (tenant_id, nfe_id, sequencia)
+);
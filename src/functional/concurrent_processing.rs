@@ -781,6 +781,9 @@ fn concurrent_processor_config() {
         chunk_size: 100,
         adaptive_chunk_sizing: false,
         max_chunk_size: 1024,
+        history_namespace: None,
+        timeout: None,
+        collect_chunk_timings: false,
     };
 
     let processor = ConcurrentProcessor::new(config).expect("should build processor");
@@ -798,6 +801,9 @@ fn concurrent_processor_with_config() {
         chunk_size: 200,
         adaptive_chunk_sizing: true,
         max_chunk_size: 2048,
+        history_namespace: None,
+        timeout: None,
+        collect_chunk_timings: false,
     };
 
     let new_processor = processor.with_config(new_config).expect("should build");
@@ -996,6 +1002,9 @@ fn concurrent_processing_error_invalid_thread_pool() {
         chunk_size: 100,
         adaptive_chunk_sizing: false,
         max_chunk_size: 1024,
+        history_namespace: None,
+        timeout: None,
+        collect_chunk_timings: false,
     };
 
     // Should succeed with 0 threads (uses default)
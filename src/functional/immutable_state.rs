@@ -16,10 +16,35 @@ use crate::models::tenant::Tenant;
 use im;
 use serde::{Deserialize, Serialize};
 #[allow(dead_code)]
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use std::time::{Duration, Instant};
 
+/// Errors returned by [`ImmutableStateManager`] operations
+///
+/// `Display` keeps the wording of the former string errors, so existing log lines
+/// read the same.
+#[derive(Debug, thiserror::Error)]
+pub enum StateError {
+    #[error("Tenant '{0}' not found")]
+    TenantNotFound(String),
+
+    #[error("Tenant '{0}' already exists")]
+    TenantExists(String),
+
+    #[error("Snapshot not found: {0}")]
+    SnapshotNotFound(String),
+
+    #[error("Memory limit exceeded: {limit_mb} MB limit configured")]
+    MemoryLimitExceeded { limit_mb: usize },
+
+    #[error("Transition failed: {0}")]
+    Transition(#[from] crate::functional::state_transitions::TransitionError),
+
+    #[error("Snapshot serialization failed: {0}")]
+    Serialization(String),
+}
+
 /// State transition metrics for performance monitoring
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateTransitionMetrics {
@@ -31,6 +56,10 @@ pub struct StateTransitionMetrics {
     pub memory_overhead_percent: f64,
     /// Peak memory usage in bytes
     pub peak_memory_usage: usize,
+    /// Estimated memory held by this manager in bytes
+    pub estimated_memory_usage: usize,
+    /// Latest memory sample in bytes, checked against `max_memory_mb`
+    pub current_memory_usage: usize,
 }
 
 impl Default for StateTransitionMetrics {
@@ -44,6 +73,8 @@ impl Default for StateTransitionMetrics {
     /// assert_eq!(m.transition_count, 0);
     /// assert_eq!(m.memory_overhead_percent, 0.0);
     /// assert_eq!(m.peak_memory_usage, 0);
+    /// assert_eq!(m.estimated_memory_usage, 0);
+    /// assert_eq!(m.current_memory_usage, 0);
     /// ```
     fn default() -> Self {
         Self {
@@ -51,6 +82,82 @@ impl Default for StateTransitionMetrics {
             transition_count: 0,
             memory_overhead_percent: 0.0,
             peak_memory_usage: 0,
+            estimated_memory_usage: 0,
+            current_memory_usage: 0,
+        }
+    }
+}
+
+/// Latency percentiles for one tenant's state transitions
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    /// Number of recorded transitions
+    pub count: u64,
+    /// Fastest observed transition in nanoseconds
+    pub min_ns: u64,
+    /// Slowest observed transition in nanoseconds
+    pub max_ns: u64,
+    /// Median transition time in nanoseconds
+    pub p50_ns: u64,
+    /// 95th percentile transition time in nanoseconds
+    pub p95_ns: u64,
+    /// 99th percentile transition time in nanoseconds
+    pub p99_ns: u64,
+}
+
+/// Power-of-two latency histogram: bucket `b` counts samples whose bit length is `b`
+#[derive(Clone, Debug)]
+struct LatencyHistogram {
+    buckets: [u64; 65],
+    count: u64,
+    min_ns: u64,
+    max_ns: u64,
+}
+
+impl LatencyHistogram {
+    fn new() -> Self {
+        Self {
+            buckets: [0; 65],
+            count: 0,
+            min_ns: u64::MAX,
+            max_ns: 0,
+        }
+    }
+
+    fn record(&mut self, duration: Duration) {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        self.buckets[(u64::BITS - nanos.leading_zeros()) as usize] += 1;
+        self.count += 1;
+        self.min_ns = self.min_ns.min(nanos);
+        self.max_ns = self.max_ns.max(nanos);
+    }
+
+    /// Upper bound of the bucket holding the `quantile` sample, clamped to the observed range
+    fn percentile(&self, quantile: f64) -> u64 {
+        let rank = ((self.count as f64 * quantile).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bits, &bucket) in self.buckets.iter().enumerate() {
+            seen += bucket;
+            if seen >= rank {
+                let upper = match bits {
+                    0 => 0,
+                    64 => u64::MAX,
+                    _ => (1u64 << bits) - 1,
+                };
+                return upper.clamp(self.min_ns, self.max_ns);
+            }
+        }
+        self.max_ns
+    }
+
+    fn stats(&self) -> LatencyStats {
+        LatencyStats {
+            count: self.count,
+            min_ns: self.min_ns,
+            max_ns: self.max_ns,
+            p50_ns: self.percentile(0.50),
+            p95_ns: self.percentile(0.95),
+            p99_ns: self.percentile(0.99),
         }
     }
 }
@@ -206,6 +313,31 @@ impl<T: Clone> PersistentVector<T> {
         }
     }
 
+    /// Builds a persistent vector from any iterator in a single pass.
+    ///
+    /// Prefer this over chained [`append`](Self::append) for bulk imports: each `append`
+    /// clones the current tree, concatenates a one-element vector and allocates a new root,
+    /// so building `n` elements that way does `n` concatenations and `n` root allocations.
+    /// Here the elements are pushed into one `im::Vector` (O(n) overall) and wrapped once.
+    /// An empty iterator yields an empty vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let pv = PersistentVector::with_capacity_from((0..5).map(|i| i * 2));
+    /// assert_eq!(pv.to_vec(), vec![0, 2, 4, 6, 8]);
+    /// ```
+    pub fn with_capacity_from(iter: impl IntoIterator<Item = T>) -> Self {
+        let vec: im::Vector<T> = iter.into_iter().collect();
+        if vec.is_empty() {
+            Self::new()
+        } else {
+            Self {
+                root: Some(Arc::new(vec)),
+            }
+        }
+    }
+
     /// Fetches a reference to the element at the given index.
     ///
     /// # Examples
@@ -469,6 +601,36 @@ where
         }
     }
 
+    /// Creates a new map with every `(key, value)` pair from `entries` set, later pairs
+    /// overwriting earlier ones with the same key.
+    ///
+    /// Unlike chaining [`insert`](Self::insert), the underlying map is copied once and then
+    /// filled in place, so only one new map is allocated for the whole batch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let m = PersistentHashMap::<String, i32>::new().insert("a".to_string(), 1);
+    /// let m2 = m.insert_all(vec![("b".to_string(), 2), ("c".to_string(), 3)]);
+    /// assert_eq!(m.len(), 1);
+    /// assert_eq!(m2.len(), 3);
+    /// ```
+    pub fn insert_all(&self, entries: impl IntoIterator<Item = (K, V)>) -> Self {
+        let mut new_map = self
+            .root
+            .as_ref()
+            .map_or_else(im::HashMap::new, |map| (**map).clone());
+        new_map.extend(entries);
+
+        if new_map.is_empty() {
+            Self::new()
+        } else {
+            Self {
+                root: Some(Arc::new(new_map)),
+            }
+        }
+    }
+
     /// Produces a new map with the specified key removed.
     ///
     /// The returned map shares structure with the original and only releases
@@ -571,8 +733,80 @@ where
     }
 }
 
+/// Key-level differences between two versions of a keyed collection
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DiffResult<K> {
+    /// Keys present only in the newer version
+    pub added: Vec<K>,
+    /// Keys present only in the older version
+    pub removed: Vec<K>,
+    /// Keys present in both versions with different values
+    pub modified: Vec<K>,
+}
+
+impl<K> DiffResult<K> {
+    /// Whether the two compared versions are identical.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Structural comparison between two versions of the same value
+pub trait Diff {
+    /// Identifier used to report individual differences
+    type Key;
+
+    /// Compares `self` (the older version) against `other` (the newer version).
+    ///
+    /// The order of keys inside each list is unspecified.
+    fn diff(&self, other: &Self) -> DiffResult<Self::Key>;
+}
+
+impl<K, V> Diff for PersistentHashMap<K, V>
+where
+    K: Clone + Eq + std::hash::Hash,
+    V: Clone + PartialEq,
+{
+    type Key = K;
+
+    /// Compares two maps key by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let before = PersistentHashMap::new().insert("a".to_string(), 1);
+    /// let after = before.insert("b".to_string(), 2);
+    /// let diff = before.diff(&after);
+    /// assert_eq!(diff.added, vec!["b".to_string()]);
+    /// assert!(diff.removed.is_empty() && diff.modified.is_empty());
+    /// ```
+    fn diff(&self, other: &Self) -> DiffResult<K> {
+        let mut result = DiffResult {
+            added: Vec::new(),
+            removed: Vec::new(),
+            modified: Vec::new(),
+        };
+
+        for (key, value) in self.iter() {
+            match other.get(key) {
+                None => result.removed.push(key.clone()),
+                Some(other_value) if other_value != value => result.modified.push(key.clone()),
+                Some(_) => {}
+            }
+        }
+
+        result.added = other
+            .iter()
+            .filter(|(key, _)| !self.contains_key(key))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        result
+    }
+}
+
 /// Session data with expiration information
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SessionData {
     /// User data (typically user ID and metadata)
     pub user_data: String,
@@ -625,8 +859,117 @@ pub struct StateSnapshot {
     pub description: Option<String>,
     /// Tags for categorization and filtering
     pub tags: Vec<String>,
+    /// Pinned snapshots are never removed by retention pruning
+    pub pinned: bool,
     /// The immutable state at this point in time
-    pub state: Arc<TenantApplicationState>,
+    pub state: SnapshotState,
+}
+
+/// State captured by a snapshot, either shared with the live state or compressed
+#[derive(Clone, Debug)]
+pub enum SnapshotState {
+    /// Shares structure with the live state; cheap to create and restore
+    Full(Arc<TenantApplicationState>),
+    /// Serialized and deflated copy; costs CPU to restore but holds no shared structure
+    Compressed(CompressedSnapshot),
+}
+
+/// Deflate-compressed JSON encoding of a `TenantApplicationState`
+#[derive(Clone, Debug)]
+pub struct CompressedSnapshot {
+    /// Compressed JSON bytes
+    bytes: Arc<[u8]>,
+    /// `last_updated` of the captured state, kept uncompressed for change detection
+    last_updated: chrono::DateTime<chrono::Utc>,
+}
+
+/// Owned, serializable mirror of `TenantApplicationState`
+#[derive(Serialize, Deserialize)]
+struct SerializedTenantState {
+    tenant: Tenant,
+    user_sessions: HashMap<String, SessionData>,
+    app_data: HashMap<String, serde_json::Value>,
+    query_cache: Vec<QueryResult>,
+    last_updated: chrono::DateTime<chrono::Utc>,
+}
+
+impl CompressedSnapshot {
+    /// Serializes `state` to JSON and deflates it.
+    pub fn compress(state: &TenantApplicationState) -> Result<Self, StateError> {
+        use std::io::Write;
+
+        let serialized = SerializedTenantState {
+            tenant: state.tenant.clone(),
+            user_sessions: state.user_sessions.to_hashmap(),
+            app_data: state.app_data.to_hashmap(),
+            query_cache: state.query_cache.to_vec(),
+            last_updated: state.last_updated,
+        };
+        let json = serde_json::to_vec(&serialized)
+            .map_err(|e| StateError::Serialization(format!("failed to serialize: {}", e)))?;
+
+        let mut encoder =
+            flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(&json)
+            .map_err(|e| StateError::Serialization(format!("failed to compress: {}", e)))?;
+        let bytes = encoder
+            .finish()
+            .map_err(|e| StateError::Serialization(format!("failed to compress: {}", e)))?;
+
+        Ok(Self {
+            bytes: bytes.into(),
+            last_updated: state.last_updated,
+        })
+    }
+
+    /// Inflates and deserializes the captured state.
+    pub fn decompress(&self) -> Result<TenantApplicationState, StateError> {
+        let decoder = flate2::read::DeflateDecoder::new(&self.bytes[..]);
+        let serialized: SerializedTenantState = serde_json::from_reader(decoder)
+            .map_err(|e| StateError::Serialization(format!("failed to decompress: {}", e)))?;
+
+        Ok(TenantApplicationState {
+            tenant: serialized.tenant,
+            user_sessions: serialized
+                .user_sessions
+                .into_iter()
+                .fold(PersistentHashMap::new(), |map, (key, value)| {
+                    map.insert(key, value)
+                }),
+            app_data: serialized
+                .app_data
+                .into_iter()
+                .fold(PersistentHashMap::new(), |map, (key, value)| {
+                    map.insert(key, value)
+                }),
+            query_cache: PersistentVector::from_vec(serialized.query_cache),
+            last_updated: serialized.last_updated,
+        })
+    }
+
+    /// Size of the compressed payload in bytes
+    pub fn compressed_len(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+impl SnapshotState {
+    /// Returns the captured state, decompressing it if necessary.
+    pub fn restore(&self) -> Result<Arc<TenantApplicationState>, StateError> {
+        match self {
+            SnapshotState::Full(state) => Ok(Arc::clone(state)),
+            SnapshotState::Compressed(compressed) => compressed.decompress().map(Arc::new),
+        }
+    }
+
+    /// `last_updated` of the captured state, available without decompressing
+    pub fn last_updated(&self) -> chrono::DateTime<chrono::Utc> {
+        match self {
+            SnapshotState::Full(state) => state.last_updated,
+            SnapshotState::Compressed(compressed) => compressed.last_updated,
+        }
+    }
 }
 
 /// Snapshot history manager for a single tenant
@@ -669,9 +1012,19 @@ impl SnapshotHistory {
     }
 
     /// Prunes old snapshots based on retention policies, removing oldest snapshots first
+    ///
+    /// Pinned snapshots are exempt: they neither count towards the limits nor get removed.
     fn prune_snapshots(&mut self, is_named: bool) {
-        let auto_count = self.snapshots.iter().filter(|s| s.name.is_none()).count();
-        let named_count = self.snapshots.iter().filter(|s| s.name.is_some()).count();
+        let auto_count = self
+            .snapshots
+            .iter()
+            .filter(|s| s.name.is_none() && !s.pinned)
+            .count();
+        let named_count = self
+            .snapshots
+            .iter()
+            .filter(|s| s.name.is_some() && !s.pinned)
+            .count();
 
         // Remove oldest automatic snapshots if over limit (keep newest ones)
         if !is_named && auto_count > self.max_auto_snapshots {
@@ -679,8 +1032,8 @@ impl SnapshotHistory {
             let to_remove = auto_count - self.max_auto_snapshots;
             let mut removed = 0;
             self.snapshots.retain(|s| {
-                // Keep all named snapshots
-                if s.name.is_some() {
+                // Keep all named and pinned snapshots
+                if s.name.is_some() || s.pinned {
                     return true;
                 }
                 // Remove oldest automatic snapshots
@@ -691,6 +1044,8 @@ impl SnapshotHistory {
                     true
                 }
             });
+            // Removals shift the positions of named snapshots
+            self.rebuild_named_index();
         }
 
         // Remove oldest named snapshots if over limit (keep newest ones)
@@ -699,8 +1054,8 @@ impl SnapshotHistory {
             let to_remove = named_count - self.max_named_snapshots;
             let mut removed = 0;
             self.snapshots.retain(|s| {
-                // Keep all automatic snapshots
-                if s.name.is_none() {
+                // Keep all automatic and pinned snapshots
+                if s.name.is_none() || s.pinned {
                     return true;
                 }
                 // Remove oldest named snapshots
@@ -726,6 +1081,43 @@ impl SnapshotHistory {
         }
     }
 
+    /// Removes the oldest automatic snapshot that is not pinned
+    ///
+    /// Returns `false` if every remaining snapshot is named or pinned.
+    pub fn remove_oldest_unpinned_auto(&mut self) -> bool {
+        match self
+            .snapshots
+            .iter()
+            .position(|s| s.name.is_none() && !s.pinned)
+        {
+            Some(idx) => {
+                self.snapshots.remove(idx);
+                // Removal shifts the positions of later named snapshots
+                self.rebuild_named_index();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the pinned flag of the snapshot with `snapshot_id`
+    ///
+    /// Returns `false` if no such snapshot exists. Unpinning does not prune immediately;
+    /// the snapshot becomes eligible again on the next `add_snapshot`.
+    pub fn set_pinned(&mut self, snapshot_id: &str, pinned: bool) -> bool {
+        match self
+            .snapshots
+            .iter_mut()
+            .find(|s| s.snapshot_id == snapshot_id)
+        {
+            Some(snapshot) => {
+                snapshot.pinned = pinned;
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Retrieves a snapshot by name
     pub fn get_named_snapshot(&self, name: &str) -> Option<&StateSnapshot> {
         self.named_snapshots
@@ -772,6 +1164,7 @@ impl SnapshotHistory {
                 created_by: s.created_by.clone(),
                 description: s.description.clone(),
                 tags: s.tags.clone(),
+                pinned: s.pinned,
             })
             .collect()
     }
@@ -787,8 +1180,134 @@ pub struct SnapshotMetadata {
     pub created_by: String,
     pub description: Option<String>,
     pub tags: Vec<String>,
+    pub pinned: bool,
+}
+
+/// Approximate memory retained by a tenant's live state and snapshot history
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryReport {
+    /// Estimated bytes held by the live state alone
+    pub live_bytes: usize,
+    /// Estimated bytes held by the live state and all snapshots, counting shared allocations once
+    pub shared_bytes: usize,
+    /// Number of snapshots walked
+    pub snapshot_count: usize,
+}
+
+/// Differences between two versions of a tenant's application state
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StateDiff {
+    /// Session keys added, removed or changed
+    pub user_sessions: DiffResult<String>,
+    /// Application data keys added, removed or changed
+    pub app_data: DiffResult<String>,
+    /// Number of cached query results in the older state
+    pub query_cache_before: usize,
+    /// Number of cached query results in the newer state
+    pub query_cache_after: usize,
+}
+
+impl StateDiff {
+    /// Compares `from` (the older state) against `to` (the newer state).
+    pub fn between(from: &TenantApplicationState, to: &TenantApplicationState) -> Self {
+        Self {
+            user_sessions: from.user_sessions.diff(&to.user_sessions),
+            app_data: from.app_data.diff(&to.app_data),
+            query_cache_before: from.query_cache.len(),
+            query_cache_after: to.query_cache.len(),
+        }
+    }
+
+    /// Whether the two states hold the same sessions, app data and cache size.
+    pub fn is_empty(&self) -> bool {
+        self.user_sessions.is_empty()
+            && self.app_data.is_empty()
+            && self.query_cache_before == self.query_cache_after
+    }
+}
+
+/// One point of a tenant's snapshot timeline, see [`ImmutableStateManager::snapshot_timeline`]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub snapshot_id: String,
+    pub name: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Seconds elapsed since the previous snapshot; `None` for the oldest one
+    pub seconds_since_previous: Option<f64>,
+    /// Application data keys added, removed or changed versus the previous snapshot
+    /// (versus empty app data for the oldest one)
+    pub changed_keys: usize,
+}
+
+/// Audit record of a rollback, see [`ImmutableStateManager::rollback_to_named_snapshot_audited`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RollbackEvent {
+    pub snapshot_name: String,
+    pub performed_by: String,
+    pub reason: String,
+    pub at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Token bucket limiting how often transitions are applied for one tenant
+#[derive(Clone, Debug)]
+struct Bucket {
+    /// Tokens currently available; one is spent per applied transition
+    tokens: f64,
+    /// When tokens were last refilled
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn full(capacity: u32) -> Self {
+        Self {
+            tokens: f64::from(capacity),
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Refills at `rate` tokens per second (capped at `rate`) and spends one token if available.
+    fn try_acquire(&mut self, rate: u32) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        let capacity = f64::from(rate);
+        self.tokens = (self.tokens + elapsed * capacity).min(capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Per-tenant schedule for automatic snapshots taken by `apply_transition`
+#[derive(Clone, Copy, Debug)]
+struct AutoSnapshotSchedule {
+    /// Take a snapshot after this many successful transitions
+    every_n: usize,
+    /// Successful transitions since the last automatic snapshot
+    since_last: usize,
 }
 
+/// `io::Write` sink that only counts bytes, for sizing JSON without building a string
+struct ByteCounter(usize);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Produces snapshot IDs; see [`ImmutableStateManager::with_snapshot_id_generator`]
+pub type SnapshotIdGenerator = Box<dyn Fn() -> String + Send + Sync>;
+
 /// Global immutable state manager
 ///
 /// This manages the complete application state across all tenants
@@ -798,6 +1317,16 @@ pub struct ImmutableStateManager {
     tenant_states: RwLock<HashMap<String, Arc<TenantApplicationState>>>,
     /// Snapshot histories per tenant
     snapshot_histories: RwLock<HashMap<String, SnapshotHistory>>,
+    /// Per-tenant token buckets used by `apply_transition_rate_limited`
+    rate_buckets: RwLock<HashMap<String, Bucket>>,
+    /// Per-tenant transition latency histograms
+    tenant_latencies: RwLock<HashMap<String, LatencyHistogram>>,
+    /// Per-tenant automatic snapshot intervals, see `set_auto_snapshot_interval`
+    auto_snapshot_schedules: RwLock<HashMap<String, AutoSnapshotSchedule>>,
+    /// Per-tenant audit log of rollbacks, oldest first
+    rollback_logs: RwLock<HashMap<String, Vec<RollbackEvent>>>,
+    /// Transitions slower than this are logged, see `set_slow_transition_threshold`
+    slow_transition_threshold: RwLock<Option<Duration>>,
     /// Performance metrics
     metrics: RwLock<StateTransitionMetrics>,
     /// Maximum memory usage limit
@@ -806,6 +1335,8 @@ pub struct ImmutableStateManager {
     max_auto_snapshots: usize,
     /// Maximum named snapshots per tenant
     max_named_snapshots: usize,
+    /// Custom snapshot ID source; `None` uses timestamp + UUID fragment IDs
+    snapshot_id_generator: Option<SnapshotIdGenerator>,
 }
 
 impl ImmutableStateManager {
@@ -838,18 +1369,66 @@ impl ImmutableStateManager {
         Self {
             tenant_states: RwLock::new(HashMap::new()),
             snapshot_histories: RwLock::new(HashMap::new()),
+            rate_buckets: RwLock::new(HashMap::new()),
+            tenant_latencies: RwLock::new(HashMap::new()),
+            auto_snapshot_schedules: RwLock::new(HashMap::new()),
+            rollback_logs: RwLock::new(HashMap::new()),
+            slow_transition_threshold: RwLock::new(None),
             metrics: RwLock::new(StateTransitionMetrics::default()),
             max_memory_mb,
             max_auto_snapshots,
             max_named_snapshots,
+            snapshot_id_generator: None,
         }
     }
 
+    /// Replaces the snapshot ID scheme with `generator`
+    ///
+    /// By default IDs combine the tenant ID, a millisecond timestamp and a random UUID
+    /// fragment. Supplying a deterministic generator makes IDs predictable, e.g. in tests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let counter = AtomicUsize::new(0);
+    /// let manager = ImmutableStateManager::new(100).with_snapshot_id_generator(Box::new(
+    ///     move || format!("snap-{}", counter.fetch_add(1, Ordering::SeqCst)),
+    /// ));
+    /// ```
+    pub fn with_snapshot_id_generator(mut self, generator: SnapshotIdGenerator) -> Self {
+        self.snapshot_id_generator = Some(generator);
+        self
+    }
+
+    /// Acquires a read guard, recovering the data if a previous holder panicked.
+    ///
+    /// Transitions only swap the stored `Arc` after they return, so a panic while a lock
+    /// is held never leaves a half-written map behind. Rather than refusing every later
+    /// call, the poison flag is cleared and a warning logged.
+    fn read_recovering<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+        lock.read().unwrap_or_else(|poisoned| {
+            log::warn!("Recovering poisoned lock in ImmutableStateManager");
+            lock.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
+    /// Acquires a write guard, recovering the data if a previous holder panicked.
+    ///
+    /// See [`read_recovering`](Self::read_recovering) for why this is safe.
+    fn write_recovering<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+        lock.write().unwrap_or_else(|poisoned| {
+            log::warn!("Recovering poisoned lock in ImmutableStateManager");
+            lock.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
     /// Registers and initializes immutable application state for a new tenant.
     ///
     /// Creates a fresh `TenantApplicationState` (empty sessions, app data, and query cache,
     /// with `last_updated` set to now) and inserts it into the manager's tenant map.
-    /// Returns an error if a state for the tenant id already exists or if the internal lock is poisoned.
+    /// Returns an error if a state for the tenant id already exists.
     ///
     /// # Arguments
     ///
@@ -857,7 +1436,7 @@ impl ImmutableStateManager {
     ///
     /// # Returns
     ///
-    /// `Ok(())` if the tenant state was created and inserted successfully, `Err(String)` with a message if the tenant already exists or a lock error occurred.
+    /// `Ok(())` if the tenant state was created and inserted successfully, `Err(StateError::TenantExists)` if the tenant already exists.
     ///
     /// # Examples
     ///
@@ -870,21 +1449,47 @@ impl ImmutableStateManager {
     /// let tenant = Tenant { id: "tenant1".to_string(), ..Default::default() };
     /// manager.initialize_tenant(tenant).expect("initialization failed");
     /// ```
-    pub fn initialize_tenant(&self, tenant: Tenant) -> Result<(), String> {
-        let mut states = self.tenant_states.write().map_err(|_| "Lock poisoned")?;
-        let mut histories = self
-            .snapshot_histories
-            .write()
-            .map_err(|_| "Lock poisoned")?;
+    pub fn initialize_tenant(&self, tenant: Tenant) -> Result<(), StateError> {
+        self.initialize_tenant_with_defaults(tenant, HashMap::new())
+    }
+
+    /// Registers a new tenant whose `app_data` starts seeded from `default_app_data`.
+    ///
+    /// Behaves like [`initialize_tenant`](Self::initialize_tenant) otherwise: sessions and
+    /// query cache start empty, a snapshot history is created, and an already-existing
+    /// tenant is rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let manager = ImmutableStateManager::new(100);
+    /// let defaults = HashMap::from([("beta_features".to_string(), serde_json::json!(false))]);
+    /// manager
+    ///     .initialize_tenant_with_defaults(create_test_tenant("t1"), defaults)
+    ///     .unwrap();
+    /// ```
+    pub fn initialize_tenant_with_defaults(
+        &self,
+        tenant: Tenant,
+        default_app_data: HashMap<String, serde_json::Value>,
+    ) -> Result<(), StateError> {
+        let mut states = Self::write_recovering(&self.tenant_states);
+        let mut histories = Self::write_recovering(&self.snapshot_histories);
 
         if states.contains_key(&tenant.id) {
-            return Err(format!("Tenant '{}' already exists", tenant.id));
+            return Err(StateError::TenantExists(tenant.id.clone()));
         }
 
+        let app_data = default_app_data
+            .into_iter()
+            .fold(PersistentHashMap::new(), |map, (key, value)| {
+                map.insert(key, value)
+            });
+
         let state = Arc::new(TenantApplicationState {
             tenant,
             user_sessions: PersistentHashMap::new(),
-            app_data: PersistentHashMap::new(),
+            app_data,
             query_cache: PersistentVector::new(),
             last_updated: chrono::Utc::now(),
         });
@@ -912,7 +1517,7 @@ impl ImmutableStateManager {
     ///
     /// # Returns
     ///
-    /// `Ok(())` if the removal completed, `Err` if the internal lock is poisoned.
+    /// `Ok(())` once the removal completed.
     ///
     /// # Examples
     ///
@@ -924,12 +1529,44 @@ impl ImmutableStateManager {
     /// manager.remove_tenant("t1").unwrap();
     /// assert!(!manager.tenant_exists("t1"));
     /// ```
-    pub fn remove_tenant(&self, tenant_id: &str) -> Result<(), String> {
-        let mut states = self.tenant_states.write().map_err(|_| "Lock poisoned")?;
+    pub fn remove_tenant(&self, tenant_id: &str) -> Result<(), StateError> {
+        let mut states = Self::write_recovering(&self.tenant_states);
         states.remove(tenant_id);
         Ok(())
     }
 
+    /// Deprovisions a tenant, removing its state and snapshot history together.
+    ///
+    /// Both write locks are held for the duration of the removal so no caller can
+    /// observe the tenant state without its history or vice versa.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(true)` if either the state or the snapshot history was removed, `Ok(false)`
+    /// if the tenant was unknown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let manager = ImmutableStateManager::new(100);
+    /// manager.initialize_tenant(create_test_tenant("t1")).unwrap();
+    /// assert!(manager.deprovision_tenant("t1").unwrap());
+    /// assert!(!manager.deprovision_tenant("t1").unwrap());
+    /// ```
+    pub fn deprovision_tenant(&self, tenant_id: &str) -> Result<bool, StateError> {
+        let mut states = Self::write_recovering(&self.tenant_states);
+        let mut histories = Self::write_recovering(&self.snapshot_histories);
+
+        let removed_state = states.remove(tenant_id).is_some();
+        let removed_history = histories.remove(tenant_id).is_some();
+        Self::write_recovering(&self.rate_buckets).remove(tenant_id);
+        Self::write_recovering(&self.tenant_latencies).remove(tenant_id);
+        Self::write_recovering(&self.auto_snapshot_schedules).remove(tenant_id);
+        Self::write_recovering(&self.rollback_logs).remove(tenant_id);
+
+        Ok(removed_state || removed_history)
+    }
+
     /// Retrieve the current immutable state for a tenant.
     ///
     /// # Returns
@@ -943,16 +1580,18 @@ impl ImmutableStateManager {
     /// assert!(manager.get_tenant_state("tenant1").is_some() || manager.get_tenant_state("tenant1").is_none());
     /// ```
     pub fn get_tenant_state(&self, tenant_id: &str) -> Option<Arc<TenantApplicationState>> {
-        let states = self.tenant_states.read().ok()?;
+        let states = Self::read_recovering(&self.tenant_states);
         states.get(tenant_id).cloned()
     }
 
     /// Applies a functional transition to a tenant's immutable state.
     ///
-    /// Replaces the stored state for `tenant_id` with the state produced by `transition`.
+    /// Replaces the stored state for `tenant_id` with the state produced by `transition`, then
+    /// takes an automatic snapshot if one is due (see
+    /// [`set_auto_snapshot_interval`](Self::set_auto_snapshot_interval)).
     ///
     /// # Errors
-    /// Returns `Err` if the tenant is not found, the provided transition returns an error, or an internal lock is poisoned.
+    /// Returns `Err` if the tenant is not found or the provided transition returns an error.
     ///
     /// # Examples
     ///
@@ -969,7 +1608,7 @@ impl ImmutableStateManager {
     /// });
     /// assert!(result.is_ok());
     /// ```
-    pub fn apply_transition<F>(&self, tenant_id: &str, transition: F) -> Result<(), String>
+    pub fn apply_transition<F>(&self, tenant_id: &str, transition: F) -> Result<(), StateError>
     where
         F: FnOnce(
             &TenantApplicationState,
@@ -978,70 +1617,299 @@ impl ImmutableStateManager {
             crate::functional::state_transitions::TransitionError,
         >,
     {
-        let start = Instant::now();
-
-        let mut states = self.tenant_states.write().map_err(|_| "Lock poisoned")?;
-
-        let current_state = match states.get(tenant_id) {
-            Some(state) => state,
-            None => return Err(format!("Tenant '{}' not found", tenant_id)),
-        };
-
-        // Apply the functional transition
-        let new_state =
-            transition(current_state).map_err(|e| format!("Transition failed: {}", e))?;
-        let new_state_arc = Arc::new(new_state);
-
-        // Capture the previous entry before mutating the map
-        let previous = states.insert(tenant_id.to_string(), new_state_arc);
-
-        // Update metrics and enforce memory limit
-        let duration = start.elapsed();
-        self.update_metrics(duration)?;
-        
-        // Check if memory limit is exceeded
-        if !self.check_memory_limits()? {
-            // Restore the previous state if the memory check fails
-            match previous {
-                Some(prev_arc) => {
-                    states.insert(tenant_id.to_string(), prev_arc);
-                }
-                None => {
-                    states.remove(tenant_id);
-                }
-            }
-            return Err(format!(
-                "Memory limit exceeded: {} MB limit configured",
-                self.max_memory_mb
-            ));
-        }
-
-        Ok(())
+        self.apply_transition_if(tenant_id, |_| true, transition)?;
+        self.record_auto_snapshot_transition(tenant_id)
     }
 
-    /// Applies multiple functional transitions atomically to a tenant's state.
-    ///
-    /// Each transition is applied sequentially to an owned copy of the tenant's state; after all transitions complete,
-    /// the tenant's state is replaced with the final resulting state. If the iterator yields no transitions, no state
-    /// change is performed and the call returns immediately.
+    /// Applies a transition like [`apply_transition`](Self::apply_transition) and returns
+    /// what it changed.
     ///
-    /// # Parameters
-    /// * `tenant_id` - Identifier of the tenant whose state will be updated.
-    /// * `transitions` - An iterator of functions that take `&TenantApplicationState` and return a new `TenantApplicationState`.
+    /// The diff is computed between the exact state the transition received and the state it
+    /// produced, so concurrent transitions on the same tenant never leak into it.
     ///
     /// # Returns
-    /// `Ok(())` if the transitions were applied and the tenant state updated; `Err(String)` if the tenant does not exist
-    /// or an internal error occurs (e.g., lock poisoning or metric update failure).
+    /// The `StateDiff` from the previous state to the new one
     ///
     /// # Examples
     ///
     /// ```
-    /// # use std::sync::Arc;
-    /// # use chrono::Utc;
-    /// # // Setup omitted: create manager and initialize tenant "t1"
-    /// // Apply two simple no-op-ish transitions (clone and update timestamp)
-    /// let transitions = vec![
-    ///     |s: &TenantApplicationState| {
+    /// let diff = mgr.apply_transition_diffed("tenant_a", |state| {
+    ///     let mut next = state.clone();
+    ///     next.app_data = state.app_data.insert("theme".to_string(), serde_json::json!("dark"));
+    ///     Ok(next)
+    /// })?;
+    /// assert_eq!(diff.app_data.added, vec!["theme".to_string()]);
+    /// ```
+    pub fn apply_transition_diffed<F>(
+        &self,
+        tenant_id: &str,
+        transition: F,
+    ) -> Result<StateDiff, StateError>
+    where
+        F: FnOnce(
+            &TenantApplicationState,
+        ) -> Result<
+            TenantApplicationState,
+            crate::functional::state_transitions::TransitionError,
+        >,
+    {
+        let mut diff = StateDiff::default();
+        self.apply_transition(tenant_id, |state| {
+            let next = transition(state)?;
+            diff = StateDiff::between(state, &next);
+            Ok(next)
+        })?;
+
+        Ok(diff)
+    }
+
+    /// Makes `apply_transition` log a warning for every transition slower than `threshold`
+    ///
+    /// Meant to catch accidentally expensive transition closures; the warning names the
+    /// tenant and the measured duration. A zero threshold disables the warning.
+    pub fn set_slow_transition_threshold(&self, threshold: Duration) {
+        *Self::write_recovering(&self.slow_transition_threshold) =
+            (!threshold.is_zero()).then_some(threshold);
+    }
+
+    /// Makes `apply_transition` snapshot the tenant automatically every `every_n` successful
+    /// transitions
+    ///
+    /// Automatic snapshots are unnamed, created by "system" and tagged `auto`, so they fall
+    /// under the automatic snapshot retention limit. Setting a new interval restarts the count.
+    ///
+    /// # Arguments
+    /// * `tenant_id` - The tenant to configure
+    /// * `every_n` - Transitions between snapshots; 0 disables automatic snapshots
+    ///
+    /// # Returns
+    /// Ok(()) once configured, `Err(StateError::TenantNotFound)` for an unknown tenant
+    pub fn set_auto_snapshot_interval(
+        &self,
+        tenant_id: &str,
+        every_n: usize,
+    ) -> Result<(), StateError> {
+        if !self.tenant_exists(tenant_id) {
+            return Err(StateError::TenantNotFound(tenant_id.to_string()));
+        }
+
+        let mut schedules = Self::write_recovering(&self.auto_snapshot_schedules);
+        if every_n == 0 {
+            schedules.remove(tenant_id);
+        } else {
+            schedules.insert(
+                tenant_id.to_string(),
+                AutoSnapshotSchedule {
+                    every_n,
+                    since_last: 0,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Counts a successful transition and takes the automatic snapshot when one is due.
+    fn record_auto_snapshot_transition(&self, tenant_id: &str) -> Result<(), StateError> {
+        let due = {
+            let mut schedules = Self::write_recovering(&self.auto_snapshot_schedules);
+            match schedules.get_mut(tenant_id) {
+                Some(schedule) => {
+                    schedule.since_last += 1;
+                    let due = schedule.since_last >= schedule.every_n;
+                    if due {
+                        schedule.since_last = 0;
+                    }
+                    due
+                }
+                None => false,
+            }
+        };
+
+        if due {
+            self.create_snapshot(
+                tenant_id,
+                None,
+                "system".to_string(),
+                Some("Automatic interval snapshot".to_string()),
+                vec!["auto".to_string()],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Applies a transition only when `precondition` holds for the current tenant state.
+    ///
+    /// The precondition is evaluated while the tenant map write lock is held, so no other
+    /// transition can slip in between the check and the update. This makes it suitable for
+    /// optimistic updates such as comparing a version counter stored in `app_data`.
+    ///
+    /// # Returns
+    /// `Ok(true)` if the precondition passed and the transition was applied, `Ok(false)` if the
+    /// precondition failed and the state was left untouched.
+    ///
+    /// # Errors
+    /// Returns `Err` if the tenant is not found, the transition returns an error, or the
+    /// memory limit is exceeded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let applied = mgr.apply_transition_if(
+    ///     "tenant_a",
+    ///     |state| state.app_data.get(&"version".to_string()) == Some(&serde_json::json!(1)),
+    ///     |state| {
+    ///         let mut next = state.clone();
+    ///         next.app_data = state.app_data.insert("version".to_string(), serde_json::json!(2));
+    ///         Ok(next)
+    ///     },
+    /// )?;
+    /// ```
+    pub fn apply_transition_if<P, F>(
+        &self,
+        tenant_id: &str,
+        precondition: P,
+        transition: F,
+    ) -> Result<bool, StateError>
+    where
+        P: Fn(&TenantApplicationState) -> bool,
+        F: FnOnce(
+            &TenantApplicationState,
+        ) -> Result<
+            TenantApplicationState,
+            crate::functional::state_transitions::TransitionError,
+        >,
+    {
+        let start = Instant::now();
+
+        let mut states = Self::write_recovering(&self.tenant_states);
+
+        let current_state = match states.get(tenant_id) {
+            Some(state) => state,
+            None => return Err(StateError::TenantNotFound(tenant_id.to_string())),
+        };
+
+        if !precondition(current_state) {
+            return Ok(false);
+        }
+
+        // Apply the functional transition
+        let new_state = transition(current_state)?;
+        let new_state_arc = Arc::new(new_state);
+
+        // Capture the previous entry before mutating the map
+        let previous = states.insert(tenant_id.to_string(), new_state_arc);
+
+        // Update metrics and enforce memory limit
+        let duration = start.elapsed();
+        self.update_metrics(tenant_id, duration)?;
+
+        if let Some(threshold) = *Self::read_recovering(&self.slow_transition_threshold) {
+            if duration > threshold {
+                log::warn!(
+                    "Slow transition for tenant {}: took {:?} (threshold {:?})",
+                    tenant_id,
+                    duration,
+                    threshold
+                );
+            }
+        }
+        
+        // Check if memory limit is exceeded
+        if !self.check_memory_limits()? {
+            // Restore the previous state if the memory check fails
+            match previous {
+                Some(prev_arc) => {
+                    states.insert(tenant_id.to_string(), prev_arc);
+                }
+                None => {
+                    states.remove(tenant_id);
+                }
+            }
+            return Err(StateError::MemoryLimitExceeded {
+                limit_mb: self.max_memory_mb,
+            });
+        }
+
+        Ok(true)
+    }
+
+    /// Applies a transition unless the tenant has exceeded `max_per_second` transitions.
+    ///
+    /// Each tenant gets a token bucket holding up to `max_per_second` tokens that refills
+    /// continuously at the same rate; every applied transition spends one token. When the
+    /// bucket is empty the transition is skipped rather than queued, protecting the manager
+    /// from a client flooding updates.
+    ///
+    /// # Returns
+    /// `Ok(true)` if the transition was applied, `Ok(false)` if it was skipped because the
+    /// rate limit was exceeded.
+    ///
+    /// # Errors
+    /// Returns `Err` under the same conditions as [`apply_transition`](Self::apply_transition).
+    /// An unknown tenant is rejected before any bucket is created for it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let applied = mgr.apply_transition_rate_limited("tenant_a", 10, |state| Ok(state.clone()))?;
+    /// ```
+    pub fn apply_transition_rate_limited<F>(
+        &self,
+        tenant_id: &str,
+        max_per_second: u32,
+        transition: F,
+    ) -> Result<bool, StateError>
+    where
+        F: FnOnce(
+            &TenantApplicationState,
+        ) -> Result<
+            TenantApplicationState,
+            crate::functional::state_transitions::TransitionError,
+        >,
+    {
+        if !self.tenant_exists(tenant_id) {
+            return Err(StateError::TenantNotFound(tenant_id.to_string()));
+        }
+
+        let acquired = {
+            let mut buckets = Self::write_recovering(&self.rate_buckets);
+            buckets
+                .entry(tenant_id.to_string())
+                .or_insert_with(|| Bucket::full(max_per_second))
+                .try_acquire(max_per_second)
+        };
+
+        if !acquired {
+            return Ok(false);
+        }
+
+        self.apply_transition(tenant_id, transition)?;
+        Ok(true)
+    }
+
+    /// Applies multiple functional transitions atomically to a tenant's state.
+    ///
+    /// Each transition is applied sequentially to an owned copy of the tenant's state; after all transitions complete,
+    /// the tenant's state is replaced with the final resulting state. If the iterator yields no transitions, no state
+    /// change is performed and the call returns immediately.
+    ///
+    /// # Parameters
+    /// * `tenant_id` - Identifier of the tenant whose state will be updated.
+    /// * `transitions` - An iterator of functions that take `&TenantApplicationState` and return a new `TenantApplicationState`.
+    ///
+    /// # Returns
+    /// `Ok(())` if the transitions were applied and the tenant state updated; `Err(StateError::TenantNotFound)` if the tenant
+    /// does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use chrono::Utc;
+    /// # // Setup omitted: create manager and initialize tenant "t1"
+    /// // Apply two simple no-op-ish transitions (clone and update timestamp)
+    /// let transitions = vec![
+    ///     |s: &TenantApplicationState| {
     ///         let mut ns = s.clone();
     ///         ns.last_updated = Utc::now();
     ///         ns
@@ -1054,18 +1922,18 @@ impl ImmutableStateManager {
     /// ];
     /// manager.apply_transitions("t1", transitions).unwrap();
     /// ```
-    pub fn apply_transitions<I, F>(&self, tenant_id: &str, transitions: I) -> Result<(), String>
+    pub fn apply_transitions<I, F>(&self, tenant_id: &str, transitions: I) -> Result<(), StateError>
     where
         I: IntoIterator<Item = F>,
         F: FnOnce(&TenantApplicationState) -> TenantApplicationState,
     {
         let start = Instant::now();
 
-        let mut states = self.tenant_states.write().map_err(|_| "Lock poisoned")?;
+        let mut states = Self::write_recovering(&self.tenant_states);
 
         let mut current_state = match states.get(tenant_id) {
             Some(state) => (**state).clone(),
-            None => return Err(format!("Tenant '{}' not found", tenant_id)),
+            None => return Err(StateError::TenantNotFound(tenant_id.to_string())),
         };
 
         // Apply all transitions sequentially
@@ -1087,119 +1955,447 @@ impl ImmutableStateManager {
         let total_duration = start.elapsed();
         let avg_duration = total_duration / transition_count as u32;
         for _ in 0..transition_count {
-            self.update_metrics(avg_duration)?;
+            self.update_metrics(tenant_id, avg_duration)?;
         }
 
         Ok(())
     }
 
-    /// Returns a clone of the current state transition metrics for the manager.
+    /// Applies a sequence of transitions like [`apply_transitions`](Self::apply_transitions),
+    /// timing each one individually.
     ///
-    /// On success, returns `Ok(StateTransitionMetrics)` containing a cloned snapshot of the metrics.
-    /// Returns `Err(String)` if the internal metrics lock is poisoned.
+    /// Each measured duration is recorded in the metrics and latency histogram, instead of
+    /// a batch-wide average, so a single slow transition stays visible.
+    ///
+    /// # Returns
+    /// The duration of each transition, in the order they were applied. An empty batch
+    /// leaves the state untouched and returns an empty vector.
+    ///
+    /// # Errors
+    /// Returns `Err` if the tenant is not found.
     ///
     /// # Examples
     ///
     /// ```
-    /// let mgr = ImmutableStateManager::default();
-    /// let metrics = mgr.get_metrics().expect("failed to read metrics");
-    /// // snapshot fields are accessible
-    /// assert_eq!(metrics.transition_count, 0);
+    /// let durations = manager.apply_transitions_timed("t1", transitions)?;
+    /// let slowest = durations.iter().max();
     /// ```
-    pub fn get_metrics(&self) -> Result<StateTransitionMetrics, String> {
-        let metrics = self.metrics.read().map_err(|_| "Lock poisoned")?;
-        Ok(metrics.clone())
+    pub fn apply_transitions_timed<I, F>(
+        &self,
+        tenant_id: &str,
+        transitions: I,
+    ) -> Result<Vec<Duration>, StateError>
+    where
+        I: IntoIterator<Item = F>,
+        F: FnOnce(&TenantApplicationState) -> TenantApplicationState,
+    {
+        let mut states = Self::write_recovering(&self.tenant_states);
+
+        let mut current_state = match states.get(tenant_id) {
+            Some(state) => (**state).clone(),
+            None => return Err(StateError::TenantNotFound(tenant_id.to_string())),
+        };
+
+        let mut durations = Vec::new();
+        for transition in transitions {
+            let start = Instant::now();
+            current_state = transition(&current_state);
+            durations.push(start.elapsed());
+        }
+
+        if durations.is_empty() {
+            return Ok(durations);
+        }
+
+        states.insert(tenant_id.to_string(), Arc::new(current_state));
+        drop(states);
+
+        for duration in &durations {
+            self.update_metrics(tenant_id, *duration)?;
+        }
+
+        Ok(durations)
     }
 
-    /// Determines whether a tenant state exists in the manager.
+    /// Replaces a tenant's entire `app_data` map in a single transition.
     ///
-    /// # Returns
+    /// A fresh `PersistentHashMap` is built from `new_data`, so keys absent from it are
+    /// dropped rather than merged. `last_updated` is refreshed as part of the swap.
     ///
-    /// `true` if a state for `tenant_id` exists, `false` otherwise.
+    /// # Errors
+    /// Returns `Err` if the tenant is not found.
     ///
     /// # Examples
     ///
     /// ```
     /// let manager = ImmutableStateManager::new(100);
-    /// let exists = manager.tenant_exists("tenant-123");
+    /// manager.initialize_tenant(create_test_tenant("t1")).unwrap();
+    /// let imported = HashMap::from([("theme".to_string(), serde_json::json!("dark"))]);
+    /// manager.replace_app_data("t1", imported).unwrap();
     /// ```
-    pub fn tenant_exists(&self, tenant_id: &str) -> bool {
-        let states = match self.tenant_states.read() {
-            Ok(states) => states,
-            Err(_) => return false,
-        };
-        states.contains_key(tenant_id)
+    pub fn replace_app_data(
+        &self,
+        tenant_id: &str,
+        new_data: HashMap<String, serde_json::Value>,
+    ) -> Result<(), StateError> {
+        let app_data = new_data
+            .into_iter()
+            .fold(PersistentHashMap::new(), |map, (key, value)| {
+                map.insert(key, value)
+            });
+
+        self.apply_transition(tenant_id, move |state| {
+            let mut new_state = state.clone();
+            new_state.app_data = app_data;
+            new_state.last_updated = chrono::Utc::now();
+            Ok(new_state)
+        })
     }
 
-    /// Checks whether the recorded peak memory usage is within the configured limit.
-    ///
-    /// The check converts the stored `peak_memory_usage` (bytes) to megabytes and compares it
-    /// against the manager's `max_memory_mb`.
+    /// Applies a JSON merge-patch to a tenant's `app_data` in a single transition.
     ///
-    /// # Returns
+    /// Every top-level key of `patch` is upserted with its value, except `null` values,
+    /// which delete the key. Keys not mentioned in the patch are left untouched, and
+    /// `last_updated` is refreshed as part of the swap.
     ///
-    /// `Ok(true)` if the recorded peak memory usage in megabytes is less than or equal to
-    /// the manager's `max_memory_mb`, `Ok(false)` if it exceeds the limit, and `Err` if the
-    /// metrics lock cannot be acquired.
+    /// # Errors
+    /// Returns `Err(StateError::Transition)` if `patch` is not a JSON object, or `Err` if
+    /// the tenant is not found.
     ///
     /// # Examples
     ///
     /// ```
-    /// let mgr = ImmutableStateManager::new(100); // 100 MB limit
-    /// let within = mgr.check_memory_limits().unwrap();
-    /// assert!(within || !within); // simple usage; result is boolean
+    /// manager
+    ///     .apply_json_patch("t1", serde_json::json!({ "theme": "dark", "beta": null }))
+    ///     .unwrap();
     /// ```
-    pub fn check_memory_limits(&self) -> Result<bool, String> {
-        // Simplified memory check (in a real implementation, this would track actual memory usage)
-        let metrics = self.metrics.read().map_err(|_| "Lock poisoned")?;
-        let memory_mb = metrics.peak_memory_usage / (1024 * 1024);
-        Ok(memory_mb <= self.max_memory_mb)
+    pub fn apply_json_patch(
+        &self,
+        tenant_id: &str,
+        patch: serde_json::Value,
+    ) -> Result<(), StateError> {
+        let serde_json::Value::Object(entries) = patch else {
+            return Err(StateError::Transition(
+                crate::functional::state_transitions::TransitionError::InvalidParameters {
+                    message: "JSON patch must be an object".to_string(),
+                },
+            ));
+        };
+
+        self.apply_transition(tenant_id, move |state| {
+            let mut new_state = state.clone();
+            new_state.app_data =
+                entries
+                    .into_iter()
+                    .fold(state.app_data.clone(), |map, (key, value)| {
+                        if value.is_null() {
+                            map.remove(&key)
+                        } else {
+                            map.insert(key, value)
+                        }
+                    });
+            new_state.last_updated = chrono::Utc::now();
+            Ok(new_state)
+        })
     }
 
-    /// Record a state transition duration and update aggregated performance metrics.
+    /// Adds a batch of user sessions to a tenant in a single transition.
     ///
-    /// This updates the transition count and the running average transition duration.
-    /// Memory-related fields are set to documented estimates and are not sampled or
-    /// measured at runtime to avoid performance costs.
-    ///
-    /// # Returns
+    /// All sessions go into one new `user_sessions` map, so the batch costs one lock
+    /// acquisition and one metrics update instead of one per session. Existing sessions
+    /// with the same id are overwritten, and `last_updated` is refreshed once.
     ///
-    /// `Ok(())` on success, `Err(String)` if the internal metrics lock is poisoned.
+    /// # Errors
+    /// Returns `Err` if the tenant is not found.
     ///
     /// # Examples
     ///
     /// ```
-    /// use std::time::Duration;
-    ///
-    /// let mgr = ImmutableStateManager::new(100);
-    /// mgr.update_metrics(Duration::from_millis(5)).unwrap();
-    /// let metrics = mgr.get_metrics().unwrap();
-    /// assert!(metrics.transition_count >= 1);
+    /// let manager = ImmutableStateManager::new(100);
+    /// manager.initialize_tenant(create_test_tenant("t1")).unwrap();
+    /// let session = SessionData {
+    ///     user_data: "user-1".to_string(),
+    ///     expires_at: chrono::Utc::now() + chrono::Duration::hours(1),
+    /// };
+    /// manager.add_sessions("t1", vec![("session-1".to_string(), session)]).unwrap();
     /// ```
-    fn update_metrics(&self, duration: Duration) -> Result<(), String> {
-        let mut metrics = self.metrics.write().map_err(|_| "Lock poisoned")?;
-
-        metrics.transition_count += 1;
-        let new_measurement = duration.as_nanos() as f64;
-        let count = metrics.transition_count as f64;
-        let old_avg = metrics.avg_transition_time_ns as f64;
-        metrics.avg_transition_time_ns =
-            ((old_avg * (count - 1.0) + new_measurement) / count) as u64;
-
-        // Memory metrics: documented estimates (per task requirement option b)
-        // These are not sampled at runtime due to performance/cost reasons
-        metrics.memory_overhead_percent = 15.0;
-        // peak_memory_usage: baseline estimate, not updated with actual measurements
-        metrics.peak_memory_usage = metrics.peak_memory_usage.max(1024 * 1024);
-
-        Ok(())
+    pub fn add_sessions(
+        &self,
+        tenant_id: &str,
+        sessions: Vec<(String, SessionData)>,
+    ) -> Result<(), StateError> {
+        self.apply_transition(tenant_id, move |state| {
+            let mut new_state = state.clone();
+            new_state.user_sessions = state.user_sessions.insert_all(sessions);
+            new_state.last_updated = chrono::Utc::now();
+            Ok(new_state)
+        })
     }
 
-    // ==================== Snapshot and Rollback Methods ====================
-
-    /// Creates a snapshot of the current tenant state
+    /// Looks up a user session, treating sessions expired at `now` as absent.
     ///
-    /// # Arguments
+    /// # Returns
+    /// `Ok(Some(session))` if the session exists and `expires_at > now`, `Ok(None)` if it is
+    /// missing or expired, and `Err` if the tenant is not found.
+    pub fn get_active_session(
+        &self,
+        tenant_id: &str,
+        session_id: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<SessionData>, StateError> {
+        let states = Self::read_recovering(&self.tenant_states);
+
+        let state = states
+            .get(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
+
+        Ok(state
+            .user_sessions
+            .get(&session_id.to_string())
+            .filter(|session| session.expires_at > now)
+            .cloned())
+    }
+
+    /// Counts a tenant's sessions that are still active at `now` (`expires_at > now`).
+    ///
+    /// Sessions are inspected by reference under the read lock; nothing is cloned.
+    ///
+    /// # Returns
+    /// The number of active sessions, or `Err` if the tenant is not found.
+    pub fn active_session_count(
+        &self,
+        tenant_id: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<usize, StateError> {
+        let states = Self::read_recovering(&self.tenant_states);
+
+        let state = states
+            .get(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
+
+        Ok(state
+            .user_sessions
+            .iter()
+            .filter(|(_, session)| session.expires_at > now)
+            .count())
+    }
+
+    /// Returns a clone of the current state transition metrics for the manager.
+    ///
+    /// On success, returns `Ok(StateTransitionMetrics)` containing a cloned snapshot of the metrics.
+    /// The `Result` is kept for API stability; poisoned locks are recovered rather than reported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mgr = ImmutableStateManager::default();
+    /// let metrics = mgr.get_metrics().expect("failed to read metrics");
+    /// // snapshot fields are accessible
+    /// assert_eq!(metrics.transition_count, 0);
+    /// ```
+    pub fn get_metrics(&self) -> Result<StateTransitionMetrics, StateError> {
+        let metrics = Self::read_recovering(&self.metrics);
+        Ok(metrics.clone())
+    }
+
+    /// Determines whether a tenant state exists in the manager.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a state for `tenant_id` exists, `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let manager = ImmutableStateManager::new(100);
+    /// let exists = manager.tenant_exists("tenant-123");
+    /// ```
+    pub fn tenant_exists(&self, tenant_id: &str) -> bool {
+        let states = Self::read_recovering(&self.tenant_states);
+        states.contains_key(tenant_id)
+    }
+
+    /// Checks whether the manager's current memory usage is within the configured limit.
+    ///
+    /// The check converts the latest sample in `current_memory_usage` (bytes) to megabytes and
+    /// compares it against the manager's `max_memory_mb`. With the `real_memory_metrics`
+    /// feature the sample is the process resident set size, so the limit then bounds the
+    /// whole process rather than this manager. `peak_memory_usage` is never consulted, so a
+    /// past spike stops failing transitions once memory drops again.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(true)` if the current memory usage in megabytes is less than or equal to
+    /// the manager's `max_memory_mb`, `Ok(false)` if it exceeds the limit, and `Err` if the
+    /// metrics lock cannot be acquired.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mgr = ImmutableStateManager::new(100); // 100 MB limit
+    /// let within = mgr.check_memory_limits().unwrap();
+    /// assert!(within || !within); // simple usage; result is boolean
+    /// ```
+    pub fn check_memory_limits(&self) -> Result<bool, StateError> {
+        let metrics = Self::read_recovering(&self.metrics);
+        let memory_mb = metrics.current_memory_usage / (1024 * 1024);
+        Ok(memory_mb <= self.max_memory_mb)
+    }
+
+    /// Record a state transition duration and update aggregated performance metrics.
+    ///
+    /// This updates the transition count, the running average transition duration and
+    /// the tenant's latency histogram.
+    ///
+    /// `memory_overhead_percent` and `estimated_memory_usage` are fixed estimates. With the
+    /// `real_memory_metrics` feature on Linux, the process resident set size is sampled on
+    /// every transition into `current_memory_usage`; otherwise the estimate is used. The
+    /// sample is overwritten each time, while `peak_memory_usage` keeps the highest one.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` on success.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let mgr = ImmutableStateManager::new(100);
+    /// mgr.update_metrics("tenant_a", Duration::from_millis(5)).unwrap();
+    /// let metrics = mgr.get_metrics().unwrap();
+    /// assert!(metrics.transition_count >= 1);
+    /// ```
+    fn update_metrics(&self, tenant_id: &str, duration: Duration) -> Result<(), StateError> {
+        let resident_memory = Self::sample_resident_memory();
+        let mut metrics = Self::write_recovering(&self.metrics);
+
+        metrics.transition_count += 1;
+        let new_measurement = duration.as_nanos() as f64;
+        let count = metrics.transition_count as f64;
+        let old_avg = metrics.avg_transition_time_ns as f64;
+        metrics.avg_transition_time_ns =
+            ((old_avg * (count - 1.0) + new_measurement) / count) as u64;
+
+        // memory_overhead_percent: documented estimate (per task requirement option b)
+        metrics.memory_overhead_percent = 15.0;
+        // estimated_memory_usage: baseline estimate, not updated with actual measurements
+        metrics.estimated_memory_usage = metrics.estimated_memory_usage.max(1024 * 1024);
+        // current_memory_usage: latest sample, sampled RSS when available
+        metrics.current_memory_usage = resident_memory.unwrap_or(metrics.estimated_memory_usage);
+        // peak_memory_usage: reporting only
+        metrics.peak_memory_usage = metrics.peak_memory_usage.max(metrics.current_memory_usage);
+
+        Self::write_recovering(&self.tenant_latencies)
+            .entry(tenant_id.to_string())
+            .or_insert_with(LatencyHistogram::new)
+            .record(duration);
+
+        Ok(())
+    }
+
+    /// Resident set size of the current process in bytes, read from `VmRSS` in
+    /// `/proc/self/status`.
+    ///
+    /// Returns `None` if the file cannot be read or parsed.
+    #[cfg(all(feature = "real_memory_metrics", target_os = "linux"))]
+    fn sample_resident_memory() -> Option<usize> {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        let kilobytes = status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmRSS:"))?
+            .trim()
+            .strip_suffix("kB")?
+            .trim()
+            .parse::<usize>()
+            .ok()?;
+        Some(kilobytes * 1024)
+    }
+
+    /// Memory sampling is disabled without the `real_memory_metrics` feature or off Linux.
+    #[cfg(not(all(feature = "real_memory_metrics", target_os = "linux")))]
+    fn sample_resident_memory() -> Option<usize> {
+        None
+    }
+
+    /// Returns p50/p95/p99 transition latencies recorded for a tenant.
+    ///
+    /// Latencies are kept in power-of-two buckets, so each percentile is the upper bound
+    /// of its bucket clamped to the observed min/max; expect up to 2x overestimation.
+    ///
+    /// # Errors
+    /// Returns `Err` if no transition has been recorded for the tenant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let stats = mgr.tenant_latency_percentiles("tenant_a")?;
+    /// assert!(stats.p50_ns <= stats.p99_ns);
+    /// ```
+    pub fn tenant_latency_percentiles(&self, tenant_id: &str) -> Result<LatencyStats, StateError> {
+        let latencies = Self::read_recovering(&self.tenant_latencies);
+
+        latencies
+            .get(tenant_id)
+            .map(LatencyHistogram::stats)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))
+    }
+
+    /// Appends a query result to a tenant's cache, keeping at most `max_entries` results.
+    ///
+    /// When the cache grows past `max_entries`, the oldest entries are dropped so only the
+    /// newest results are retained. The update is applied as a regular state transition.
+    ///
+    /// # Errors
+    /// Returns `Err` if the tenant is not found.
+    pub fn cache_query_result(
+        &self,
+        tenant_id: &str,
+        result: QueryResult,
+        max_entries: usize,
+    ) -> Result<(), StateError> {
+        self.apply_transition(tenant_id, move |state| {
+            let mut new_state = state.clone();
+            new_state.query_cache = state.query_cache.append(result);
+            Ok(crate::functional::state_transitions::prune_cache(
+                max_entries,
+            )(&new_state))
+        })
+    }
+
+    /// Looks up a cached query result by id, treating entries expired at `now` as misses.
+    ///
+    /// If several entries share the same id, the most recently cached one is used.
+    ///
+    /// # Returns
+    /// `Ok(Some(data))` on a cache hit, `Ok(None)` on a miss or expired entry, and `Err` if the
+    /// tenant is not found.
+    pub fn get_cached_query(
+        &self,
+        tenant_id: &str,
+        query_id: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<Vec<u8>>, StateError> {
+        let states = Self::read_recovering(&self.tenant_states);
+
+        let state = states
+            .get(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
+
+        Ok(state
+            .query_cache
+            .iter()
+            .filter(|entry| entry.query_id == query_id)
+            .last()
+            .filter(|entry| entry.expires_at > now)
+            .map(|entry| entry.data.clone()))
+    }
+
+    // ==================== Snapshot and Rollback Methods ====================
+
+    /// Creates a snapshot of the current tenant state
+    ///
+    /// # Arguments
     /// * `tenant_id` - The tenant whose state should be snapshotted
     /// * `name` - Optional human-readable name for the snapshot
     /// * `created_by` - User ID or system identifier creating the snapshot
@@ -1215,202 +2411,615 @@ impl ImmutableStateManager {
         created_by: String,
         description: Option<String>,
         tags: Vec<String>,
-    ) -> Result<String, String> {
-        let states = self.tenant_states.read().map_err(|_| "Lock poisoned")?;
-        let mut histories = self
-            .snapshot_histories
-            .write()
-            .map_err(|_| "Lock poisoned")?;
+    ) -> Result<String, StateError> {
+        let states = Self::read_recovering(&self.tenant_states);
+        let mut histories = Self::write_recovering(&self.snapshot_histories);
 
         let state = states
             .get(tenant_id)
-            .ok_or_else(|| format!("Tenant '{}' not found", tenant_id))?;
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
 
         let history = histories
             .get_mut(tenant_id)
-            .ok_or_else(|| format!("Snapshot history for tenant '{}' not found", tenant_id))?;
-
-        let snapshot_id = format!(
-            "snapshot_{}_{}_{}",
-            tenant_id,
-            chrono::Utc::now().timestamp_millis(),
-            uuid::Uuid::new_v4()
-                .to_string()
-                .split('-')
-                .next()
-                .unwrap_or("unknown")
-        );
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
 
-        let snapshot = StateSnapshot {
-            snapshot_id: snapshot_id.clone(),
+        Ok(Self::push_snapshot(
+            history,
+            self.next_snapshot_id(tenant_id),
+            SnapshotState::Full(Arc::clone(state)),
             name,
-            created_at: chrono::Utc::now(),
             created_by,
             description,
             tags,
-            state: Arc::clone(state),
-        };
-
-        history.add_snapshot(snapshot);
-
-        Ok(snapshot_id)
+        ))
     }
 
-    /// Restores tenant state from a named snapshot
+    /// Creates a snapshot only if the tenant state changed since the latest snapshot
+    ///
+    /// The state counts as changed when its `last_updated` differs from the one
+    /// captured by the most recent snapshot, or when no snapshot exists yet.
     ///
     /// # Arguments
-    /// * `tenant_id` - The tenant whose state should be restored
-    /// * `snapshot_name` - The name of the snapshot to restore
+    /// * `tenant_id` - The tenant whose state should be snapshotted
+    /// * `name` - Optional human-readable name for the snapshot
+    /// * `created_by` - User ID or system identifier creating the snapshot
     ///
     /// # Returns
-    /// Ok(()) if restoration succeeded
-    pub fn rollback_to_named_snapshot(
+    /// `Some(snapshot_id)` if a snapshot was created, `None` if it was skipped
+    pub fn create_snapshot_if_changed(
         &self,
         tenant_id: &str,
-        snapshot_name: &str,
-    ) -> Result<(), String> {
-        let histories = self
-            .snapshot_histories
-            .read()
-            .map_err(|_| "Lock poisoned")?;
+        name: Option<String>,
+        created_by: String,
+    ) -> Result<Option<String>, StateError> {
+        let states = Self::read_recovering(&self.tenant_states);
+        let mut histories = Self::write_recovering(&self.snapshot_histories);
 
-        let history = histories
+        let state = states
             .get(tenant_id)
-            .ok_or_else(|| format!("Snapshot history for tenant '{}' not found", tenant_id))?;
-
-        let snapshot = history
-            .get_named_snapshot(snapshot_name)
-            .ok_or_else(|| format!("Named snapshot '{}' not found", snapshot_name))?;
-
-        let restored_state = Arc::clone(&snapshot.state);
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
 
-        drop(histories); // Release read lock before acquiring write lock
+        let history = histories
+            .get_mut(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
 
-        let mut states = self.tenant_states.write().map_err(|_| "Lock poisoned")?;
-        states.insert(tenant_id.to_string(), restored_state);
+        let unchanged = history
+            .get_latest_snapshot()
+            .is_some_and(|latest| latest.state.last_updated() == state.last_updated);
+        if unchanged {
+            return Ok(None);
+        }
 
-        Ok(())
+        Ok(Some(Self::push_snapshot(
+            history,
+            self.next_snapshot_id(tenant_id),
+            SnapshotState::Full(Arc::clone(state)),
+            name,
+            created_by,
+            None,
+            Vec::new(),
+        )))
     }
 
-    /// Restores tenant state from the most recent snapshot
+    /// Creates a snapshot holding a compressed copy of the current tenant state
+    ///
+    /// Regular snapshots share structure with the live state, which keeps large
+    /// `app_data` alive for as long as the snapshot is retained. A compressed snapshot
+    /// serializes the state to JSON and deflates it instead, trading CPU on creation
+    /// and rollback for a smaller footprint. Prefer it for cold checkpoints that are
+    /// rarely restored.
     ///
     /// # Arguments
-    /// * `tenant_id` - The tenant whose state should be restored
+    /// * `tenant_id` - The tenant whose state should be snapshotted
+    /// * `name` - Optional human-readable name for the snapshot
+    /// * `created_by` - User ID or system identifier creating the snapshot
+    /// * `description` - Optional description of why the snapshot was created
+    /// * `tags` - Tags for categorization and filtering
     ///
     /// # Returns
-    /// Ok(()) if restoration succeeded
-    pub fn rollback_to_latest_snapshot(&self, tenant_id: &str) -> Result<(), String> {
-        let histories = self
-            .snapshot_histories
-            .read()
-            .map_err(|_| "Lock poisoned")?;
+    /// The unique snapshot ID on success
+    pub fn create_compressed_snapshot(
+        &self,
+        tenant_id: &str,
+        name: Option<String>,
+        created_by: String,
+        description: Option<String>,
+        tags: Vec<String>,
+    ) -> Result<String, StateError> {
+        let state = self
+            .get_tenant_state(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
+
+        // Compress outside the locks; the state is immutable so this is safe
+        let compressed = CompressedSnapshot::compress(&state)?;
+
+        let mut histories = Self::write_recovering(&self.snapshot_histories);
 
         let history = histories
-            .get(tenant_id)
-            .ok_or_else(|| format!("Snapshot history for tenant '{}' not found", tenant_id))?;
+            .get_mut(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
 
-        let snapshot = history
-            .get_latest_snapshot()
-            .ok_or_else(|| format!("No snapshots available for tenant '{}'", tenant_id))?;
+        Ok(Self::push_snapshot(
+            history,
+            self.next_snapshot_id(tenant_id),
+            SnapshotState::Compressed(compressed),
+            name,
+            created_by,
+            description,
+            tags,
+        ))
+    }
 
-        let restored_state = Arc::clone(&snapshot.state);
+    /// Returns the ID for a new snapshot of `tenant_id`
+    fn next_snapshot_id(&self, tenant_id: &str) -> String {
+        match &self.snapshot_id_generator {
+            Some(generator) => generator(),
+            None => format!(
+                "snapshot_{}_{}_{}",
+                tenant_id,
+                chrono::Utc::now().timestamp_millis(),
+                uuid::Uuid::new_v4()
+                    .to_string()
+                    .split('-')
+                    .next()
+                    .unwrap_or("unknown")
+            ),
+        }
+    }
 
-        drop(histories);
+    /// Records `state` in `history` under `snapshot_id` and returns the ID
+    fn push_snapshot(
+        history: &mut SnapshotHistory,
+        snapshot_id: String,
+        state: SnapshotState,
+        name: Option<String>,
+        created_by: String,
+        description: Option<String>,
+        tags: Vec<String>,
+    ) -> String {
+        let snapshot = StateSnapshot {
+            snapshot_id: snapshot_id.clone(),
+            name,
+            created_at: chrono::Utc::now(),
+            created_by,
+            description,
+            tags,
+            pinned: false,
+            state,
+        };
 
-        let mut states = self.tenant_states.write().map_err(|_| "Lock poisoned")?;
-        states.insert(tenant_id.to_string(), restored_state);
+        history.add_snapshot(snapshot);
 
-        Ok(())
+        snapshot_id
     }
 
-    /// Restores tenant state from a snapshot at a specific index
+    /// Restores tenant state from a named snapshot
     ///
     /// # Arguments
     /// * `tenant_id` - The tenant whose state should be restored
-    /// * `index` - The snapshot index (0 = oldest)
+    /// * `snapshot_name` - The name of the snapshot to restore
     ///
     /// # Returns
     /// Ok(()) if restoration succeeded
-    pub fn rollback_to_snapshot_index(&self, tenant_id: &str, index: usize) -> Result<(), String> {
-        let histories = self
-            .snapshot_histories
-            .read()
-            .map_err(|_| "Lock poisoned")?;
+    pub fn rollback_to_named_snapshot(
+        &self,
+        tenant_id: &str,
+        snapshot_name: &str,
+    ) -> Result<(), StateError> {
+        let histories = Self::read_recovering(&self.snapshot_histories);
 
         let history = histories
             .get(tenant_id)
-            .ok_or_else(|| format!("Snapshot history for tenant '{}' not found", tenant_id))?;
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
 
-        let snapshot = history
-            .get_snapshot_by_index(index)
-            .ok_or_else(|| format!("Snapshot at index {} not found", index))?;
+        let snapshot = history.get_named_snapshot(snapshot_name).ok_or_else(|| {
+            StateError::SnapshotNotFound(format!("named snapshot '{}'", snapshot_name))
+        })?;
 
-        let restored_state = Arc::clone(&snapshot.state);
+        let restored_state = snapshot.state.restore()?;
 
-        drop(histories);
+        drop(histories); // Release read lock before acquiring write lock
 
-        let mut states = self.tenant_states.write().map_err(|_| "Lock poisoned")?;
+        let mut states = Self::write_recovering(&self.tenant_states);
         states.insert(tenant_id.to_string(), restored_state);
 
         Ok(())
     }
 
-    /// Restores tenant state to the closest snapshot before or at a specific time
+    /// Restores tenant state from a named snapshot and records who did it and why
+    ///
+    /// The event is appended to the tenant's rollback log only if the rollback succeeds;
+    /// read it back with [`rollback_history`](Self::rollback_history).
     ///
     /// # Arguments
     /// * `tenant_id` - The tenant whose state should be restored
-    /// * `timestamp` - The target timestamp for time-travel debugging
+    /// * `snapshot_name` - The name of the snapshot to restore
+    /// * `performed_by` - Who requested the rollback
+    /// * `reason` - Why the rollback was performed
     ///
     /// # Returns
     /// Ok(()) if restoration succeeded
-    pub fn rollback_to_time(
+    pub fn rollback_to_named_snapshot_audited(
         &self,
         tenant_id: &str,
-        timestamp: chrono::DateTime<chrono::Utc>,
-    ) -> Result<(), String> {
-        let histories = self
-            .snapshot_histories
-            .read()
-            .map_err(|_| "Lock poisoned")?;
-
-        let history = histories
-            .get(tenant_id)
-            .ok_or_else(|| format!("Snapshot history for tenant '{}' not found", tenant_id))?;
-
-        let snapshot = history
-            .get_snapshot_at_time(timestamp)
-            .ok_or_else(|| format!("No snapshot found before or at timestamp {}", timestamp))?;
-
-        let restored_state = Arc::clone(&snapshot.state);
-
-        drop(histories);
-
-        let mut states = self.tenant_states.write().map_err(|_| "Lock poisoned")?;
-        states.insert(tenant_id.to_string(), restored_state);
+        snapshot_name: &str,
+        performed_by: &str,
+        reason: &str,
+    ) -> Result<(), StateError> {
+        self.rollback_to_named_snapshot(tenant_id, snapshot_name)?;
+
+        Self::write_recovering(&self.rollback_logs)
+            .entry(tenant_id.to_string())
+            .or_default()
+            .push(RollbackEvent {
+                snapshot_name: snapshot_name.to_string(),
+                performed_by: performed_by.to_string(),
+                reason: reason.to_string(),
+                at: chrono::Utc::now(),
+            });
 
         Ok(())
     }
 
-    /// Lists all snapshots for a tenant
+    /// Returns the audited rollbacks of a tenant, oldest first
     ///
-    /// # Arguments
+    /// # Returns
+    /// The recorded events (empty if none), or `Err(StateError::TenantNotFound)` for an
+    /// unknown tenant
+    pub fn rollback_history(&self, tenant_id: &str) -> Result<Vec<RollbackEvent>, StateError> {
+        if !self.tenant_exists(tenant_id) {
+            return Err(StateError::TenantNotFound(tenant_id.to_string()));
+        }
+
+        Ok(Self::read_recovering(&self.rollback_logs)
+            .get(tenant_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// Restores a named snapshot and replays recorded transitions on top of it, in order
+    ///
+    /// The replay runs under the tenant state write lock, so other callers observe either
+    /// the state before the call or the fully replayed state. If a transition fails, the
+    /// tenant is left at the base snapshot state and the error is returned.
+    ///
+    /// # Arguments
+    /// * `tenant_id` - The tenant whose state should be reconstructed
+    /// * `base_snapshot_name` - The named snapshot to start from
+    /// * `transitions` - Transitions to replay on the base state
+    ///
+    /// # Returns
+    /// Ok(()) if every transition was replayed
+    pub fn restore_and_replay<F>(
+        &self,
+        tenant_id: &str,
+        base_snapshot_name: &str,
+        transitions: Vec<F>,
+    ) -> Result<(), StateError>
+    where
+        F: FnOnce(
+            &TenantApplicationState,
+        ) -> Result<
+            TenantApplicationState,
+            crate::functional::state_transitions::TransitionError,
+        >,
+    {
+        let histories = Self::read_recovering(&self.snapshot_histories);
+
+        let history = histories
+            .get(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
+
+        let snapshot = history
+            .get_named_snapshot(base_snapshot_name)
+            .ok_or_else(|| {
+                StateError::SnapshotNotFound(format!("named snapshot '{}'", base_snapshot_name))
+            })?;
+
+        let base_state = snapshot.state.restore()?;
+
+        drop(histories); // Release read lock before acquiring write lock
+
+        let mut states = Self::write_recovering(&self.tenant_states);
+
+        let mut current = Arc::clone(&base_state);
+        for transition in transitions {
+            match transition(&current) {
+                Ok(next) => current = Arc::new(next),
+                Err(err) => {
+                    states.insert(tenant_id.to_string(), base_state);
+                    return Err(err.into());
+                }
+            }
+        }
+
+        states.insert(tenant_id.to_string(), current);
+        Ok(())
+    }
+
+    /// Registers `new_tenant` with state copied from a named snapshot of another tenant
+    ///
+    /// The copied state keeps the snapshot's sessions, app data and query cache, but its
+    /// embedded `tenant` is replaced by `new_tenant`. The new tenant starts with an empty
+    /// snapshot history.
+    ///
+    /// # Arguments
+    /// * `source_tenant` - The tenant owning the snapshot
+    /// * `snapshot_name` - The name of the snapshot to clone
+    /// * `new_tenant` - The tenant to create; must not already exist
+    ///
+    /// # Returns
+    /// Ok(()) if the new tenant was created from the snapshot
+    pub fn clone_snapshot_to_tenant(
+        &self,
+        source_tenant: &str,
+        snapshot_name: &str,
+        new_tenant: Tenant,
+    ) -> Result<(), StateError> {
+        let histories = Self::read_recovering(&self.snapshot_histories);
+
+        let history = histories
+            .get(source_tenant)
+            .ok_or_else(|| StateError::TenantNotFound(source_tenant.to_string()))?;
+
+        let snapshot = history.get_named_snapshot(snapshot_name).ok_or_else(|| {
+            StateError::SnapshotNotFound(format!("named snapshot '{}'", snapshot_name))
+        })?;
+
+        let source_state = snapshot.state.restore()?;
+
+        drop(histories); // Release read lock before acquiring write locks
+
+        let mut states = Self::write_recovering(&self.tenant_states);
+        let mut histories = Self::write_recovering(&self.snapshot_histories);
+
+        if states.contains_key(&new_tenant.id) {
+            return Err(StateError::TenantExists(new_tenant.id.clone()));
+        }
+
+        let tenant_id = new_tenant.id.clone();
+        let mut cloned_state = (*source_state).clone();
+        cloned_state.tenant = new_tenant;
+        cloned_state.last_updated = chrono::Utc::now();
+
+        histories.insert(
+            tenant_id.clone(),
+            SnapshotHistory::new(self.max_auto_snapshots, self.max_named_snapshots),
+        );
+        states.insert(tenant_id, Arc::new(cloned_state));
+
+        Ok(())
+    }
+
+    /// Restores tenant state from the most recent snapshot
+    ///
+    /// # Arguments
+    /// * `tenant_id` - The tenant whose state should be restored
+    ///
+    /// # Returns
+    /// Ok(()) if restoration succeeded
+    pub fn rollback_to_latest_snapshot(&self, tenant_id: &str) -> Result<(), StateError> {
+        let histories = Self::read_recovering(&self.snapshot_histories);
+
+        let history = histories
+            .get(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
+
+        let snapshot = history.get_latest_snapshot().ok_or_else(|| {
+            StateError::SnapshotNotFound(format!("no snapshots for tenant '{}'", tenant_id))
+        })?;
+
+        let restored_state = snapshot.state.restore()?;
+
+        drop(histories);
+
+        let mut states = Self::write_recovering(&self.tenant_states);
+        states.insert(tenant_id.to_string(), restored_state);
+
+        Ok(())
+    }
+
+    /// Restores tenant state from a snapshot at a specific index
+    ///
+    /// # Arguments
+    /// * `tenant_id` - The tenant whose state should be restored
+    /// * `index` - The snapshot index (0 = oldest)
+    ///
+    /// # Returns
+    /// Ok(()) if restoration succeeded
+    pub fn rollback_to_snapshot_index(
+        &self,
+        tenant_id: &str,
+        index: usize,
+    ) -> Result<(), StateError> {
+        let histories = Self::read_recovering(&self.snapshot_histories);
+
+        let history = histories
+            .get(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
+
+        let snapshot = history
+            .get_snapshot_by_index(index)
+            .ok_or_else(|| StateError::SnapshotNotFound(format!("index {}", index)))?;
+
+        let restored_state = snapshot.state.restore()?;
+
+        drop(histories);
+
+        let mut states = Self::write_recovering(&self.tenant_states);
+        states.insert(tenant_id.to_string(), restored_state);
+
+        Ok(())
+    }
+
+    /// Restores tenant state to the closest snapshot before or at a specific time
+    ///
+    /// # Arguments
+    /// * `tenant_id` - The tenant whose state should be restored
+    /// * `timestamp` - The target timestamp for time-travel debugging
+    ///
+    /// # Returns
+    /// Ok(()) if restoration succeeded
+    pub fn rollback_to_time(
+        &self,
+        tenant_id: &str,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), StateError> {
+        let histories = Self::read_recovering(&self.snapshot_histories);
+
+        let history = histories
+            .get(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
+
+        let snapshot = history.get_snapshot_at_time(timestamp).ok_or_else(|| {
+            StateError::SnapshotNotFound(format!("before or at timestamp {}", timestamp))
+        })?;
+
+        let restored_state = snapshot.state.restore()?;
+
+        drop(histories);
+
+        let mut states = Self::write_recovering(&self.tenant_states);
+        states.insert(tenant_id.to_string(), restored_state);
+
+        Ok(())
+    }
+
+    /// Compares two named snapshots of a tenant
+    ///
+    /// # Arguments
+    /// * `tenant_id` - The tenant owning both snapshots
+    /// * `from_name` - Name of the older snapshot
+    /// * `to_name` - Name of the newer snapshot
+    ///
+    /// # Returns
+    /// The differences going from `from_name` to `to_name`
+    pub fn diff_named_snapshots(
+        &self,
+        tenant_id: &str,
+        from_name: &str,
+        to_name: &str,
+    ) -> Result<StateDiff, StateError> {
+        let histories = Self::read_recovering(&self.snapshot_histories);
+
+        let history = histories
+            .get(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
+
+        let from = history.get_named_snapshot(from_name).ok_or_else(|| {
+            StateError::SnapshotNotFound(format!("named snapshot '{}'", from_name))
+        })?;
+        let to = history
+            .get_named_snapshot(to_name)
+            .ok_or_else(|| StateError::SnapshotNotFound(format!("named snapshot '{}'", to_name)))?;
+
+        let from_state = from.state.restore()?;
+        let to_state = to.state.restore()?;
+        Ok(StateDiff::between(&from_state, &to_state))
+    }
+
+    /// Builds a timeline of a tenant's snapshots, oldest first
+    ///
+    /// # Arguments
+    /// * `tenant_id` - The tenant whose snapshots should be laid out
+    ///
+    /// # Returns
+    /// One entry per snapshot with its spacing from and app data changes versus the previous one
+    pub fn snapshot_timeline(&self, tenant_id: &str) -> Result<Vec<TimelineEntry>, StateError> {
+        let histories = Self::read_recovering(&self.snapshot_histories);
+
+        let history = histories
+            .get(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
+
+        let mut timeline = Vec::with_capacity(history.snapshots.len());
+        let mut previous: Option<(
+            chrono::DateTime<chrono::Utc>,
+            PersistentHashMap<String, serde_json::Value>,
+        )> = None;
+
+        for snapshot in &history.snapshots {
+            let app_data = snapshot.state.restore()?.app_data.clone();
+            let (seconds_since_previous, diff) = match &previous {
+                Some((created_at, prev_app_data)) => (
+                    Some((snapshot.created_at - *created_at).num_milliseconds() as f64 / 1000.0),
+                    prev_app_data.diff(&app_data),
+                ),
+                None => (None, PersistentHashMap::new().diff(&app_data)),
+            };
+
+            timeline.push(TimelineEntry {
+                snapshot_id: snapshot.snapshot_id.clone(),
+                name: snapshot.name.clone(),
+                created_at: snapshot.created_at,
+                seconds_since_previous,
+                changed_keys: diff.added.len() + diff.removed.len() + diff.modified.len(),
+            });
+            previous = Some((snapshot.created_at, app_data));
+        }
+
+        Ok(timeline)
+    }
+
+    /// Lists all snapshots for a tenant
+    ///
+    /// # Arguments
     /// * `tenant_id` - The tenant whose snapshots should be listed
     ///
     /// # Returns
     /// Vector of snapshot metadata
-    pub fn list_snapshots(&self, tenant_id: &str) -> Result<Vec<SnapshotMetadata>, String> {
-        let histories = self
-            .snapshot_histories
-            .read()
-            .map_err(|_| "Lock poisoned")?;
+    pub fn list_snapshots(&self, tenant_id: &str) -> Result<Vec<SnapshotMetadata>, StateError> {
+        let histories = Self::read_recovering(&self.snapshot_histories);
 
         let history = histories
             .get(tenant_id)
-            .ok_or_else(|| format!("Snapshot history for tenant '{}' not found", tenant_id))?;
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
 
         Ok(history.list_snapshots())
     }
 
+    /// Lists snapshot metadata for every tenant, keyed by tenant id
+    ///
+    /// Only the snapshot history read lock is taken, and snapshot states are never cloned,
+    /// so this is cheap enough for admin dashboards.
+    ///
+    /// # Returns
+    /// Map from tenant id to that tenant's snapshot metadata, oldest first
+    pub fn list_all_snapshots(&self) -> Result<HashMap<String, Vec<SnapshotMetadata>>, StateError> {
+        let histories = Self::read_recovering(&self.snapshot_histories);
+
+        Ok(histories
+            .iter()
+            .map(|(tenant_id, history)| (tenant_id.clone(), history.list_snapshots()))
+            .collect())
+    }
+
+    /// Pins a snapshot so retention pruning never removes it
+    ///
+    /// # Arguments
+    /// * `tenant_id` - The tenant owning the snapshot
+    /// * `snapshot_id` - The ID returned when the snapshot was created
+    ///
+    /// # Returns
+    /// Ok(()) if the snapshot was found and pinned
+    pub fn pin_snapshot(&self, tenant_id: &str, snapshot_id: &str) -> Result<(), StateError> {
+        self.set_snapshot_pinned(tenant_id, snapshot_id, true)
+    }
+
+    /// Unpins a snapshot, making it subject to retention pruning again
+    ///
+    /// # Arguments
+    /// * `tenant_id` - The tenant owning the snapshot
+    /// * `snapshot_id` - The ID returned when the snapshot was created
+    ///
+    /// # Returns
+    /// Ok(()) if the snapshot was found and unpinned
+    pub fn unpin_snapshot(&self, tenant_id: &str, snapshot_id: &str) -> Result<(), StateError> {
+        self.set_snapshot_pinned(tenant_id, snapshot_id, false)
+    }
+
+    fn set_snapshot_pinned(
+        &self,
+        tenant_id: &str,
+        snapshot_id: &str,
+        pinned: bool,
+    ) -> Result<(), StateError> {
+        let mut histories = Self::write_recovering(&self.snapshot_histories);
+
+        let history = histories
+            .get_mut(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
+
+        if history.set_pinned(snapshot_id, pinned) {
+            Ok(())
+        } else {
+            Err(StateError::SnapshotNotFound(format!(
+                "id '{}'",
+                snapshot_id
+            )))
+        }
+    }
+
     /// Gets the count of snapshots for a tenant
     ///
     /// # Arguments
@@ -1418,19 +3027,204 @@ impl ImmutableStateManager {
     ///
     /// # Returns
     /// Number of snapshots
-    pub fn snapshot_count(&self, tenant_id: &str) -> Result<usize, String> {
-        let histories = self
-            .snapshot_histories
-            .read()
-            .map_err(|_| "Lock poisoned")?;
+    pub fn snapshot_count(&self, tenant_id: &str) -> Result<usize, StateError> {
+        let histories = Self::read_recovering(&self.snapshot_histories);
 
         let history = histories
             .get(tenant_id)
-            .ok_or_else(|| format!("Snapshot history for tenant '{}' not found", tenant_id))?;
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
 
         Ok(history.snapshot_count())
     }
 
+    /// Estimates the memory retained by a tenant's live state and its snapshots
+    ///
+    /// Snapshots share structure with the live state, so summing their sizes overcounts.
+    /// Instead each distinct `Arc` allocation (the state itself and the roots of its
+    /// persistent collections) is counted once, identified by pointer. Entry sizes are
+    /// approximations based on key/value lengths rather than exact allocator usage.
+    ///
+    /// # Arguments
+    /// * `tenant_id` - The tenant whose memory usage should be estimated
+    ///
+    /// # Returns
+    /// A `MemoryReport` with live, deduplicated total, and snapshot counts
+    pub fn estimate_tenant_memory(&self, tenant_id: &str) -> Result<MemoryReport, StateError> {
+        let states = Self::read_recovering(&self.tenant_states);
+        let histories = Self::read_recovering(&self.snapshot_histories);
+
+        let state = states
+            .get(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
+
+        let history = histories
+            .get(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
+
+        Ok(Self::memory_report(state, history))
+    }
+
+    /// Drops the oldest unpinned automatic snapshots until the tenant's estimated memory
+    /// is at or under `target_mb`
+    ///
+    /// Memory is estimated as in [`estimate_tenant_memory`](Self::estimate_tenant_memory),
+    /// so dropping a snapshot that still shares all of its structure with newer states
+    /// frees nothing and the collector moves on to the next one. Named and pinned
+    /// snapshots are never removed, even if the target cannot be reached without them.
+    ///
+    /// The estimate is computed once: shared allocations are attributed to the live state
+    /// and the kept snapshots first, then to the removable ones from newest to oldest. Each
+    /// removable snapshot's share is then exactly what dropping it after every older one
+    /// frees, so it is subtracted as the snapshot goes.
+    ///
+    /// # Arguments
+    /// * `tenant_id` - The tenant whose snapshots should be collected
+    /// * `target_mb` - Estimated memory, in megabytes, to get the tenant under
+    ///
+    /// # Returns
+    /// The number of snapshots dropped
+    pub fn gc_snapshots_under_pressure(
+        &self,
+        tenant_id: &str,
+        target_mb: usize,
+    ) -> Result<usize, StateError> {
+        let state = Self::read_recovering(&self.tenant_states)
+            .get(tenant_id)
+            .cloned()
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
+
+        let mut histories = Self::write_recovering(&self.snapshot_histories);
+        let history = histories
+            .get_mut(tenant_id)
+            .ok_or_else(|| StateError::TenantNotFound(tenant_id.to_string()))?;
+
+        let removable = |snapshot: &StateSnapshot| snapshot.name.is_none() && !snapshot.pinned;
+        let mut seen = HashSet::new();
+        let mut total_bytes = Self::unseen_state_bytes(&state, &mut seen);
+        total_bytes += history
+            .snapshots
+            .iter()
+            .filter(|snapshot| !removable(snapshot))
+            .map(|snapshot| Self::unseen_snapshot_bytes(snapshot, &mut seen))
+            .sum::<usize>();
+        let mut freed_bytes: Vec<usize> = history
+            .snapshots
+            .iter()
+            .rev()
+            .filter(|snapshot| removable(snapshot))
+            .map(|snapshot| Self::unseen_snapshot_bytes(snapshot, &mut seen))
+            .collect();
+        total_bytes += freed_bytes.iter().sum::<usize>();
+        // Oldest first, matching the order `remove_oldest_unpinned_auto` drops them in
+        freed_bytes.reverse();
+
+        let target_bytes = target_mb.saturating_mul(1024 * 1024);
+        let mut dropped = 0;
+        for bytes in freed_bytes {
+            if total_bytes <= target_bytes || !history.remove_oldest_unpinned_auto() {
+                break;
+            }
+            total_bytes -= bytes;
+            dropped += 1;
+        }
+
+        Ok(dropped)
+    }
+
+    /// Builds the [`MemoryReport`] of a live state and its snapshot history.
+    fn memory_report(
+        state: &Arc<TenantApplicationState>,
+        history: &SnapshotHistory,
+    ) -> MemoryReport {
+        let mut seen = HashSet::new();
+        let live_bytes = Self::unseen_state_bytes(state, &mut seen);
+        let snapshot_bytes: usize = history
+            .snapshots
+            .iter()
+            .map(|snapshot| Self::unseen_snapshot_bytes(snapshot, &mut seen))
+            .sum();
+
+        MemoryReport {
+            live_bytes,
+            shared_bytes: live_bytes + snapshot_bytes,
+            snapshot_count: history.snapshot_count(),
+        }
+    }
+
+    /// Estimates the bytes a snapshot holds beyond the allocations already in `seen`.
+    fn unseen_snapshot_bytes(snapshot: &StateSnapshot, seen: &mut HashSet<usize>) -> usize {
+        match &snapshot.state {
+            SnapshotState::Full(state) => Self::unseen_state_bytes(state, seen),
+            SnapshotState::Compressed(compressed) => compressed.compressed_len(),
+        }
+    }
+
+    /// Estimates the bytes of `state` whose allocations are not already in `seen`,
+    /// recording every allocation it visits.
+    fn unseen_state_bytes(state: &Arc<TenantApplicationState>, seen: &mut HashSet<usize>) -> usize {
+        if !seen.insert(Arc::as_ptr(state) as usize) {
+            // The whole state is shared, and with it every collection root
+            return 0;
+        }
+
+        let tenant = &state.tenant;
+        let own_bytes = std::mem::size_of::<TenantApplicationState>()
+            + tenant.id.len()
+            + tenant.name.len()
+            + tenant.db_url.len();
+
+        let session_bytes = Self::unseen_root_bytes(&state.user_sessions.root, seen, |map| {
+            map.iter()
+                .map(|(key, session)| {
+                    std::mem::size_of::<(String, SessionData)>()
+                        + key.len()
+                        + session.user_data.len()
+                })
+                .sum()
+        });
+        let app_data_bytes = Self::unseen_root_bytes(&state.app_data.root, seen, |map| {
+            map.iter()
+                .map(|(key, value)| {
+                    std::mem::size_of::<(String, serde_json::Value)>()
+                        + key.len()
+                        + Self::json_len(value)
+                })
+                .sum()
+        });
+        let query_cache_bytes = Self::unseen_root_bytes(&state.query_cache.root, seen, |cache| {
+            cache
+                .iter()
+                .map(|result| {
+                    std::mem::size_of::<QueryResult>() + result.query_id.len() + result.data.len()
+                })
+                .sum()
+        });
+
+        own_bytes + session_bytes + app_data_bytes + query_cache_bytes
+    }
+
+    /// Length of `value` serialized as compact JSON, without allocating the string.
+    fn json_len(value: &serde_json::Value) -> usize {
+        let mut counter = ByteCounter(0);
+        // Serializing a `Value` into a sink that never fails cannot fail
+        let _ = serde_json::to_writer(&mut counter, value);
+        counter.0
+    }
+
+    /// Measures a persistent collection root with `measure` unless it was already seen.
+    fn unseen_root_bytes<R>(
+        root: &Option<Arc<R>>,
+        seen: &mut HashSet<usize>,
+        measure: impl Fn(&R) -> usize,
+    ) -> usize {
+        match root {
+            Some(root) if seen.insert(Arc::as_ptr(root) as usize) => {
+                std::mem::size_of::<R>() + measure(root)
+            }
+            _ => 0,
+        }
+    }
+
     /// Applies a transition and automatically creates a snapshot before the change
     ///
     /// # Arguments
@@ -1445,7 +3239,7 @@ impl ImmutableStateManager {
         tenant_id: &str,
         transition: F,
         snapshot_name: Option<String>,
-    ) -> Result<String, String>
+    ) -> Result<String, StateError>
     where
         F: FnOnce(
             &TenantApplicationState,
@@ -1546,6 +3340,23 @@ mod tests {
         assert_eq!(v2.len(), 1); // v2 still unchanged
     }
 
+    #[test]
+    fn test_persistent_vector_with_capacity_from_matches_appends() {
+        let rows = (0..2_000u64).map(|i| format!("row-{}", i));
+
+        let appended = rows
+            .clone()
+            .fold(PersistentVector::new(), |acc, row| acc.append(row));
+        let bulk = PersistentVector::with_capacity_from(rows);
+
+        assert_eq!(bulk.len(), 2_000);
+        assert_eq!(bulk.to_vec(), appended.to_vec());
+        assert_eq!(bulk.get(1_999), Some(&"row-1999".to_string()));
+
+        let empty = PersistentVector::<u64>::with_capacity_from(Vec::new());
+        assert!(empty.is_empty());
+    }
+
     #[test]
     fn test_persistent_hashmap() {
         let m1 = PersistentHashMap::new();
@@ -1563,6 +3374,38 @@ mod tests {
         assert_eq!(m2.get(&"key1".to_string()), Some(&"value1".to_string())); // m2 unchanged
     }
 
+    #[test]
+    fn test_persistent_hashmap_diff() {
+        let before = PersistentHashMap::new()
+            .insert("kept".to_string(), 1)
+            .insert("changed".to_string(), 2)
+            .insert("dropped".to_string(), 3)
+            .insert("dropped_too".to_string(), 4);
+        let after = before
+            .insert("changed".to_string(), 20)
+            .remove(&"dropped".to_string())
+            .remove(&"dropped_too".to_string())
+            .insert("new".to_string(), 5);
+
+        let mut diff = before.diff(&after);
+        diff.removed.sort();
+        assert_eq!(diff.added, vec!["new".to_string()]);
+        assert_eq!(
+            diff.removed,
+            vec!["dropped".to_string(), "dropped_too".to_string()]
+        );
+        assert_eq!(diff.modified, vec!["changed".to_string()]);
+
+        let reverse = after.diff(&before);
+        assert_eq!(reverse.removed, vec!["new".to_string()]);
+        assert_eq!(reverse.added.len(), 2);
+
+        assert!(before.diff(&before.clone()).is_empty());
+        assert!(PersistentHashMap::<String, i32>::new()
+            .diff(&PersistentHashMap::new())
+            .is_empty());
+    }
+
     #[test]
     fn test_state_manager_initialization() {
         let manager = ImmutableStateManager::new(100);
@@ -1903,11 +3746,11 @@ mod tests {
         // Verify we have the expected number of transitions
         assert_eq!(metrics.transition_count, transition_count);
 
-        // Verify peak memory usage is reasonable (under our 50MB limit)
+        // Verify estimated memory usage is reasonable (under our 50MB limit)
         assert!(
-            metrics.peak_memory_usage < 50 * 1024 * 1024,
-            "Peak memory usage {} bytes exceeds 50MB limit",
-            metrics.peak_memory_usage
+            metrics.estimated_memory_usage < 50 * 1024 * 1024,
+            "Estimated memory usage {} bytes exceeds 50MB limit",
+            metrics.estimated_memory_usage
         );
 
         // Verify state integrity after many operations
@@ -1918,6 +3761,47 @@ mod tests {
 
     // ==================== Snapshot and Rollback Tests ====================
 
+    #[test]
+    fn test_snapshot_id_generator_is_deterministic() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let counter = AtomicUsize::new(0);
+        let manager =
+            ImmutableStateManager::new(100).with_snapshot_id_generator(Box::new(move || {
+                format!("snap-{}", counter.fetch_add(1, Ordering::SeqCst))
+            }));
+        manager
+            .initialize_tenant(create_test_tenant("ids_test"))
+            .unwrap();
+
+        let first = manager
+            .create_snapshot("ids_test", None, "system".to_string(), None, vec![])
+            .unwrap();
+        let second = manager
+            .create_snapshot(
+                "ids_test",
+                Some("named".to_string()),
+                "system".to_string(),
+                None,
+                vec![],
+            )
+            .unwrap();
+        let third = manager
+            .create_compressed_snapshot("ids_test", None, "system".to_string(), None, vec![])
+            .unwrap();
+
+        assert_eq!(first, "snap-0");
+        assert_eq!(second, "snap-1");
+        assert_eq!(third, "snap-2");
+        let listed: Vec<String> = manager
+            .list_snapshots("ids_test")
+            .unwrap()
+            .into_iter()
+            .map(|meta| meta.snapshot_id)
+            .collect();
+        assert_eq!(listed, vec!["snap-0", "snap-1", "snap-2"]);
+    }
+
     #[test]
     fn test_create_snapshot() {
         let manager = ImmutableStateManager::new(100);
@@ -2009,6 +3893,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rollback_to_named_snapshot_audited_records_history() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("audit_test"))
+            .unwrap();
+        assert!(manager.rollback_history("audit_test").unwrap().is_empty());
+
+        for name in ["first", "second"] {
+            manager
+                .create_snapshot(
+                    "audit_test",
+                    Some(name.to_string()),
+                    "system".to_string(),
+                    None,
+                    vec![],
+                )
+                .unwrap();
+        }
+
+        manager
+            .rollback_to_named_snapshot_audited("audit_test", "first", "alice", "bad import")
+            .unwrap();
+        manager
+            .rollback_to_named_snapshot_audited("audit_test", "second", "bob", "undo")
+            .unwrap();
+        assert!(manager
+            .rollback_to_named_snapshot_audited("audit_test", "missing", "carol", "typo")
+            .is_err());
+
+        let history = manager.rollback_history("audit_test").unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].snapshot_name, "first");
+        assert_eq!(history[0].performed_by, "alice");
+        assert_eq!(history[0].reason, "bad import");
+        assert_eq!(history[1].snapshot_name, "second");
+        assert_eq!(history[1].performed_by, "bob");
+        assert!(history[0].at <= history[1].at);
+
+        assert!(matches!(
+            manager.rollback_history("unknown"),
+            Err(StateError::TenantNotFound(_))
+        ));
+    }
+
     #[test]
     fn test_rollback_to_latest_snapshot() {
         let manager = ImmutableStateManager::new(100);
@@ -2189,6 +4118,32 @@ mod tests {
         assert_eq!(snapshots[2].tags.len(), 2);
     }
 
+    #[test]
+    fn test_list_all_snapshots_covers_every_tenant() {
+        let manager = ImmutableStateManager::new(100);
+        for (tenant_id, snapshot_count) in [("admin_a", 2), ("admin_b", 3)] {
+            manager
+                .initialize_tenant(create_test_tenant(tenant_id))
+                .unwrap();
+            for i in 0..snapshot_count {
+                manager
+                    .create_snapshot(
+                        tenant_id,
+                        Some(format!("{}_{}", tenant_id, i)),
+                        "admin".to_string(),
+                        None,
+                        vec![],
+                    )
+                    .unwrap();
+            }
+        }
+
+        let all = manager.list_all_snapshots().unwrap();
+        assert_eq!(all["admin_a"].len(), 2);
+        assert_eq!(all["admin_b"].len(), 3);
+        assert_eq!(all["admin_b"][2].name, Some("admin_b_2".to_string()));
+    }
+
     #[test]
     fn test_apply_transition_with_snapshot() {
         let manager = ImmutableStateManager::new(100);
@@ -2443,7 +4398,8 @@ mod tests {
                 created_by: "test".to_string(),
                 description: None,
                 tags: vec![],
-                state: empty_state.clone(),
+                pinned: false,
+                state: SnapshotState::Full(empty_state.clone()),
             });
         }
 
@@ -2466,7 +4422,8 @@ mod tests {
                 created_by: "test".to_string(),
                 description: None,
                 tags: vec![],
-                state: empty_state.clone(),
+                pinned: false,
+                state: SnapshotState::Full(empty_state.clone()),
             });
         }
 
@@ -2489,7 +4446,8 @@ mod tests {
                 created_by: "test".to_string(),
                 description: None,
                 tags: vec![],
-                state: empty_state.clone(),
+                pinned: false,
+                state: SnapshotState::Full(empty_state.clone()),
             });
         }
 
@@ -2501,7 +4459,8 @@ mod tests {
                 created_by: "test".to_string(),
                 description: None,
                 tags: vec![],
-                state: empty_state.clone(),
+                pinned: false,
+                state: SnapshotState::Full(empty_state.clone()),
             });
         }
 
@@ -2513,4 +4472,1424 @@ mod tests {
         assert!(named_count <= 2, "Named snapshots {} exceeds limit", named_count);
         assert!(auto_count + named_count <= 4, "Total snapshots exceeds limits");
     }
+
+    #[test]
+    fn test_pinned_snapshot_survives_pruning() {
+        let manager = ImmutableStateManager::with_snapshot_limits(100, 2, 2);
+        manager
+            .initialize_tenant(create_test_tenant("pinned"))
+            .unwrap();
+
+        let pinned_id = manager
+            .create_snapshot(
+                "pinned",
+                Some("release".to_string()),
+                "test".to_string(),
+                None,
+                vec![],
+            )
+            .unwrap();
+        manager.pin_snapshot("pinned", &pinned_id).unwrap();
+
+        for i in 0..5 {
+            manager
+                .create_snapshot(
+                    "pinned",
+                    Some(format!("named_{}", i)),
+                    "test".to_string(),
+                    None,
+                    vec![],
+                )
+                .unwrap();
+            manager
+                .create_snapshot("pinned", None, "test".to_string(), None, vec![])
+                .unwrap();
+        }
+
+        let snapshots = manager.list_snapshots("pinned").unwrap();
+        assert_eq!(snapshots.len(), 5);
+        assert_eq!(snapshots[0].snapshot_id, pinned_id);
+        assert!(snapshots[0].pinned);
+        manager
+            .rollback_to_named_snapshot("pinned", "release")
+            .unwrap();
+
+        manager.unpin_snapshot("pinned", &pinned_id).unwrap();
+        manager
+            .create_snapshot(
+                "pinned",
+                Some("named_5".to_string()),
+                "test".to_string(),
+                None,
+                vec![],
+            )
+            .unwrap();
+        let snapshots = manager.list_snapshots("pinned").unwrap();
+        assert!(snapshots.iter().all(|s| s.snapshot_id != pinned_id));
+
+        assert!(manager.pin_snapshot("pinned", "missing").is_err());
+    }
+
+    #[test]
+    fn test_gc_snapshots_under_pressure_keeps_named_and_pinned() {
+        let manager = ImmutableStateManager::with_snapshot_limits(100, 50, 50);
+        manager.initialize_tenant(create_test_tenant("gc")).unwrap();
+
+        // Every snapshot gets its own 64 KB app_data so dropping it frees memory
+        let write_blob = |i: usize| {
+            manager
+                .apply_transition("gc", move |state| {
+                    let mut new_state = state.clone();
+                    new_state.app_data = state.app_data.insert(
+                        "blob".to_string(),
+                        serde_json::json!(format!("{}{}", i, "x".repeat(64 * 1024))),
+                    );
+                    Ok(new_state)
+                })
+                .unwrap();
+        };
+
+        write_blob(0);
+        let pinned_id = manager
+            .create_snapshot("gc", None, "test".to_string(), None, vec![])
+            .unwrap();
+        manager.pin_snapshot("gc", &pinned_id).unwrap();
+        write_blob(1);
+        let named_id = manager
+            .create_snapshot(
+                "gc",
+                Some("release".to_string()),
+                "test".to_string(),
+                None,
+                vec![],
+            )
+            .unwrap();
+        for i in 2..22 {
+            write_blob(i);
+            manager
+                .create_snapshot("gc", None, "test".to_string(), None, vec![])
+                .unwrap();
+        }
+        assert_eq!(manager.snapshot_count("gc").unwrap(), 22);
+        assert!(manager.estimate_tenant_memory("gc").unwrap().shared_bytes > 1024 * 1024);
+
+        assert_eq!(manager.gc_snapshots_under_pressure("gc", 100).unwrap(), 0);
+
+        let dropped = manager.gc_snapshots_under_pressure("gc", 1).unwrap();
+        assert!(dropped > 0 && dropped < 20);
+        assert_eq!(manager.snapshot_count("gc").unwrap(), 22 - dropped);
+        assert!(manager.estimate_tenant_memory("gc").unwrap().shared_bytes <= 1024 * 1024);
+
+        // An unreachable target drops every automatic snapshot that is not pinned
+        assert_eq!(
+            manager.gc_snapshots_under_pressure("gc", 0).unwrap(),
+            20 - dropped
+        );
+        let remaining: Vec<String> = manager
+            .list_snapshots("gc")
+            .unwrap()
+            .into_iter()
+            .map(|s| s.snapshot_id)
+            .collect();
+        assert_eq!(remaining, vec![pinned_id, named_id]);
+        manager.rollback_to_named_snapshot("gc", "release").unwrap();
+
+        assert!(manager.gc_snapshots_under_pressure("missing", 0).is_err());
+    }
+
+    #[test]
+    fn test_cache_query_result_caps_entries() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("cache_tenant"))
+            .unwrap();
+
+        let max_entries = 10;
+        for i in 0..max_entries + 5 {
+            let result = QueryResult {
+                query_id: format!("query_{}", i),
+                data: vec![i as u8],
+                expires_at: Utc::now() + chrono::Duration::hours(1),
+            };
+            manager
+                .cache_query_result("cache_tenant", result, max_entries)
+                .unwrap();
+        }
+
+        let state = manager.get_tenant_state("cache_tenant").unwrap();
+        assert_eq!(state.query_cache.len(), max_entries);
+        assert_eq!(state.query_cache.get(0).unwrap().query_id, "query_5");
+        assert_eq!(
+            state.query_cache.get(max_entries - 1).unwrap().query_id,
+            format!("query_{}", max_entries + 4)
+        );
+    }
+
+    #[test]
+    fn test_get_cached_query_hit_expired_and_miss() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("lookup_tenant"))
+            .unwrap();
+
+        let now = Utc::now();
+        let fresh = QueryResult {
+            query_id: "fresh".to_string(),
+            data: vec![1, 2, 3],
+            expires_at: now + chrono::Duration::minutes(5),
+        };
+        let stale = QueryResult {
+            query_id: "stale".to_string(),
+            data: vec![4, 5, 6],
+            expires_at: now - chrono::Duration::minutes(5),
+        };
+        manager
+            .cache_query_result("lookup_tenant", fresh, 10)
+            .unwrap();
+        manager
+            .cache_query_result("lookup_tenant", stale, 10)
+            .unwrap();
+
+        assert_eq!(
+            manager
+                .get_cached_query("lookup_tenant", "fresh", now)
+                .unwrap(),
+            Some(vec![1, 2, 3])
+        );
+        assert_eq!(
+            manager
+                .get_cached_query("lookup_tenant", "stale", now)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            manager
+                .get_cached_query("lookup_tenant", "missing", now)
+                .unwrap(),
+            None
+        );
+        assert!(manager.get_cached_query("unknown", "fresh", now).is_err());
+    }
+
+    #[test]
+    fn test_diff_named_snapshots() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("diff_test"))
+            .unwrap();
+
+        manager
+            .apply_transition("diff_test", |state| {
+                let mut new_state = state.clone();
+                new_state.app_data = state
+                    .app_data
+                    .insert("version".to_string(), serde_json::json!("1.0"))
+                    .insert("legacy_flag".to_string(), serde_json::json!(true));
+                Ok(new_state)
+            })
+            .unwrap();
+        manager
+            .create_snapshot(
+                "diff_test",
+                Some("before_deploy".to_string()),
+                "system".to_string(),
+                None,
+                vec![],
+            )
+            .unwrap();
+
+        manager
+            .apply_transition("diff_test", |state| {
+                let mut new_state = state.clone();
+                new_state.app_data = state
+                    .app_data
+                    .insert("version".to_string(), serde_json::json!("1.1"))
+                    .remove(&"legacy_flag".to_string())
+                    .insert("feature_x".to_string(), serde_json::json!(true));
+                Ok(new_state)
+            })
+            .unwrap();
+        manager
+            .create_snapshot(
+                "diff_test",
+                Some("after_deploy".to_string()),
+                "system".to_string(),
+                None,
+                vec![],
+            )
+            .unwrap();
+
+        let diff = manager
+            .diff_named_snapshots("diff_test", "before_deploy", "after_deploy")
+            .unwrap();
+        assert_eq!(diff.app_data.added, vec!["feature_x".to_string()]);
+        assert_eq!(diff.app_data.removed, vec!["legacy_flag".to_string()]);
+        assert_eq!(diff.app_data.modified, vec!["version".to_string()]);
+        assert!(diff.user_sessions.is_empty());
+        assert!(!diff.is_empty());
+
+        assert!(manager
+            .diff_named_snapshots("diff_test", "before_deploy", "before_deploy")
+            .unwrap()
+            .is_empty());
+
+        let err = manager
+            .diff_named_snapshots("diff_test", "before_deploy", "missing")
+            .unwrap_err();
+        assert!(
+            matches!(err, StateError::SnapshotNotFound(ref what) if what.contains("'missing'"))
+        );
+        assert!(manager
+            .diff_named_snapshots("unknown", "before_deploy", "after_deploy")
+            .is_err());
+    }
+
+    #[test]
+    fn test_apply_transition_diffed_reports_changes() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("diffed"))
+            .unwrap();
+        manager
+            .apply_transition("diffed", |state| {
+                let mut new_state = state.clone();
+                new_state.app_data = state
+                    .app_data
+                    .insert("plan".to_string(), serde_json::json!("basic"))
+                    .insert("seats".to_string(), serde_json::json!(5));
+                Ok(new_state)
+            })
+            .unwrap();
+
+        let diff = manager
+            .apply_transition_diffed("diffed", |state| {
+                let mut new_state = state.clone();
+                new_state.app_data = state
+                    .app_data
+                    .insert("plan".to_string(), serde_json::json!("pro"))
+                    .insert("region".to_string(), serde_json::json!("sa-east-1"));
+                Ok(new_state)
+            })
+            .unwrap();
+
+        assert_eq!(diff.app_data.added, vec!["region".to_string()]);
+        assert_eq!(diff.app_data.modified, vec!["plan".to_string()]);
+        assert!(diff.app_data.removed.is_empty());
+        assert!(diff.user_sessions.is_empty());
+        assert_eq!(
+            manager
+                .get_tenant_state("diffed")
+                .unwrap()
+                .app_data
+                .get(&"plan".to_string()),
+            Some(&serde_json::json!("pro"))
+        );
+
+        assert!(matches!(
+            manager.apply_transition_diffed("unknown", |state| Ok(state.clone())),
+            Err(StateError::TenantNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_restore_and_replay() {
+        use crate::functional::state_transitions::TransitionError;
+
+        type Replayed = Box<
+            dyn FnOnce(&TenantApplicationState) -> Result<TenantApplicationState, TransitionError>,
+        >;
+        fn set(key: &'static str, value: i64) -> Replayed {
+            Box::new(move |state: &TenantApplicationState| {
+                let mut next = state.clone();
+                next.app_data = state
+                    .app_data
+                    .insert(key.to_string(), serde_json::json!(value));
+                Ok(next)
+            })
+        }
+        let read = |manager: &ImmutableStateManager, key: &str| {
+            manager
+                .get_tenant_state("replay")
+                .unwrap()
+                .app_data
+                .get(&key.to_string())
+                .cloned()
+        };
+
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("replay"))
+            .unwrap();
+        manager.apply_transition("replay", set("count", 1)).unwrap();
+        manager
+            .create_snapshot(
+                "replay",
+                Some("base".to_string()),
+                "system".to_string(),
+                None,
+                vec![],
+            )
+            .unwrap();
+        manager
+            .apply_transition("replay", set("count", 99))
+            .unwrap();
+
+        manager
+            .restore_and_replay("replay", "base", vec![set("count", 2), set("total", 10)])
+            .unwrap();
+        assert_eq!(read(&manager, "count"), Some(serde_json::json!(2)));
+        assert_eq!(read(&manager, "total"), Some(serde_json::json!(10)));
+
+        let failing: Replayed = Box::new(|_: &TenantApplicationState| {
+            Err(TransitionError::InvalidParameters {
+                message: "corrupt event".to_string(),
+            })
+        });
+        let err = manager
+            .restore_and_replay("replay", "base", vec![set("count", 3), failing])
+            .unwrap_err();
+        assert!(matches!(err, StateError::Transition(_)));
+        assert_eq!(read(&manager, "count"), Some(serde_json::json!(1)));
+        assert_eq!(read(&manager, "total"), None);
+
+        assert!(matches!(
+            manager.restore_and_replay("replay", "missing", Vec::<Replayed>::new()),
+            Err(StateError::SnapshotNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_auto_snapshot_interval() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("auto_snap"))
+            .unwrap();
+        manager.set_auto_snapshot_interval("auto_snap", 3).unwrap();
+
+        for i in 0..7 {
+            manager
+                .apply_transition("auto_snap", |state| {
+                    let mut new_state = state.clone();
+                    new_state.app_data = state
+                        .app_data
+                        .insert("counter".to_string(), serde_json::json!(i));
+                    Ok(new_state)
+                })
+                .unwrap();
+        }
+
+        let auto: Vec<_> = manager
+            .list_snapshots("auto_snap")
+            .unwrap()
+            .into_iter()
+            .filter(|s| s.tags.contains(&"auto".to_string()))
+            .collect();
+        assert_eq!(auto.len(), 2);
+        assert!(auto.iter().all(|s| s.name.is_none()));
+
+        manager.set_auto_snapshot_interval("auto_snap", 0).unwrap();
+        for _ in 0..3 {
+            manager
+                .apply_transition("auto_snap", |state| Ok(state.clone()))
+                .unwrap();
+        }
+        assert_eq!(manager.snapshot_count("auto_snap").unwrap(), 2);
+
+        assert!(matches!(
+            manager.set_auto_snapshot_interval("unknown", 3),
+            Err(StateError::TenantNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_timeline_reports_gaps_and_change_counts() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("timeline_test"))
+            .unwrap();
+
+        let edits: [&[(&str, serde_json::Value)]; 3] = [
+            &[("a", serde_json::json!(1)), ("b", serde_json::json!(2))],
+            &[("a", serde_json::json!(10))],
+            &[
+                ("b", serde_json::json!(2)),
+                ("c", serde_json::json!(3)),
+                ("d", serde_json::json!(4)),
+            ],
+        ];
+        for (idx, edit) in edits.iter().enumerate() {
+            manager
+                .apply_transition("timeline_test", |state| {
+                    let mut new_state = state.clone();
+                    for (key, value) in edit.iter() {
+                        new_state.app_data =
+                            new_state.app_data.insert(key.to_string(), value.clone());
+                    }
+                    Ok(new_state)
+                })
+                .unwrap();
+            manager
+                .create_snapshot(
+                    "timeline_test",
+                    Some(format!("step_{}", idx)),
+                    "system".to_string(),
+                    None,
+                    vec![],
+                )
+                .unwrap();
+        }
+
+        let base = Utc::now() - chrono::Duration::minutes(10);
+        {
+            let mut histories = manager.snapshot_histories.write().unwrap();
+            let history = histories.get_mut("timeline_test").unwrap();
+            history.snapshots[0].created_at = base;
+            history.snapshots[1].created_at = base + chrono::Duration::seconds(90);
+            history.snapshots[2].created_at = base + chrono::Duration::milliseconds(92_500);
+        }
+
+        let timeline = manager.snapshot_timeline("timeline_test").unwrap();
+        let names: Vec<_> = timeline
+            .iter()
+            .map(|e| e.name.as_deref().unwrap())
+            .collect();
+        assert_eq!(names, vec!["step_0", "step_1", "step_2"]);
+        assert_eq!(
+            timeline
+                .iter()
+                .map(|e| e.seconds_since_previous)
+                .collect::<Vec<_>>(),
+            vec![None, Some(90.0), Some(2.5)]
+        );
+        assert_eq!(
+            timeline.iter().map(|e| e.changed_keys).collect::<Vec<_>>(),
+            vec![2, 1, 2]
+        );
+        assert_eq!(timeline[0].created_at, base);
+
+        assert!(matches!(
+            manager.snapshot_timeline("unknown"),
+            Err(StateError::TenantNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_clone_snapshot_to_tenant() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("production"))
+            .unwrap();
+        manager
+            .apply_transition("production", |state| {
+                let mut new_state = state.clone();
+                new_state.app_data = state
+                    .app_data
+                    .insert("plan".to_string(), serde_json::json!("enterprise"))
+                    .insert("seats".to_string(), serde_json::json!(250));
+                Ok(new_state)
+            })
+            .unwrap();
+        manager
+            .create_snapshot(
+                "production",
+                Some("nightly".to_string()),
+                "system".to_string(),
+                None,
+                vec![],
+            )
+            .unwrap();
+
+        // Later changes to the source must not leak into the clone
+        manager
+            .apply_transition("production", |state| {
+                let mut new_state = state.clone();
+                new_state.app_data = state
+                    .app_data
+                    .insert("plan".to_string(), serde_json::json!("trial"));
+                Ok(new_state)
+            })
+            .unwrap();
+
+        manager
+            .clone_snapshot_to_tenant("production", "nightly", create_test_tenant("staging"))
+            .unwrap();
+
+        let staging = manager.get_tenant_state("staging").unwrap();
+        assert_eq!(staging.tenant.id, "staging");
+        assert_eq!(
+            staging.app_data.get(&"plan".to_string()),
+            Some(&serde_json::json!("enterprise"))
+        );
+        assert_eq!(
+            staging.app_data.get(&"seats".to_string()),
+            Some(&serde_json::json!(250))
+        );
+        assert_eq!(staging.app_data.len(), 2);
+        assert_eq!(manager.snapshot_count("staging").unwrap(), 0);
+        assert_eq!(
+            manager.get_tenant_state("production").unwrap().tenant.id,
+            "production"
+        );
+
+        let err = manager
+            .clone_snapshot_to_tenant("production", "nightly", create_test_tenant("staging"))
+            .unwrap_err();
+        assert!(matches!(err, StateError::TenantExists(ref id) if id == "staging"));
+        assert!(manager
+            .clone_snapshot_to_tenant("production", "missing", create_test_tenant("qa"))
+            .is_err());
+        assert!(!manager.tenant_exists("qa"));
+    }
+
+    #[test]
+    fn test_create_snapshot_if_changed() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("conditional_test"))
+            .unwrap();
+
+        let first = manager
+            .create_snapshot_if_changed("conditional_test", None, "system".to_string())
+            .unwrap();
+        assert!(first.is_some());
+
+        let skipped = manager
+            .create_snapshot_if_changed("conditional_test", None, "system".to_string())
+            .unwrap();
+        assert_eq!(skipped, None);
+        assert_eq!(manager.snapshot_count("conditional_test").unwrap(), 1);
+
+        manager
+            .apply_transition("conditional_test", |state| {
+                let mut new_state = state.clone();
+                new_state.app_data = state
+                    .app_data
+                    .insert("counter".to_string(), serde_json::json!(1));
+                new_state.last_updated = state.last_updated + chrono::Duration::seconds(1);
+                Ok(new_state)
+            })
+            .unwrap();
+
+        let after_change = manager
+            .create_snapshot_if_changed("conditional_test", None, "system".to_string())
+            .unwrap();
+        assert!(after_change.is_some());
+        assert_ne!(after_change, first);
+        assert_eq!(manager.snapshot_count("conditional_test").unwrap(), 2);
+
+        assert!(manager
+            .create_snapshot_if_changed("unknown", None, "system".to_string())
+            .is_err());
+    }
+
+    #[test]
+    fn test_initialize_tenant_with_defaults_seeds_app_data() {
+        let manager = ImmutableStateManager::new(100);
+        let defaults = HashMap::from([
+            (
+                "feature_flags".to_string(),
+                serde_json::json!({"beta": false}),
+            ),
+            ("welcome_shown".to_string(), serde_json::json!(false)),
+        ]);
+
+        manager
+            .initialize_tenant_with_defaults(create_test_tenant("seeded"), defaults.clone())
+            .unwrap();
+
+        let state = manager.get_tenant_state("seeded").unwrap();
+        assert_eq!(state.app_data.len(), 2);
+        assert_eq!(
+            state.app_data.get(&"feature_flags".to_string()),
+            Some(&serde_json::json!({"beta": false}))
+        );
+        assert!(state.user_sessions.is_empty());
+        assert!(state.query_cache.is_empty());
+        assert_eq!(manager.snapshot_count("seeded").unwrap(), 0);
+
+        let err = manager
+            .initialize_tenant_with_defaults(create_test_tenant("seeded"), defaults)
+            .unwrap_err();
+        assert!(matches!(err, StateError::TenantExists(ref id) if id == "seeded"));
+    }
+
+    #[test]
+    fn test_deprovision_tenant_removes_state_and_snapshots() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("offboarded"))
+            .unwrap();
+        manager
+            .create_snapshot(
+                "offboarded",
+                Some("before_offboarding".to_string()),
+                "admin".to_string(),
+                None,
+                vec![],
+            )
+            .unwrap();
+        assert_eq!(manager.snapshot_count("offboarded").unwrap(), 1);
+
+        assert!(manager.deprovision_tenant("offboarded").unwrap());
+
+        assert!(!manager.tenant_exists("offboarded"));
+        let err = manager.snapshot_count("offboarded").unwrap_err();
+        assert!(matches!(err, StateError::TenantNotFound(ref id) if id == "offboarded"));
+        assert!(!manager.deprovision_tenant("offboarded").unwrap());
+    }
+
+    #[test]
+    fn test_apply_transitions_timed_reports_each_duration() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("timed_test"))
+            .unwrap();
+
+        let work_ms = [0u64, 30, 5];
+        let transitions = work_ms.iter().enumerate().map(|(step, ms)| {
+            let ms = *ms;
+            move |state: &TenantApplicationState| {
+                std::thread::sleep(Duration::from_millis(ms));
+                let mut new_state = state.clone();
+                new_state.app_data = state
+                    .app_data
+                    .insert(format!("step_{}", step), serde_json::json!(ms));
+                new_state
+            }
+        });
+
+        let durations = manager
+            .apply_transitions_timed("timed_test", transitions)
+            .unwrap();
+
+        assert_eq!(durations.len(), 3);
+        assert!(durations[1] >= Duration::from_millis(30));
+        assert!(durations[2] >= Duration::from_millis(5));
+        assert!(durations[1] > durations[2]);
+        assert!(durations[1] > durations[0]);
+
+        let state = manager.get_tenant_state("timed_test").unwrap();
+        assert_eq!(state.app_data.len(), 3);
+        assert_eq!(manager.get_metrics().unwrap().transition_count, 3);
+        let stats = manager.tenant_latency_percentiles("timed_test").unwrap();
+        assert!(stats.max_ns >= 30_000_000);
+
+        let no_transitions: Vec<fn(&TenantApplicationState) -> TenantApplicationState> = Vec::new();
+        assert!(manager
+            .apply_transitions_timed("missing", no_transitions)
+            .is_err());
+    }
+
+    #[test]
+    fn test_replace_app_data_swaps_whole_map() {
+        let manager = ImmutableStateManager::new(100);
+        let defaults = HashMap::from([
+            ("legacy_flag".to_string(), serde_json::json!(true)),
+            ("theme".to_string(), serde_json::json!("light")),
+        ]);
+        manager
+            .initialize_tenant_with_defaults(create_test_tenant("importer"), defaults)
+            .unwrap();
+        let before = manager.get_tenant_state("importer").unwrap().last_updated;
+
+        let imported = HashMap::from([
+            ("theme".to_string(), serde_json::json!("dark")),
+            ("locale".to_string(), serde_json::json!("pt-BR")),
+        ]);
+        manager.replace_app_data("importer", imported).unwrap();
+
+        let state = manager.get_tenant_state("importer").unwrap();
+        assert_eq!(state.app_data.len(), 2);
+        assert!(!state.app_data.contains_key(&"legacy_flag".to_string()));
+        assert_eq!(
+            state.app_data.get(&"theme".to_string()),
+            Some(&serde_json::json!("dark"))
+        );
+        assert_eq!(
+            state.app_data.get(&"locale".to_string()),
+            Some(&serde_json::json!("pt-BR"))
+        );
+        assert!(state.last_updated >= before);
+
+        assert!(manager.replace_app_data("missing", HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_apply_json_patch_upserts_keys() {
+        let manager = ImmutableStateManager::new(100);
+        let defaults = HashMap::from([
+            ("theme".to_string(), serde_json::json!("light")),
+            ("locale".to_string(), serde_json::json!("en-US")),
+        ]);
+        manager
+            .initialize_tenant_with_defaults(create_test_tenant("patched"), defaults)
+            .unwrap();
+
+        manager
+            .apply_json_patch(
+                "patched",
+                serde_json::json!({ "theme": "dark", "limits": { "max_users": 10 } }),
+            )
+            .unwrap();
+
+        let state = manager.get_tenant_state("patched").unwrap();
+        assert_eq!(state.app_data.len(), 3);
+        assert_eq!(
+            state.app_data.get(&"theme".to_string()),
+            Some(&serde_json::json!("dark"))
+        );
+        assert_eq!(
+            state.app_data.get(&"locale".to_string()),
+            Some(&serde_json::json!("en-US"))
+        );
+        assert_eq!(
+            state.app_data.get(&"limits".to_string()),
+            Some(&serde_json::json!({ "max_users": 10 }))
+        );
+    }
+
+    #[test]
+    fn test_apply_json_patch_null_deletes_key() {
+        let manager = ImmutableStateManager::new(100);
+        let defaults = HashMap::from([
+            ("legacy_flag".to_string(), serde_json::json!(true)),
+            ("theme".to_string(), serde_json::json!("light")),
+        ]);
+        manager
+            .initialize_tenant_with_defaults(create_test_tenant("pruned"), defaults)
+            .unwrap();
+        let transitions = manager.get_metrics().unwrap().transition_count;
+
+        manager
+            .apply_json_patch(
+                "pruned",
+                serde_json::json!({ "legacy_flag": null, "unknown": null }),
+            )
+            .unwrap();
+
+        let state = manager.get_tenant_state("pruned").unwrap();
+        assert_eq!(state.app_data.len(), 1);
+        assert!(!state.app_data.contains_key(&"legacy_flag".to_string()));
+        assert!(state.app_data.contains_key(&"theme".to_string()));
+        assert_eq!(
+            manager.get_metrics().unwrap().transition_count,
+            transitions + 1
+        );
+    }
+
+    #[test]
+    fn test_apply_json_patch_rejects_non_object() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("rejected"))
+            .unwrap();
+        let before = manager.get_tenant_state("rejected").unwrap();
+
+        for patch in [
+            serde_json::json!([1, 2]),
+            serde_json::json!("theme"),
+            serde_json::Value::Null,
+        ] {
+            assert!(matches!(
+                manager.apply_json_patch("rejected", patch),
+                Err(StateError::Transition(_))
+            ));
+        }
+        assert!(Arc::ptr_eq(
+            &before,
+            &manager.get_tenant_state("rejected").unwrap()
+        ));
+        assert!(manager
+            .apply_json_patch("missing", serde_json::json!({}))
+            .is_err());
+    }
+
+    #[test]
+    fn test_add_sessions_inserts_batch_in_one_transition() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("federated"))
+            .unwrap();
+        let before = manager.get_metrics().unwrap().transition_count;
+
+        let expires_at = chrono::Utc::now() + chrono::Duration::hours(1);
+        let sessions = (0..50)
+            .map(|i| {
+                (
+                    format!("session-{}", i),
+                    SessionData {
+                        user_data: format!("user-{}", i),
+                        expires_at,
+                    },
+                )
+            })
+            .collect();
+        manager.add_sessions("federated", sessions).unwrap();
+
+        let state = manager.get_tenant_state("federated").unwrap();
+        assert_eq!(state.user_sessions.len(), 50);
+        for i in 0..50 {
+            let session = state
+                .user_sessions
+                .get(&format!("session-{}", i))
+                .expect("session present");
+            assert_eq!(session.user_data, format!("user-{}", i));
+        }
+        assert_eq!(manager.get_metrics().unwrap().transition_count, before + 1);
+
+        assert!(manager.add_sessions("missing", Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_get_active_session_honours_expiry() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("sessions"))
+            .unwrap();
+        let now = chrono::Utc::now();
+        let session = |user: &str, expires_at| SessionData {
+            user_data: user.to_string(),
+            expires_at,
+        };
+        manager
+            .add_sessions(
+                "sessions",
+                vec![
+                    (
+                        "active".to_string(),
+                        session("user-1", now + chrono::Duration::minutes(5)),
+                    ),
+                    (
+                        "expired".to_string(),
+                        session("user-2", now - chrono::Duration::minutes(5)),
+                    ),
+                ],
+            )
+            .unwrap();
+
+        let active = manager
+            .get_active_session("sessions", "active", now)
+            .unwrap();
+        assert_eq!(active.map(|s| s.user_data), Some("user-1".to_string()));
+
+        assert!(manager
+            .get_active_session("sessions", "expired", now)
+            .unwrap()
+            .is_none());
+        assert!(manager
+            .get_active_session("sessions", "missing", now)
+            .unwrap()
+            .is_none());
+        assert!(manager
+            .get_active_session("unknown", "active", now)
+            .is_err());
+    }
+
+    #[test]
+    fn test_active_session_count_skips_expired() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("billing"))
+            .unwrap();
+        let now = chrono::Utc::now();
+        assert_eq!(manager.active_session_count("billing", now).unwrap(), 0);
+
+        let sessions = (0..10)
+            .map(|i| {
+                let offset = chrono::Duration::minutes(if i % 3 == 0 { -1 } else { 1 });
+                (
+                    format!("session-{}", i),
+                    SessionData {
+                        user_data: format!("user-{}", i),
+                        expires_at: now + offset,
+                    },
+                )
+            })
+            .collect();
+        manager.add_sessions("billing", sessions).unwrap();
+
+        assert_eq!(manager.active_session_count("billing", now).unwrap(), 6);
+        assert_eq!(
+            manager
+                .active_session_count("billing", now + chrono::Duration::minutes(2))
+                .unwrap(),
+            0
+        );
+        assert!(manager.active_session_count("unknown", now).is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "real_memory_metrics", target_os = "linux"))]
+    fn test_real_memory_metrics_samples_resident_set() {
+        // The limit bounds the whole test process here, so leave it out of the way
+        let manager = ImmutableStateManager::new(usize::MAX);
+        manager
+            .initialize_tenant(create_test_tenant("rss"))
+            .unwrap();
+
+        for i in 0..5 {
+            manager
+                .apply_transition("rss", |state| {
+                    let mut new_state = state.clone();
+                    new_state.app_data = state
+                        .app_data
+                        .insert(format!("key-{}", i), serde_json::json!(i));
+                    Ok(new_state)
+                })
+                .unwrap();
+        }
+
+        let sampled = ImmutableStateManager::sample_resident_memory().unwrap();
+        assert!(sampled > 0);
+        assert!(manager.get_metrics().unwrap().peak_memory_usage > 0);
+    }
+
+    #[test]
+    #[cfg(all(feature = "real_memory_metrics", target_os = "linux"))]
+    fn test_real_memory_metrics_check_follows_current_sample() {
+        let manager = ImmutableStateManager::new(4096);
+        manager
+            .initialize_tenant(create_test_tenant("spike"))
+            .unwrap();
+
+        // A current sample over the limit fails the check
+        let spike = 8 * 1024 * 1024 * 1024;
+        {
+            let mut metrics = ImmutableStateManager::write_recovering(&manager.metrics);
+            metrics.current_memory_usage = spike;
+            metrics.peak_memory_usage = spike;
+        }
+        assert!(!manager.check_memory_limits().unwrap());
+
+        // The next transition takes a lower sample, which passes again
+        manager
+            .apply_transition("spike", |state| Ok(state.clone()))
+            .unwrap();
+
+        let metrics = manager.get_metrics().unwrap();
+        assert!(metrics.current_memory_usage < spike);
+        assert_eq!(metrics.peak_memory_usage, spike);
+        assert!(manager.check_memory_limits().unwrap());
+    }
+
+    /// Bumps the `version` counter in `app_data` by one.
+    fn bump_version(
+        state: &TenantApplicationState,
+    ) -> Result<TenantApplicationState, crate::functional::state_transitions::TransitionError> {
+        let current = state
+            .app_data
+            .get(&"version".to_string())
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        let mut new_state = state.clone();
+        new_state.app_data = state
+            .app_data
+            .insert("version".to_string(), serde_json::json!(current + 1));
+        Ok(new_state)
+    }
+
+    #[test]
+    fn test_apply_transition_if_precondition_satisfied() {
+        let manager = ImmutableStateManager::new(100);
+        let defaults = HashMap::from([("version".to_string(), serde_json::json!(1))]);
+        manager
+            .initialize_tenant_with_defaults(create_test_tenant("optimistic"), defaults)
+            .unwrap();
+
+        let applied = manager
+            .apply_transition_if(
+                "optimistic",
+                |state| state.app_data.get(&"version".to_string()) == Some(&serde_json::json!(1)),
+                bump_version,
+            )
+            .unwrap();
+
+        assert!(applied);
+        let state = manager.get_tenant_state("optimistic").unwrap();
+        assert_eq!(
+            state.app_data.get(&"version".to_string()),
+            Some(&serde_json::json!(2))
+        );
+        assert_eq!(manager.get_metrics().unwrap().transition_count, 1);
+    }
+
+    #[test]
+    fn test_state_errors_expose_specific_variants() {
+        use crate::functional::state_transitions::TransitionError;
+
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("errors_test"))
+            .unwrap();
+
+        let err = manager
+            .apply_transition("missing", |state| Ok(state.clone()))
+            .unwrap_err();
+        assert!(matches!(err, StateError::TenantNotFound(ref id) if id == "missing"));
+        assert_eq!(err.to_string(), "Tenant 'missing' not found");
+
+        let err = manager
+            .initialize_tenant(create_test_tenant("errors_test"))
+            .unwrap_err();
+        assert!(matches!(err, StateError::TenantExists(ref id) if id == "errors_test"));
+
+        let err = manager
+            .rollback_to_named_snapshot("errors_test", "nope")
+            .unwrap_err();
+        assert!(matches!(err, StateError::SnapshotNotFound(_)));
+        assert!(matches!(
+            manager
+                .rollback_to_latest_snapshot("errors_test")
+                .unwrap_err(),
+            StateError::SnapshotNotFound(_)
+        ));
+        assert!(matches!(
+            manager
+                .rollback_to_snapshot_index("errors_test", 3)
+                .unwrap_err(),
+            StateError::SnapshotNotFound(_)
+        ));
+        assert!(matches!(
+            manager.pin_snapshot("errors_test", "snap-x").unwrap_err(),
+            StateError::SnapshotNotFound(_)
+        ));
+
+        let err = manager
+            .apply_transition("errors_test", |_| {
+                Err(TransitionError::ValidationFailed {
+                    field: "plan".to_string(),
+                    reason: "unknown".to_string(),
+                })
+            })
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            StateError::Transition(TransitionError::ValidationFailed { .. })
+        ));
+        assert!(err.to_string().starts_with("Transition failed:"));
+
+        // The metrics baseline estimate (1 MB) exceeds a zero limit on the first transition
+        let tight = ImmutableStateManager::new(0);
+        tight
+            .initialize_tenant(create_test_tenant("tight"))
+            .unwrap();
+        let err = tight
+            .apply_transition("tight", |state| Ok(state.clone()))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            StateError::MemoryLimitExceeded { limit_mb: 0 }
+        ));
+    }
+
+    #[test]
+    fn test_apply_transition_if_precondition_unsatisfied() {
+        let manager = ImmutableStateManager::new(100);
+        let defaults = HashMap::from([("version".to_string(), serde_json::json!(3))]);
+        manager
+            .initialize_tenant_with_defaults(create_test_tenant("stale"), defaults)
+            .unwrap();
+        let before = manager.get_tenant_state("stale").unwrap();
+
+        let applied = manager
+            .apply_transition_if(
+                "stale",
+                |state| state.app_data.get(&"version".to_string()) == Some(&serde_json::json!(1)),
+                bump_version,
+            )
+            .unwrap();
+
+        assert!(!applied);
+        let after = manager.get_tenant_state("stale").unwrap();
+        assert!(Arc::ptr_eq(&before, &after));
+        assert_eq!(manager.get_metrics().unwrap().transition_count, 0);
+    }
+
+    #[test]
+    fn test_manager_recovers_from_poisoned_lock() {
+        let manager = Arc::new(ImmutableStateManager::new(100));
+        manager
+            .initialize_tenant(create_test_tenant("poisoned"))
+            .unwrap();
+
+        let panicking = Arc::clone(&manager);
+        let join = std::thread::spawn(move || {
+            panicking
+                .apply_transition("poisoned", |_| panic!("transition bug"))
+                .unwrap();
+        })
+        .join();
+        assert!(join.is_err());
+        assert!(manager.tenant_states.is_poisoned());
+
+        manager.apply_transition("poisoned", bump_version).unwrap();
+        assert!(!manager.tenant_states.is_poisoned());
+
+        let state = manager.get_tenant_state("poisoned").unwrap();
+        assert_eq!(
+            state.app_data.get(&"version".to_string()),
+            Some(&serde_json::json!(1))
+        );
+        assert!(manager.tenant_exists("poisoned"));
+        manager
+            .create_snapshot("poisoned", None, "test".to_string(), None, vec![])
+            .unwrap();
+        assert_eq!(manager.get_metrics().unwrap().transition_count, 1);
+    }
+
+    #[test]
+    fn test_tenant_latency_percentiles_are_ordered() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("latency"))
+            .unwrap();
+        manager
+            .initialize_tenant(create_test_tenant("idle"))
+            .unwrap();
+
+        for i in 0..200 {
+            manager
+                .apply_transition("latency", move |state| {
+                    if i % 20 == 0 {
+                        std::thread::sleep(Duration::from_micros(200));
+                    }
+                    bump_version(state)
+                })
+                .unwrap();
+        }
+
+        let stats = manager.tenant_latency_percentiles("latency").unwrap();
+        assert_eq!(stats.count, 200);
+        assert!(stats.min_ns <= stats.p50_ns);
+        assert!(stats.p50_ns <= stats.p95_ns);
+        assert!(stats.p95_ns <= stats.p99_ns);
+        assert!(stats.p99_ns <= stats.max_ns);
+        assert!(stats.max_ns >= 200_000);
+
+        assert!(manager.tenant_latency_percentiles("idle").is_err());
+    }
+
+    /// Global `log` backend that records formatted messages for assertions
+    struct CapturingLogger;
+
+    static CAPTURED_LOGS: std::sync::Mutex<Vec<(log::Level, String)>> =
+        std::sync::Mutex::new(Vec::new());
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            CAPTURED_LOGS
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .push((record.level(), record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn captured_warnings_mentioning(needle: &str) -> usize {
+        CAPTURED_LOGS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .filter(|(level, message)| *level == log::Level::Warn && message.contains(needle))
+            .count()
+    }
+
+    #[test]
+    fn test_slow_transition_threshold_logs_warning() {
+        static LOGGER: CapturingLogger = CapturingLogger;
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Warn);
+
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("slow_tenant"))
+            .unwrap();
+        let slow = |state: &TenantApplicationState| {
+            std::thread::sleep(Duration::from_millis(50));
+            bump_version(state)
+        };
+
+        manager.apply_transition("slow_tenant", slow).unwrap();
+        assert_eq!(captured_warnings_mentioning("slow_tenant"), 0);
+
+        // Wide margins on both sides so scheduler jitter cannot flip either outcome
+        manager.set_slow_transition_threshold(Duration::from_millis(20));
+        manager.apply_transition("slow_tenant", slow).unwrap();
+        manager
+            .apply_transition("slow_tenant", bump_version)
+            .unwrap();
+        assert_eq!(captured_warnings_mentioning("slow_tenant"), 1);
+
+        manager.set_slow_transition_threshold(Duration::ZERO);
+        manager.apply_transition("slow_tenant", slow).unwrap();
+        assert_eq!(captured_warnings_mentioning("slow_tenant"), 1);
+    }
+
+    #[test]
+    fn test_compressed_snapshot_rollback_restores_state() {
+        let manager = ImmutableStateManager::new(100);
+        let defaults = (0..200)
+            .map(|i| (format!("key_{}", i), serde_json::json!({ "value": i })))
+            .collect::<HashMap<_, _>>();
+        manager
+            .initialize_tenant_with_defaults(create_test_tenant("cold"), defaults)
+            .unwrap();
+        manager
+            .cache_query_result(
+                "cold",
+                QueryResult {
+                    query_id: "q1".to_string(),
+                    data: vec![1, 2, 3],
+                    expires_at: chrono::Utc::now() + chrono::Duration::minutes(5),
+                },
+                10,
+            )
+            .unwrap();
+        let original = manager.get_tenant_state("cold").unwrap();
+
+        manager
+            .create_compressed_snapshot(
+                "cold",
+                Some("cold_checkpoint".to_string()),
+                "test".to_string(),
+                None,
+                vec![],
+            )
+            .unwrap();
+        manager.apply_transition("cold", bump_version).unwrap();
+        manager
+            .rollback_to_named_snapshot("cold", "cold_checkpoint")
+            .unwrap();
+
+        let restored = manager.get_tenant_state("cold").unwrap();
+        assert!(!Arc::ptr_eq(&original, &restored));
+        assert_eq!(restored.tenant.id, original.tenant.id);
+        assert_eq!(
+            restored.app_data.to_hashmap(),
+            original.app_data.to_hashmap()
+        );
+        assert_eq!(
+            restored.user_sessions.to_hashmap(),
+            original.user_sessions.to_hashmap()
+        );
+        assert_eq!(restored.query_cache.len(), 1);
+        assert_eq!(restored.query_cache.to_vec()[0].data, vec![1, 2, 3]);
+        assert_eq!(restored.last_updated, original.last_updated);
+    }
+
+    #[test]
+    fn test_apply_transition_rate_limited_skips_excess() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("flood"))
+            .unwrap();
+
+        let outcomes: Vec<bool> = (0..50)
+            .map(|_| {
+                manager
+                    .apply_transition_rate_limited("flood", 5, bump_version)
+                    .unwrap()
+            })
+            .collect();
+        let applied = outcomes.iter().filter(|applied| **applied).count();
+
+        // The bucket starts full, so the first burst is admitted and the rest skipped
+        assert!(outcomes[..5].iter().all(|applied| *applied));
+        assert!((5..50).contains(&applied));
+
+        let state = manager.get_tenant_state("flood").unwrap();
+        assert_eq!(
+            state.app_data.get(&"version".to_string()),
+            Some(&serde_json::json!(applied))
+        );
+        assert_eq!(
+            manager.get_metrics().unwrap().transition_count,
+            applied as u64
+        );
+    }
+
+    #[test]
+    fn test_apply_transition_rate_limited_rejects_unknown_tenant() {
+        let manager = ImmutableStateManager::new(100);
+
+        let result = manager.apply_transition_rate_limited("ghost", 5, bump_version);
+
+        assert!(matches!(result, Err(StateError::TenantNotFound(id)) if id == "ghost"));
+        assert!(ImmutableStateManager::read_recovering(&manager.rate_buckets).is_empty());
+    }
+
+    #[test]
+    fn test_estimate_tenant_memory_counts_shared_structure_once() {
+        let manager = ImmutableStateManager::new(100);
+        manager
+            .initialize_tenant(create_test_tenant("memory"))
+            .unwrap();
+
+        for i in 0..50 {
+            manager
+                .cache_query_result(
+                    "memory",
+                    QueryResult {
+                        query_id: format!("query_{}", i),
+                        data: vec![0u8; 4096],
+                        expires_at: Utc::now() + chrono::Duration::hours(1),
+                    },
+                    100,
+                )
+                .unwrap();
+        }
+
+        // Each snapshot differs only in app_data; the large query cache stays shared
+        for i in 0..20 {
+            manager
+                .apply_transition("memory", move |state| {
+                    let mut new_state = state.clone();
+                    new_state.app_data = state
+                        .app_data
+                        .insert("counter".to_string(), serde_json::json!(i));
+                    Ok(new_state)
+                })
+                .unwrap();
+            manager
+                .create_snapshot(
+                    "memory",
+                    Some(format!("snap_{}", i)),
+                    "test".to_string(),
+                    None,
+                    vec![],
+                )
+                .unwrap();
+        }
+
+        let report = manager.estimate_tenant_memory("memory").unwrap();
+        assert_eq!(report.snapshot_count, 20);
+        assert!(report.live_bytes >= 50 * 4096);
+
+        let histories = manager.snapshot_histories.read().unwrap();
+        let naive_bytes: usize = histories["memory"]
+            .snapshots
+            .iter()
+            .map(|snapshot| match &snapshot.state {
+                SnapshotState::Full(state) => {
+                    ImmutableStateManager::unseen_state_bytes(state, &mut HashSet::new())
+                }
+                SnapshotState::Compressed(compressed) => compressed.compressed_len(),
+            })
+            .sum();
+
+        assert!(report.shared_bytes >= report.live_bytes);
+        assert!(
+            report.shared_bytes * 10 < naive_bytes,
+            "shared estimate {} should be far below naive sum {}",
+            report.shared_bytes,
+            naive_bytes
+        );
+
+        assert!(manager.estimate_tenant_memory("missing").is_err());
+    }
 }
@@ -11,6 +11,8 @@ use std::hash::Hash;
 #[cfg(feature = "functional")]
 use itertools::Itertools;
 
+use crate::functional::response_transformers::escape_csv_field;
+
 #[cfg(feature = "performance_monitoring")]
 use crate::functional::performance_monitoring::{
     get_performance_monitor, Measurable, OperationType,
@@ -122,6 +124,59 @@ where
     }
 }
 
+/// Lazily pages through a database loader, fetching the next page only once the
+/// current one is drained.
+///
+/// An empty page ends the iteration; a loader error is yielded once and then ends it.
+struct QueryPager<T, L>
+where
+    L: FnMut() -> diesel::QueryResult<Vec<T>>,
+{
+    loader: L,
+    page: std::vec::IntoIter<T>,
+    exhausted: bool,
+}
+
+impl<T, L> QueryPager<T, L>
+where
+    L: FnMut() -> diesel::QueryResult<Vec<T>>,
+{
+    fn new(loader: L) -> Self {
+        Self {
+            loader,
+            page: Vec::new().into_iter(),
+            exhausted: false,
+        }
+    }
+}
+
+impl<T, L> Iterator for QueryPager<T, L>
+where
+    L: FnMut() -> diesel::QueryResult<Vec<T>>,
+{
+    type Item = diesel::QueryResult<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(row) = self.page.next() {
+                return Some(Ok(row));
+            }
+            if self.exhausted {
+                return None;
+            }
+
+            match (self.loader)() {
+                Ok(rows) if rows.is_empty() => self.exhausted = true,
+                Ok(rows) => self.page = rows.into_iter(),
+                Err(err) => {
+                    self.exhausted = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
 /// Extension trait to re-wrap any iterator back into an IteratorChain
 ///
 /// This trait provides a convenient way to recover IteratorChain functionality
@@ -190,6 +245,9 @@ impl Default for IteratorConfig {
     }
 }
 
+/// Pair yielded by [`IteratorChain::zip_longest`]; a side is `None` once it is exhausted
+pub type ZipLongestPair<A, B> = (Option<A>, Option<B>);
+
 /// Iterator chain with lazy evaluation support
 pub struct IteratorChain<T, I>
 where
@@ -238,6 +296,30 @@ where
         self
     }
 
+    /// Returns the operations recorded on this chain, oldest first.
+    pub fn operations(&self) -> &[String] {
+        &self.operations
+    }
+
+    /// Renders the recorded operations as a pipeline starting at the source.
+    ///
+    /// Nothing is evaluated; this only describes how the chain was composed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let chain = IteratorChain::new(vec![1, 2, 3].into_iter())
+    ///     .map(|x| x * 2)
+    ///     .filter(|x| *x > 2);
+    /// assert_eq!(chain.explain(), "source → map → filter");
+    /// ```
+    pub fn explain(&self) -> String {
+        std::iter::once("source")
+            .chain(self.operations.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" → ")
+    }
+
     /// Wraps any iterator back into an IteratorChain to regain access to custom methods.
     ///
     /// This is useful when you've used standard iterator methods (like `chain`, `take`, `skip`, etc.)
@@ -324,6 +406,33 @@ where
         }
     }
 
+    /// Flattens a chain of iterables into a chain of their items.
+    ///
+    /// Combined with `map`, this builds flat-map pipelines without leaving the chain.
+    /// Appends "flatten" to the chain's operations log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let flat = IteratorChain::new(vec![vec![1, 2], vec![3]].into_iter())
+    ///     .flatten()
+    ///     .collect();
+    /// assert_eq!(flat, vec![1, 2, 3]);
+    /// ```
+    pub fn flatten(self) -> IteratorChain<T::Item, std::iter::Flatten<I>>
+    where
+        T: IntoIterator,
+    {
+        let mut operations = self.operations;
+        operations.push("flatten".to_string());
+
+        IteratorChain {
+            iterator: self.iterator.flatten(),
+            config: self.config,
+            operations,
+        }
+    }
+
     /// Filters items in the chain using the provided predicate and returns a new chain with the filter operation recorded.
     ///
     /// The predicate is applied to a reference to each item; items for which the predicate returns `true` are retained.
@@ -351,6 +460,120 @@ where
         }
     }
 
+    /// Yields items while the predicate holds, stopping at the first item for which it returns `false`.
+    ///
+    /// Unlike `Iterator::take_while`, the result stays an `IteratorChain`, so chain-specific
+    /// methods remain available. Appends "take_while" to the chain's operations log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let taken = IteratorChain::new(vec![1, 2, 3, 4, 1].into_iter())
+    ///     .take_while(|&x| x < 3)
+    ///     .collect();
+    /// assert_eq!(taken, vec![1, 2]);
+    /// ```
+    pub fn take_while<F>(self, f: F) -> IteratorChain<T, std::iter::TakeWhile<I, F>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut operations = self.operations;
+        operations.push("take_while".to_string());
+
+        IteratorChain {
+            iterator: self.iterator.take_while(f),
+            config: self.config,
+            operations,
+        }
+    }
+
+    /// Skips items while the predicate holds, then yields every remaining item.
+    ///
+    /// The result stays an `IteratorChain` and "skip_while" is appended to the operations log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let rest = IteratorChain::new(vec![1, 2, 3, 4, 1].into_iter())
+    ///     .skip_while(|&x| x < 3)
+    ///     .collect();
+    /// assert_eq!(rest, vec![3, 4, 1]);
+    /// ```
+    pub fn skip_while<F>(self, f: F) -> IteratorChain<T, std::iter::SkipWhile<I, F>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut operations = self.operations;
+        operations.push("skip_while".to_string());
+
+        IteratorChain {
+            iterator: self.iterator.skip_while(f),
+            config: self.config,
+            operations,
+        }
+    }
+
+    /// Pairs each item with its position in the chain at this point, starting at zero.
+    ///
+    /// Unlike `Iterator::enumerate`, the result stays an `IteratorChain`, so indices can be
+    /// carried through later `filter`, `chunk_by` or `kmerge` steps. Appends "enumerate" to
+    /// the chain's operations log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let tagged = IteratorChain::new(vec!["a", "b", "c"].into_iter())
+    ///     .enumerate_chain()
+    ///     .filter(|(_, s)| *s != "b")
+    ///     .collect();
+    /// assert_eq!(tagged, vec![(0, "a"), (2, "c")]);
+    /// ```
+    pub fn enumerate_chain(self) -> IteratorChain<(usize, T), impl Iterator<Item = (usize, T)>> {
+        let mut operations = self.operations;
+        operations.push("enumerate".to_string());
+
+        IteratorChain {
+            iterator: self.iterator.enumerate(),
+            config: self.config,
+            operations,
+        }
+    }
+
+    /// Map each item to one CSV line built from the fields returned by `field_fn`.
+    ///
+    /// Fields containing commas, double quotes or line breaks are quoted and embedded quotes
+    /// doubled, following RFC 4180. Lines carry no trailing newline. Appends "to_csv_rows" to
+    /// the chain's operations log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let rows = IteratorChain::new(vec![("Acme, Inc", 3)].into_iter())
+    ///     .to_csv_rows(|(name, qty)| vec![name.to_string(), qty.to_string()])
+    ///     .collect();
+    /// assert_eq!(rows, vec!["\"Acme, Inc\",3".to_string()]);
+    /// ```
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_csv_rows<F>(self, field_fn: F) -> IteratorChain<String, impl Iterator<Item = String>>
+    where
+        F: Fn(&T) -> Vec<String>,
+    {
+        let mut operations = self.operations;
+        operations.push("to_csv_rows".to_string());
+
+        IteratorChain {
+            iterator: self.iterator.map(move |item| {
+                field_fn(&item)
+                    .iter()
+                    .map(|field| escape_csv_field(field))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }),
+            config: self.config,
+            operations,
+        }
+    }
+
     /// Group consecutive elements by a derived key, yielding `(key, Vec<items>)` for each contiguous run.
     ///
     /// The resulting `IteratorChain` produces one `(key, Vec<T>)` tuple for each sequence of adjacent
@@ -416,6 +639,35 @@ where
         }
     }
 
+    /// K-way merge this sorted iterator with any number of other sorted iterators
+    #[cfg(feature = "functional")]
+    pub fn kmerge_all<J>(self, others: Vec<J>) -> IteratorChain<T, impl Iterator<Item = T>>
+    where
+        T: Ord,
+        J: IntoIterator<Item = T>,
+        I: 'static,
+        <J as IntoIterator>::IntoIter: 'static,
+    {
+        let mut operations = self.operations;
+        operations.push("kmerge_all".to_string());
+
+        let mut iterators: Vec<Box<dyn Iterator<Item = T>>> = Vec::with_capacity(others.len() + 1);
+        iterators.push(Box::new(self.iterator));
+        iterators.extend(
+            others
+                .into_iter()
+                .map(|other| Box::new(other.into_iter()) as Box<dyn Iterator<Item = T>>),
+        );
+
+        let merged = iterators.into_iter().kmerge();
+
+        IteratorChain {
+            iterator: merged,
+            config: self.config,
+            operations,
+        }
+    }
+
     /// Lockstep iteration over multiple iterators (zip all with equal lengths)
     #[cfg(feature = "functional")]
     pub fn lockstep_zip<J>(
@@ -531,6 +783,41 @@ where
         }
     }
 
+    /// Zips with another iterator without truncating to the shorter of the two.
+    ///
+    /// Once one side is exhausted, its position in each pair is `None` until the
+    /// other side ends. Appends "zip_longest" to the chain's operations log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let pairs = IteratorChain::new(vec![1, 2, 3].into_iter())
+    ///     .zip_longest(vec![10, 20])
+    ///     .collect();
+    /// assert_eq!(pairs, vec![(Some(1), Some(10)), (Some(2), Some(20)), (Some(3), None)]);
+    /// ```
+    #[cfg(feature = "functional")]
+    pub fn zip_longest<J>(
+        self,
+        other: J,
+    ) -> IteratorChain<ZipLongestPair<T, J::Item>, impl Iterator<Item = ZipLongestPair<T, J::Item>>>
+    where
+        J: IntoIterator,
+    {
+        let mut operations = self.operations;
+        operations.push("zip_longest".to_string());
+
+        let zipped = self
+            .iterator
+            .zip_longest(other)
+            .map(|pair| pair.left_and_right());
+        IteratorChain {
+            iterator: zipped,
+            config: self.config,
+            operations,
+        }
+    }
+
     /// Partition the iterator into two collections based on a predicate
     ///
     /// Items for which the predicate returns `true` are placed in the first collection,
@@ -585,6 +872,69 @@ where
         (chain_true, chain_false)
     }
 
+    /// Duplicates the chain into two independent chains over the same items.
+    ///
+    /// This is eager: the upstream iterator is drained once into a buffer, so upstream
+    /// work is never recomputed, and each branch iterates its own copy of the buffer.
+    /// Appends "tee" to the operations log of both chains.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let (left, right) = IteratorChain::new(vec![1, 2, 3].into_iter()).tee();
+    /// assert_eq!(left.collect(), vec![1, 2, 3]);
+    /// assert_eq!(right.map(|x| x * 2).collect(), vec![2, 4, 6]);
+    /// ```
+    pub fn tee(
+        self,
+    ) -> (
+        IteratorChain<T, impl Iterator<Item = T>>,
+        IteratorChain<T, impl Iterator<Item = T>>,
+    )
+    where
+        T: Clone,
+    {
+        let mut operations = self.operations;
+        operations.push("tee".to_string());
+
+        let buffered: Vec<T> = self.iterator.collect();
+
+        let first = IteratorChain {
+            iterator: buffered.clone().into_iter(),
+            config: self.config.clone(),
+            operations: operations.clone(),
+        };
+
+        let second = IteratorChain {
+            iterator: buffered.into_iter(),
+            config: self.config,
+            operations,
+        };
+
+        (first, second)
+    }
+
+    /// Groups the items into owned batches of `batch_size`, the last one possibly shorter.
+    ///
+    /// Batches are filled lazily from the underlying iterator, so at most one batch is
+    /// held in memory at a time. A `batch_size` of 0 is treated as 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let batches: Vec<Vec<i32>> = IteratorChain::new(1..=5).into_batches(2).collect();
+    /// assert_eq!(batches, vec![vec![1, 2], vec![3, 4], vec![5]]);
+    /// ```
+    pub fn into_batches(self, batch_size: usize) -> impl Iterator<Item = Vec<T>> {
+        let batch_size = batch_size.max(1);
+        let mut iterator = self.iterator;
+
+        std::iter::from_fn(move || {
+            let batch: Vec<T> = iterator.by_ref().take(batch_size).collect();
+            (!batch.is_empty()).then_some(batch)
+        })
+    }
+
     /// Collects all items from the chain into a `Vec`.
     ///
     /// Returns a `Vec<T>` containing every item produced by the chain's iterator.
@@ -677,8 +1027,79 @@ where
         self.iterator.count()
     }
 
+    /// Returns the `n` most frequent items with their occurrence counts.
+    ///
+    /// Results are sorted by descending count; items with equal counts keep the
+    /// order of their first appearance in the chain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let chain = IteratorChain::new(vec!["a", "a", "b", "c", "c", "c"].into_iter());
+    /// assert_eq!(chain.most_common(2), vec![("c", 3), ("a", 2)]);
+    /// ```
+    pub fn most_common(self, n: usize) -> Vec<(T, usize)>
+    where
+        T: Eq + Hash,
+    {
+        // Each distinct item maps to (first appearance, count)
+        let mut tally: HashMap<T, (usize, usize)> = HashMap::new();
+        for (position, item) in self.iterator.enumerate() {
+            tally.entry(item).or_insert((position, 0)).1 += 1;
+        }
+
+        let mut ranked: Vec<(T, usize, usize)> = tally
+            .into_iter()
+            .map(|(item, (first_seen, count))| (item, first_seen, count))
+            .collect();
+        ranked.sort_by(|a, b| b.2.cmp(&a.2).then(a.1.cmp(&b.1)));
+
+        ranked
+            .into_iter()
+            .take(n)
+            .map(|(item, _, count)| (item, count))
+            .collect()
+    }
+
+    /// Groups items by `key_fn` and folds each group in a single pass.
+    ///
+    /// Each new key starts from `init()` and every item is folded into its group's
+    /// accumulator immediately, so no intermediate group vectors are built.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let chain = IteratorChain::new(vec![1, 2, 3, 4, 5].into_iter());
+    /// let sums = chain.group_and_aggregate(|&x| x % 2, || 0, |acc, &x| acc + x);
+    /// assert_eq!(sums[&1], 9);
+    /// assert_eq!(sums[&0], 6);
+    /// ```
+    pub fn group_and_aggregate<K, A, KeyFn, InitFn, FoldFn>(
+        self,
+        key_fn: KeyFn,
+        init: InitFn,
+        fold: FoldFn,
+    ) -> HashMap<K, A>
+    where
+        K: Eq + Hash,
+        KeyFn: Fn(&T) -> K,
+        InitFn: Fn() -> A,
+        FoldFn: Fn(A, &T) -> A,
+    {
+        let mut groups: HashMap<K, A> = HashMap::new();
+        for item in self.iterator {
+            let key = key_fn(&item);
+            let acc = groups.remove(&key).unwrap_or_else(&init);
+            groups.insert(key, fold(acc, &item));
+        }
+        groups
+    }
+
     /// Reduces the iterator's items into a single value by applying an accumulator function.
     ///
+    /// With the `performance_monitoring` feature enabled, the fold is recorded as an
+    /// `OperationType::IteratorChain` measurement.
+    ///
     /// # Returns
     ///
     /// The final accumulated value after processing all items.
@@ -694,12 +1115,31 @@ where
     where
         F: FnMut(B, T) -> B,
     {
-        self.iterator.fold(init, f)
+        #[cfg(feature = "performance_monitoring")]
+        {
+            let start = std::time::Instant::now();
+
+            let result = self.iterator.fold(init, f);
+
+            get_performance_monitor().record_operation(
+                OperationType::IteratorChain,
+                start.elapsed(),
+                std::mem::size_of::<B>() as u64,
+                false,
+            );
+
+            result
+        }
+        #[cfg(not(feature = "performance_monitoring"))]
+        {
+            self.iterator.fold(init, f)
+        }
     }
 
     /// Reduces the iterator's items into a single value by applying an accumulator function.
     ///
-    /// This is similar to fold, but uses the first element as the initial value.
+    /// This is similar to fold, but uses the first element as the initial value, and is
+    /// recorded the same way when `performance_monitoring` is enabled.
     ///
     /// # Returns
     ///
@@ -716,7 +1156,25 @@ where
     where
         F: FnMut(T, T) -> T,
     {
-        self.iterator.reduce(f)
+        #[cfg(feature = "performance_monitoring")]
+        {
+            let start = std::time::Instant::now();
+
+            let result = self.iterator.reduce(f);
+
+            get_performance_monitor().record_operation(
+                OperationType::IteratorChain,
+                start.elapsed(),
+                std::mem::size_of::<Option<T>>() as u64,
+                false,
+            );
+
+            result
+        }
+        #[cfg(not(feature = "performance_monitoring"))]
+        {
+            self.iterator.reduce(f)
+        }
     }
 
     /// Accumulate items into a collection using a custom accumulator function
@@ -946,6 +1404,49 @@ impl IteratorEngine {
         self.from_iter(vec.into_iter())
     }
 
+    /// Creates an `IteratorChain` that streams rows from a paged database `loader`.
+    ///
+    /// `loader` is called for the next page only when the chain has consumed the previous
+    /// one, and an empty page marks the end. A loader error is logged and ends the chain;
+    /// use [`try_from_query`](Self::try_from_query) to receive it instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut offset = 0;
+    /// let rows = engine
+    ///     .from_query(|| {
+    ///         let page = nfe_documents.limit(100).offset(offset).load::<NfeDocument>(conn);
+    ///         offset += 100;
+    ///         page
+    ///     })
+    ///     .filter(|doc| doc.status == "autorizada")
+    ///     .collect();
+    /// ```
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_query<T, L>(&self, loader: L) -> IteratorChain<T, impl Iterator<Item = T>>
+    where
+        L: FnMut() -> diesel::QueryResult<Vec<T>>,
+    {
+        self.from_iter(QueryPager::new(loader).map_while(|row| {
+            row.map_err(|err| log::error!("Query source terminated: {}", err))
+                .ok()
+        }))
+    }
+
+    /// Like [`from_query`](Self::from_query), but yields each row as `Ok` and a loader
+    /// error as a final `Err` item.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn try_from_query<T, L>(
+        &self,
+        loader: L,
+    ) -> IteratorChain<diesel::QueryResult<T>, impl Iterator<Item = diesel::QueryResult<T>>>
+    where
+        L: FnMut() -> diesel::QueryResult<Vec<T>>,
+    {
+        self.from_iter(QueryPager::new(loader))
+    }
+
     /// Applies `transform` to each element of `data` by reference and returns a `Vec` of the results.
     ///
     /// This function borrows each input (`&T`) so elements are not cloned; when the engine's
@@ -1073,6 +1574,82 @@ mod tests {
         assert_eq!(products, vec![(1, 3), (1, 4), (2, 3), (2, 4)]);
     }
 
+    #[test]
+    fn test_zip_longest_keeps_unmatched_items() {
+        let engine = IteratorEngine::new();
+        let chain = engine.from_vec(vec![1, 2, 3]).zip_longest(vec![10, 20]);
+
+        assert_eq!(chain.operations, vec!["zip_longest".to_string()]);
+        assert_eq!(
+            chain.collect(),
+            vec![(Some(1), Some(10)), (Some(2), Some(20)), (Some(3), None)]
+        );
+    }
+
+    #[test]
+    fn test_tee_yields_independent_branches() {
+        let engine = IteratorEngine::new();
+        let (left, right) = engine.from_vec(vec![1, 2, 3, 4]).map(|x| x * 10).tee();
+
+        assert_eq!(left.operations, vec!["map".to_string(), "tee".to_string()]);
+        assert_eq!(right.operations, left.operations);
+
+        let evens = right.filter(|x| x % 20 == 0).collect();
+        assert_eq!(evens, vec![20, 40]);
+        assert_eq!(left.collect(), vec![10, 20, 30, 40]);
+    }
+
+    #[test]
+    fn test_most_common_orders_by_count_then_first_appearance() {
+        let engine = IteratorEngine::new();
+
+        let top = engine
+            .from_vec(vec!["a", "a", "b", "c", "c", "c"])
+            .most_common(2);
+        assert_eq!(top, vec![("c", 3), ("a", 2)]);
+
+        let tied = engine.from_vec(vec![3, 1, 2, 1, 3, 2]).most_common(5);
+        assert_eq!(tied, vec![(3, 2), (1, 2), (2, 2)]);
+        assert!(engine.from_vec(vec![1, 2]).most_common(0).is_empty());
+    }
+
+    #[test]
+    fn test_into_batches_yields_fixed_size_batches() {
+        let engine = IteratorEngine::new();
+
+        let sizes: Vec<usize> = engine
+            .from_iter(1..=10)
+            .into_batches(4)
+            .map(|batch| batch.len())
+            .collect();
+        assert_eq!(sizes, vec![4, 4, 2]);
+
+        let batches: Vec<Vec<i32>> = engine.from_vec(vec![1, 2]).into_batches(0).collect();
+        assert_eq!(batches, vec![vec![1], vec![2]]);
+        assert!(engine
+            .from_vec(Vec::<i32>::new())
+            .into_batches(3)
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn test_group_and_aggregate_sums_by_parity() {
+        let engine = IteratorEngine::new();
+
+        let sums = engine
+            .from_vec(vec![1, 2, 3, 4, 5, 6, 7])
+            .group_and_aggregate(|&x| x % 2 == 0, || 0, |acc, &x| acc + x);
+
+        assert_eq!(sums.len(), 2);
+        assert_eq!(sums[&true], 12);
+        assert_eq!(sums[&false], 16);
+        assert!(engine
+            .from_vec(Vec::<i32>::new())
+            .group_and_aggregate(|&x| x, || 0, |acc, _| acc + 1)
+            .is_empty());
+    }
+
     #[test]
     fn test_zero_copy_processing() {
         let engine = IteratorEngine::new();
@@ -1083,6 +1660,161 @@ mod tests {
         assert_eq!(result, vec![2, 4, 6, 8, 10]);
     }
 
+    #[test]
+    fn test_take_while_preserves_chain() {
+        let engine = IteratorEngine::new();
+        let chain = engine.from_vec(vec![1, 2, 3, 4, 1]).take_while(|&x| x < 3);
+
+        assert_eq!(chain.operations, vec!["take_while".to_string()]);
+        assert_eq!(chain.collect(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_flatten_nested_vectors() {
+        let engine = IteratorEngine::new();
+        let chain = engine
+            .from_vec(vec![vec![1, 2], vec![3], vec![4, 5]])
+            .flatten();
+
+        assert!(chain.operations.contains(&"flatten".to_string()));
+        assert_eq!(chain.collect(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_fold_and_reduce_terminals() {
+        let engine = IteratorEngine::new();
+
+        let sum = engine.from_vec(vec![1, 2, 3, 4]).fold(0, |acc, x| acc + x);
+        assert_eq!(sum, 10);
+
+        let max = engine
+            .from_vec(vec![3, 9, 2, 7])
+            .reduce(|a, b| if b > a { b } else { a });
+        assert_eq!(max, Some(9));
+        assert_eq!(
+            engine.from_vec(Vec::<i32>::new()).reduce(|a, b| a + b),
+            None
+        );
+
+        #[cfg(feature = "performance_monitoring")]
+        {
+            let metrics = get_performance_monitor()
+                .get_metrics(&OperationType::IteratorChain)
+                .expect("fold/reduce should record IteratorChain metrics");
+            assert!(metrics.operation_count >= 1);
+        }
+    }
+
+    #[test]
+    fn test_skip_while_preserves_chain() {
+        let engine = IteratorEngine::new();
+        let chain = engine
+            .from_vec(vec![1, 2, 3, 4, 1])
+            .skip_while(|&x| x < 3)
+            .map(|x| x * 10);
+
+        assert_eq!(
+            chain.operations,
+            vec!["skip_while".to_string(), "map".to_string()]
+        );
+        assert_eq!(chain.collect(), vec![30, 40, 10]);
+    }
+
+    #[test]
+    fn test_enumerate_chain_indices_survive_filter() {
+        let engine = IteratorEngine::new();
+        let chain = engine
+            .from_vec(vec![10, 15, 20, 25, 30])
+            .enumerate_chain()
+            .filter(|(_, value)| value % 10 == 0);
+
+        assert_eq!(
+            chain.operations,
+            vec!["enumerate".to_string(), "filter".to_string()]
+        );
+        assert_eq!(chain.collect(), vec![(0, 10), (2, 20), (4, 30)]);
+    }
+
+    #[test]
+    fn test_explain_lists_operations_in_order() {
+        let engine = IteratorEngine::new();
+        assert_eq!(engine.from_vec(vec![1]).explain(), "source");
+
+        let chain = engine
+            .from_vec(vec![1, 3, 5, 8])
+            .map(|x| x * 2)
+            .filter(|x| x % 3 != 0)
+            .kmerge(vec![4, 12]);
+
+        assert_eq!(chain.operations(), ["map", "filter", "kmerge"]);
+        assert_eq!(chain.explain(), "source → map → filter → kmerge");
+        assert_eq!(chain.collect(), vec![2, 4, 10, 12, 16]);
+    }
+
+    #[test]
+    fn test_from_query_pages_lazily() {
+        let engine = IteratorEngine::new();
+        let mut pages = vec![vec![1, 2, 3], vec![4, 5, 6], vec![]].into_iter();
+        let calls = std::cell::Cell::new(0);
+        let loader = || {
+            calls.set(calls.get() + 1);
+            Ok(pages.next().unwrap_or_default())
+        };
+
+        let mut chain = engine.from_query(loader).filter(|row| row % 2 == 0);
+        assert_eq!(calls.get(), 0);
+        assert_eq!(chain.next(), Some(2));
+        assert_eq!(calls.get(), 1);
+
+        assert_eq!(chain.collect(), vec![4, 6]);
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_from_query_stops_at_loader_error() {
+        let engine = IteratorEngine::new();
+        let page_loader = || {
+            let mut pages = vec![
+                Ok(vec![1, 2]),
+                Err(diesel::result::Error::NotFound),
+                Ok(vec![3]),
+            ]
+            .into_iter();
+            move || pages.next().unwrap_or(Ok(Vec::new()))
+        };
+
+        assert_eq!(engine.from_query(page_loader()).collect(), vec![1, 2]);
+
+        let rows = engine.try_from_query(page_loader()).collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], Ok(1));
+        assert_eq!(rows[1], Ok(2));
+        assert_eq!(rows[2], Err(diesel::result::Error::NotFound));
+    }
+
+    #[test]
+    fn test_to_csv_rows_escapes_special_characters() {
+        let records = vec![
+            ("plain", "Widget", 3),
+            ("comma", "Bolts, assorted", 10),
+            ("quote", "12\" \"heavy\" pipe", 1),
+            ("newline", "line one\nline two", 2),
+        ];
+        let chain = IteratorChain::new(records.into_iter())
+            .to_csv_rows(|(id, name, qty)| vec![id.to_string(), name.to_string(), qty.to_string()]);
+
+        assert_eq!(chain.operations, vec!["to_csv_rows".to_string()]);
+        assert_eq!(
+            chain.collect(),
+            vec![
+                "plain,Widget,3".to_string(),
+                "comma,\"Bolts, assorted\",10".to_string(),
+                "quote,\"12\"\" \"\"heavy\"\" pipe\",1".to_string(),
+                "newline,\"line one\nline two\",2".to_string(),
+            ]
+        );
+    }
+
     #[cfg(feature = "functional")]
     mod functional_more_tests {
         use super::*;
@@ -1341,6 +2073,23 @@ mod tests {
             }
         }
 
+        #[test]
+        fn test_kmerge_all_merges_many_sorted_streams() {
+            let engine = IteratorEngine::new();
+            let serie_1 = vec![1, 5, 9, 13];
+            let serie_2 = vec![2, 6, 10];
+            let serie_3 = vec![0, 3, 7, 11, 15];
+            let serie_4 = vec![4, 8, 12, 14];
+
+            let chain = engine
+                .from_vec(serie_1)
+                .kmerge_all(vec![serie_2, serie_3, serie_4]);
+
+            assert_eq!(chain.operations, vec!["kmerge_all".to_string()]);
+            let merged: Vec<i32> = chain.collect();
+            assert_eq!(merged, (0..16).collect::<Vec<i32>>());
+        }
+
         // Comprehensive tests for lockstep_zip
         #[test]
         fn test_lockstep_zip_with_empty_iterators() {
@@ -14,10 +14,15 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
 
+use crate::functional::immutable_state::PersistentVector;
+use crate::functional::validation_rules::ValidationError;
 use log;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, Instant};
 
@@ -70,6 +75,30 @@ fn record_performance(operation_key: String, entry: PerformanceEntry) {
     entries.retain(|entry| entry.timestamp > one_hour_ago);
 }
 
+/// Clear all recorded performance history used for adaptive chunk sizing.
+///
+/// Useful for long-running servers and for getting consistent benchmark runs.
+pub fn clear_performance_history() {
+    let history = get_performance_history();
+    match history.write() {
+        Ok(mut map) => map.clear(),
+        Err(_) => log::warn!("Performance history lock was poisoned, skipping clear"),
+    };
+}
+
+/// Total number of recorded performance entries across all operation keys.
+pub fn performance_history_len() -> usize {
+    let history = get_performance_history();
+    let len = match history.read() {
+        Ok(map) => map.values().map(Vec::len).sum(),
+        Err(_) => {
+            log::warn!("Performance history lock was poisoned, reporting empty history");
+            0
+        }
+    };
+    len
+}
+
 /// Calculate optimal chunk size based on performance history
 fn calculate_adaptive_chunk_size(
     operation_key: &str,
@@ -122,6 +151,80 @@ fn calculate_adaptive_chunk_size(
     }
 }
 
+/// Build the performance history key for an operation over element type `T`.
+///
+/// Keys are `"{operation}:{type}"`, prefixed with `"{namespace}:"` when the config
+/// sets a `history_namespace`, so isolated workloads keep separate statistics.
+fn performance_history_key<T>(config: &ParallelConfig, operation: &str) -> String {
+    match &config.history_namespace {
+        Some(namespace) => format!("{}:{}:{}", namespace, operation, std::any::type_name::<T>()),
+        None => format!("{}:{}", operation, std::any::type_name::<T>()),
+    }
+}
+
+/// Run `work` with a cancellation flag raised by a watchdog once `timeout` elapses.
+///
+/// The watchdog is a dedicated scoped thread rather than a rayon task so it never
+/// competes with the work for pool threads. `work` is expected to check the flag
+/// before each element; without a timeout the flag simply stays `false`.
+fn run_with_watchdog<R>(timeout: Option<Duration>, work: impl FnOnce(&AtomicBool) -> R) -> R {
+    let cancelled = AtomicBool::new(false);
+    let Some(timeout) = timeout else {
+        return work(&cancelled);
+    };
+
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    let flag = &cancelled;
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            if let Err(RecvTimeoutError::Timeout) = done_rx.recv_timeout(timeout) {
+                flag.store(true, Ordering::Relaxed);
+            }
+        });
+        let result = work(flag);
+        // Disconnecting the channel releases the watchdog immediately
+        drop(done_tx);
+        result
+    })
+}
+
+/// Map `data` in chunks of `chunk_size`, timing each chunk individually.
+///
+/// Returns the mapped data in input order, or `None` if `cancelled` was raised
+/// before every element ran, together with one elapsed time per chunk.
+fn map_timed_chunks<T, U, F>(
+    data: Vec<T>,
+    chunk_size: usize,
+    cancelled: &AtomicBool,
+    f: &F,
+) -> (Option<Vec<U>>, Vec<Duration>)
+where
+    T: Send,
+    U: Send,
+    F: Fn(T) -> U + Send + Sync,
+{
+    let chunks: Vec<(Option<Vec<U>>, Duration)> = data
+        .into_par_iter()
+        .chunks(chunk_size)
+        .map(|chunk| {
+            let chunk_start = Instant::now();
+            let mapped: Option<Vec<U>> = chunk
+                .into_iter()
+                .map(|item| (!cancelled.load(Ordering::Relaxed)).then(|| f(item)))
+                .collect();
+            (mapped, chunk_start.elapsed())
+        })
+        .collect();
+
+    let timings = chunks.iter().map(|(_, elapsed)| *elapsed).collect();
+    let completed = chunks
+        .into_iter()
+        .map(|(mapped, _)| mapped)
+        .collect::<Option<Vec<_>>>()
+        .map(|mapped| mapped.into_iter().flatten().collect());
+    (completed, timings)
+}
+
 /// Dynamic load balancer for optimizing parallel execution
 #[derive(Debug, Clone)]
 pub struct DynamicLoadBalancer {
@@ -266,6 +369,20 @@ pub struct ParallelConfig {
     pub adaptive_chunk_sizing: bool,
     /// Maximum chunk size for adaptive sizing
     pub max_chunk_size: usize,
+    /// Namespace for performance history keys (None = shared global history)
+    pub history_namespace: Option<String>,
+    /// Deadline for the parallel paths of `par_map`, `par_filter`, `par_fold`,
+    /// `par_flat_map`, `par_find` and `par_count` (None = no limit)
+    ///
+    /// Once it elapses, elements not yet started are skipped and the operation returns
+    /// empty data (`init` for `par_fold`, `None` for `par_find`, `0` for `par_count`) with
+    /// `ParallelMetrics::timed_out` set. Element closures that are already running cannot
+    /// be interrupted, so the call returns after they finish. A match `par_find` found
+    /// before the deadline is still returned.
+    pub timeout: Option<Duration>,
+    /// Record the elapsed time of every chunk processed by the parallel path of `par_map`
+    /// in `ParallelMetrics::chunk_timings`, for profiling tail latency
+    pub collect_chunk_timings: bool,
 }
 
 impl Default for ParallelConfig {
@@ -277,13 +394,128 @@ impl Default for ParallelConfig {
             chunk_size: 1024,
             adaptive_chunk_sizing: true,
             max_chunk_size: 8192,
+            history_namespace: None,
+            timeout: None,
+            collect_chunk_timings: false,
+        }
+    }
+}
+
+impl ParallelConfig {
+    /// Starts a [`ParallelConfigBuilder`] from the default configuration.
+    pub fn builder() -> ParallelConfigBuilder {
+        ParallelConfigBuilder::new()
+    }
+}
+
+/// Chainable, validating constructor for [`ParallelConfig`]
+///
+/// Unset options keep their `ParallelConfig::default()` values.
+///
+/// # Examples
+///
+/// ```
+/// let config = ParallelConfig::builder()
+///     .min_parallel_size(10_000)
+///     .chunk_size(2048)
+///     .max_chunk_size(4096)
+///     .build()
+///     .unwrap();
+/// assert_eq!(config.chunk_size, 2048);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ParallelConfigBuilder {
+    config: ParallelConfig,
+}
+
+impl ParallelConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Minimum dataset size before the parallel path is used.
+    pub fn min_parallel_size(mut self, size: usize) -> Self {
+        self.config.min_parallel_size = size;
+        self
+    }
+
+    /// Buffer size for chunked operations.
+    pub fn chunk_size(mut self, size: usize) -> Self {
+        self.config.chunk_size = size;
+        self
+    }
+
+    /// Enables or disables adaptive chunk sizing.
+    pub fn adaptive(mut self, enabled: bool) -> Self {
+        self.config.adaptive_chunk_sizing = enabled;
+        self
+    }
+
+    /// Upper bound for adaptive chunk sizing.
+    pub fn max_chunk_size(mut self, size: usize) -> Self {
+        self.config.max_chunk_size = size;
+        self
+    }
+
+    /// Thread pool size hint (0 = automatic).
+    pub fn thread_pool_size(mut self, size: usize) -> Self {
+        self.config.thread_pool_size = size;
+        self
+    }
+
+    /// Enables or disables the work-stealing optimization.
+    pub fn work_stealing(mut self, enabled: bool) -> Self {
+        self.config.enable_work_stealing = enabled;
+        self
+    }
+
+    /// Namespace isolating this configuration's performance history.
+    pub fn history_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.config.history_namespace = Some(namespace.into());
+        self
+    }
+
+    /// Deadline for the parallel paths, see [`ParallelConfig::timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = Some(timeout);
+        self
+    }
+
+    /// Enables or disables per-chunk timing, see [`ParallelConfig::collect_chunk_timings`].
+    pub fn collect_chunk_timings(mut self, enabled: bool) -> Self {
+        self.config.collect_chunk_timings = enabled;
+        self
+    }
+
+    /// Validates the options and returns the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `chunk_size` or `max_chunk_size` is zero, or if
+    /// `chunk_size` exceeds `max_chunk_size`.
+    pub fn build(self) -> Result<ParallelConfig, String> {
+        let config = self.config;
+
+        if config.chunk_size == 0 {
+            return Err("chunk_size must be greater than zero".to_string());
         }
+        if config.max_chunk_size == 0 {
+            return Err("max_chunk_size must be greater than zero".to_string());
+        }
+        if config.chunk_size > config.max_chunk_size {
+            return Err(format!(
+                "chunk_size ({}) must not exceed max_chunk_size ({})",
+                config.chunk_size, config.max_chunk_size
+            ));
+        }
+
+        Ok(config)
     }
 }
 
 /// Note: These metrics are not yet collected and will contain default values.
 /// Work-stealing performance metrics
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct WorkStealingMetrics {
     /// Number of tasks stolen by threads
     pub tasks_stolen: u64,
@@ -297,7 +529,7 @@ pub struct WorkStealingMetrics {
 
 /// Note: These metrics are not yet collected and will contain default values.
 /// Load balancing metrics for detailed performance analysis
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LoadBalancingMetrics {
     /// Average work per thread
     pub avg_work_per_thread: f64,
@@ -312,7 +544,7 @@ pub struct LoadBalancingMetrics {
 }
 
 /// Performance metrics for parallel operations
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ParallelMetrics {
     /// Total processing time
     pub total_time: Duration,
@@ -328,6 +560,11 @@ pub struct ParallelMetrics {
     pub work_stealing_metrics: WorkStealingMetrics,
     /// Detailed load balancing metrics
     pub load_balancing_metrics: LoadBalancingMetrics,
+    /// Whether `ParallelConfig::timeout` elapsed before every element was processed
+    pub timed_out: bool,
+    /// Elapsed time of each chunk, in input order; empty unless
+    /// `ParallelConfig::collect_chunk_timings` is set
+    pub chunk_timings: Vec<Duration>,
 }
 
 /// Parallel iterator extension trait for functional programming
@@ -339,6 +576,7 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
     /// The method collects the iterator into a vector, chooses between a sequential or Rayon-backed
     /// parallel execution based on `config.min_parallel_size`, and records timing, thread usage,
     /// throughput, memory estimate, and a simple efficiency heuristic in the returned `ParallelResult`.
+    /// The parallel path honours `config.timeout` (see [`ParallelConfig::timeout`]).
     ///
     /// # Examples
     ///
@@ -371,6 +609,8 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
                 efficiency: 1.0,
                 work_stealing_metrics: WorkStealingMetrics::default(),
                 load_balancing_metrics: LoadBalancingMetrics::default(),
+                timed_out: false,
+                chunk_timings: Vec::new(),
             };
             return ParallelResult {
                 data: result,
@@ -381,7 +621,7 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
         // Parallel processing for large datasets
         let base_chunk_size = config.chunk_size.max(1);
         let chunk_size = if config.adaptive_chunk_sizing {
-            let operation_key = format!("{}:{}", "par_map", std::any::type_name::<T>());
+            let operation_key = performance_history_key::<T>(config, "par_map");
             calculate_adaptive_chunk_size(
                 &operation_key,
                 data_len,
@@ -393,12 +633,20 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
             base_chunk_size
         };
 
-        let result: Vec<U> = data
-            .into_par_iter()
-            .with_min_len(chunk_size)
-            .with_max_len(chunk_size * 4)
-            .map(f)
-            .collect();
+        let (completed, chunk_timings) = run_with_watchdog(config.timeout, |cancelled| {
+            if config.collect_chunk_timings {
+                return map_timed_chunks(data, chunk_size, cancelled, &f);
+            }
+            let completed: Option<Vec<U>> = data
+                .into_par_iter()
+                .with_min_len(chunk_size)
+                .with_max_len(chunk_size * 4)
+                .map(|item| (!cancelled.load(Ordering::Relaxed)).then(|| f(item)))
+                .collect();
+            (completed, Vec::new())
+        });
+        let timed_out = completed.is_none();
+        let result = completed.unwrap_or_default();
 
         let elapsed = start_time.elapsed();
         let thread_count = rayon::current_num_threads();
@@ -422,11 +670,13 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
             efficiency,
             work_stealing_metrics: WorkStealingMetrics::default(),
             load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out,
+            chunk_timings,
         };
 
         // Record performance for adaptive chunk sizing
         if config.adaptive_chunk_sizing {
-            let operation_key = format!("{}:{}", "par_map", std::any::type_name::<T>());
+            let operation_key = performance_history_key::<T>(config, "par_map");
             let entry = PerformanceEntry {
                 chunk_size,
                 data_size: data_len,
@@ -447,6 +697,7 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
     /// Performs a fold (reduction) over the iterator, using `fold` per item and `combine` to merge partial results.
     ///
     /// Chooses a sequential fold when the collected input length is less than `config.min_parallel_size`; otherwise it performs a parallel fold and reduction using Rayon. The returned `ParallelResult` includes the folded value and `ParallelMetrics` (total time, thread count, throughput, memory usage, and an efficiency heuristic).
+    /// The parallel path honours `config.timeout` (see [`ParallelConfig::timeout`]).
     ///
     /// # Examples
     ///
@@ -490,6 +741,8 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
                 efficiency: 1.0,
                 work_stealing_metrics: WorkStealingMetrics::default(),
                 load_balancing_metrics: LoadBalancingMetrics::default(),
+                timed_out: false,
+                chunk_timings: Vec::new(),
             };
             return ParallelResult {
                 data: result,
@@ -498,10 +751,24 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
         }
 
         // Parallel fold with combiner
-        let result = data
-            .into_par_iter()
-            .fold(|| init.clone(), fold)
-            .reduce(|| init.clone(), combine);
+        let skipped = AtomicBool::new(false);
+        let folded = run_with_watchdog(config.timeout, |cancelled| {
+            data.into_par_iter()
+                .fold(
+                    || init.clone(),
+                    |acc, item| {
+                        if cancelled.load(Ordering::Relaxed) {
+                            skipped.store(true, Ordering::Relaxed);
+                            acc
+                        } else {
+                            fold(acc, item)
+                        }
+                    },
+                )
+                .reduce(|| init.clone(), combine)
+        });
+        let timed_out = skipped.load(Ordering::Relaxed);
+        let result = if timed_out { init } else { folded };
 
         let elapsed = start_time.elapsed();
         let thread_count = rayon::current_num_threads();
@@ -518,6 +785,8 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
             efficiency,
             work_stealing_metrics: WorkStealingMetrics::default(),
             load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out,
+            chunk_timings: Vec::new(),
         };
 
         ParallelResult {
@@ -529,6 +798,7 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
     /// Filters elements using `predicate`, processing in parallel when the dataset exceeds the configured threshold, and preserves the original input order.
     ///
     /// If the collected input length is less than `config.min_parallel_size`, the function performs a sequential filter; otherwise it performs a parallel filter. The predicate is applied to references to elements (`&T`). The returned `ParallelResult` includes both the filtered `Vec<T>` and operation metrics (timing, thread count, throughput, memory usage, efficiency).
+    /// The parallel path honours `config.timeout` (see [`ParallelConfig::timeout`]).
     ///
     /// # Examples
     ///
@@ -561,6 +831,8 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
                 efficiency: 1.0,
                 work_stealing_metrics: WorkStealingMetrics::default(),
                 load_balancing_metrics: LoadBalancingMetrics::default(),
+                timed_out: false,
+                chunk_timings: Vec::new(),
             };
             return ParallelResult {
                 data: result,
@@ -570,10 +842,24 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
 
         // Parallel filter with temporary indices to preserve order
         let indexed: Vec<(usize, T)> = data.into_iter().enumerate().collect();
-        let mut filtered: Vec<(usize, T)> = indexed
-            .into_par_iter()
-            .filter(|(_, item)| predicate(item))
-            .collect();
+        let skipped = AtomicBool::new(false);
+        let mut filtered: Vec<(usize, T)> = run_with_watchdog(config.timeout, |cancelled| {
+            indexed
+                .into_par_iter()
+                .filter(|(_, item)| {
+                    if cancelled.load(Ordering::Relaxed) {
+                        skipped.store(true, Ordering::Relaxed);
+                        false
+                    } else {
+                        predicate(item)
+                    }
+                })
+                .collect()
+        });
+        let timed_out = skipped.load(Ordering::Relaxed);
+        if timed_out {
+            filtered.clear();
+        }
 
         // Sort by original index to restore input order
         filtered.sort_unstable_by_key(|(idx, _)| *idx);
@@ -593,6 +879,8 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
             efficiency: (throughput as f64 / (data_len as f64 / elapsed.as_secs_f64())).min(1.0),
             work_stealing_metrics: WorkStealingMetrics::default(),
             load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out,
+            chunk_timings: Vec::new(),
         };
 
         ParallelResult {
@@ -648,6 +936,8 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
                 efficiency: 1.0,
                 work_stealing_metrics: WorkStealingMetrics::default(),
                 load_balancing_metrics: LoadBalancingMetrics::default(),
+                timed_out: false,
+                chunk_timings: Vec::new(),
             };
             return ParallelResult {
                 data: result,
@@ -681,6 +971,8 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
             efficiency,
             work_stealing_metrics: WorkStealingMetrics::default(),
             load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
         };
 
         ParallelResult {
@@ -689,6 +981,114 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
         }
     }
 
+    /// Reduces the iterator like [`par_reduce`](Self::par_reduce) while recording the running
+    /// reduction after every `checkpoint_every` items, for resumable aggregation.
+    ///
+    /// Returns `(final, checkpoints)`, where `checkpoints[i]` is the reduction of the first
+    /// `(i + 1) * checkpoint_every` items. A trailing partial chunk contributes to the final value
+    /// but produces no checkpoint, and `checkpoint_every == 0` disables checkpoints.
+    ///
+    /// The sequential path is fully deterministic. The parallel path is best-effort: each chunk is
+    /// reduced concurrently and the chunk results are folded in order, so the checkpoints only
+    /// match the sequential ones when `reduce` is associative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let config = ParallelConfig::default();
+    /// let result = (1..=6).into_iter().par_reduce_checkpointed(&config, |a, b| a + b, 2);
+    /// assert_eq!(result.data, (Some(21), vec![3, 10, 21]));
+    /// ```
+    fn par_reduce_checkpointed<F>(
+        self,
+        config: &ParallelConfig,
+        reduce: F,
+        checkpoint_every: usize,
+    ) -> ParallelResult<(Option<T>, Vec<T>)>
+    where
+        F: Fn(T, T) -> T + Send + Sync,
+        T: Send + Clone,
+        Self: Sized,
+    {
+        let start_time = Instant::now();
+        let data: Vec<T> = self.collect();
+        let data_len = data.len();
+
+        if data_len < config.min_parallel_size || checkpoint_every == 0 {
+            // Sequential reduction, snapshotting the accumulator at each boundary
+            let mut checkpoints = Vec::new();
+            let mut acc: Option<T> = None;
+            for (index, item) in data.into_iter().enumerate() {
+                acc = Some(match acc {
+                    Some(current) => reduce(current, item),
+                    None => item,
+                });
+                if checkpoint_every > 0 && (index + 1) % checkpoint_every == 0 {
+                    checkpoints.extend(acc.clone());
+                }
+            }
+            let elapsed = start_time.elapsed();
+            let metrics = ParallelMetrics {
+                total_time: elapsed,
+                thread_count: 1,
+                throughput: (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64,
+                memory_usage: ((data_len + checkpoints.len()) * std::mem::size_of::<T>()) as u64,
+                efficiency: 1.0,
+                work_stealing_metrics: WorkStealingMetrics::default(),
+                load_balancing_metrics: LoadBalancingMetrics::default(),
+                timed_out: false,
+                chunk_timings: Vec::new(),
+            };
+            return ParallelResult {
+                data: (acc, checkpoints),
+                metrics,
+            };
+        }
+
+        // Reduce each checkpoint-sized chunk in parallel, then fold the chunk results in order
+        let chunk_results: Vec<T> = data
+            .par_chunks(checkpoint_every)
+            .filter_map(|chunk| chunk.iter().cloned().reduce(&reduce))
+            .collect();
+        let full_chunks = data_len / checkpoint_every;
+
+        let mut checkpoints = Vec::with_capacity(full_chunks);
+        let mut acc: Option<T> = None;
+        for (index, partial) in chunk_results.into_iter().enumerate() {
+            acc = Some(match acc {
+                Some(current) => reduce(current, partial),
+                None => partial,
+            });
+            if index < full_chunks {
+                checkpoints.extend(acc.clone());
+            }
+        }
+
+        let elapsed = start_time.elapsed();
+        let thread_count = rayon::current_num_threads();
+        let throughput = (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64;
+
+        // Estimate parallel efficiency
+        let efficiency = (throughput as f64 / (data_len as f64 / elapsed.as_secs_f64())).min(1.0);
+
+        let metrics = ParallelMetrics {
+            total_time: elapsed,
+            thread_count,
+            throughput,
+            memory_usage: ((data_len + checkpoints.len()) * std::mem::size_of::<T>()) as u64,
+            efficiency,
+            work_stealing_metrics: WorkStealingMetrics::default(),
+            load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
+        };
+
+        ParallelResult {
+            data: (acc, checkpoints),
+            metrics,
+        }
+    }
+
     /// Groups items by a key produced from each element using the provided key function.
     ///
     /// Returns a `HashMap` that maps each distinct key to a `Vec<T>` containing the items that produced that key.
@@ -760,6 +1160,8 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
                 efficiency: 1.0,
                 work_stealing_metrics: WorkStealingMetrics::default(),
                 load_balancing_metrics: LoadBalancingMetrics::default(),
+                timed_out: false,
+                chunk_timings: Vec::new(),
             };
             return ParallelResult {
                 data: groups,
@@ -803,6 +1205,8 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
             efficiency,
             work_stealing_metrics: WorkStealingMetrics::default(),
             load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
         };
 
         ParallelResult {
@@ -811,32 +1215,43 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
         }
     }
 
-    /// Sorts the elements of the iterator in parallel.
+    /// Splits the elements into disjoint buckets keyed by `key`, in parallel.
     ///
-    /// The elements must implement `Ord` for comparison. This method collects the iterator
-    /// into a vector and sorts it using Rayon's parallel sort when the dataset size exceeds
-    /// the configured threshold.
+    /// Every element lands in exactly one bucket. Unlike
+    /// [`par_group_by`](Self::par_group_by), elements need not be `Clone`, and passing
+    /// `stable = true` guarantees each bucket keeps the elements' original relative order
+    /// (at the cost of one sort per bucket). With `stable = false` the order within a bucket
+    /// is unspecified on the parallel path.
     ///
     /// # Examples
     ///
     /// ```
     /// let config = ParallelConfig::default();
-    /// let data = vec![3, 1, 4, 1, 5];
-    /// let result = data.into_iter().par_sort(&config);
-    /// assert_eq!(result.data, vec![1, 1, 3, 4, 5]);
+    /// let docs = vec![("SP", 1), ("RJ", 2), ("SP", 3)];
+    /// let buckets = docs.into_iter().par_bucket_by(&config, |doc| doc.0, true);
+    /// assert_eq!(buckets.data[&"SP"], vec![("SP", 1), ("SP", 3)]);
     /// ```
-    fn par_sort(self, config: &ParallelConfig) -> ParallelResult<Vec<T>>
+    fn par_bucket_by<K, F>(
+        self,
+        config: &ParallelConfig,
+        key: F,
+        stable: bool,
+    ) -> ParallelResult<HashMap<K, Vec<T>>>
     where
-        T: Ord + Send + Clone,
+        K: std::hash::Hash + Eq + Send,
+        F: Fn(&T) -> K + Send + Sync,
         Self: Sized,
     {
         let start_time = Instant::now();
-        let mut data: Vec<T> = self.collect();
+        let data: Vec<T> = self.collect();
         let data_len = data.len();
 
         if data_len < config.min_parallel_size {
-            // Sequential sort for small datasets
-            data.sort();
+            // Sequential bucketing already preserves input order
+            let mut buckets: HashMap<K, Vec<T>> = HashMap::new();
+            for item in data {
+                buckets.entry(key(&item)).or_default().push(item);
+            }
             let elapsed = start_time.elapsed();
             let metrics = ParallelMetrics {
                 total_time: elapsed,
@@ -846,91 +1261,337 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
                 efficiency: 1.0,
                 work_stealing_metrics: WorkStealingMetrics::default(),
                 load_balancing_metrics: LoadBalancingMetrics::default(),
+                timed_out: false,
+                chunk_timings: Vec::new(),
+            };
+            return ParallelResult {
+                data: buckets,
+                metrics,
             };
-            return ParallelResult { data, metrics };
         }
 
-        // Parallel sort for large datasets
-        data.par_sort();
+        // Tag each element with its input position so buckets can be restored to input order
+        let indexed = data
+            .into_par_iter()
+            .enumerate()
+            .with_min_len(config.chunk_size.max(1))
+            .fold(
+                HashMap::new,
+                |mut buckets: HashMap<K, Vec<(usize, T)>>, (index, item)| {
+                    buckets.entry(key(&item)).or_default().push((index, item));
+                    buckets
+                },
+            )
+            .reduce(
+                HashMap::new,
+                |mut acc: HashMap<K, Vec<(usize, T)>>, partial| {
+                    for (bucket_key, mut items) in partial {
+                        acc.entry(bucket_key).or_default().append(&mut items);
+                    }
+                    acc
+                },
+            );
+
+        let result = indexed
+            .into_iter()
+            .map(|(bucket_key, mut items)| {
+                if stable {
+                    items.sort_unstable_by_key(|(index, _)| *index);
+                }
+                (
+                    bucket_key,
+                    items.into_iter().map(|(_, item)| item).collect(),
+                )
+            })
+            .collect();
 
         let elapsed = start_time.elapsed();
         let thread_count = rayon::current_num_threads();
         let throughput = (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64;
-
-        // Estimate parallel efficiency
         let efficiency = (throughput as f64 / (data_len as f64 / elapsed.as_secs_f64())).min(1.0);
 
         let metrics = ParallelMetrics {
             total_time: elapsed,
             thread_count,
             throughput,
-            memory_usage: (data_len * std::mem::size_of::<T>()) as u64,
+            memory_usage: (data_len * std::mem::size_of::<(usize, T)>()) as u64,
             efficiency,
             work_stealing_metrics: WorkStealingMetrics::default(),
             load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
         };
 
-        ParallelResult { data, metrics }
+        ParallelResult {
+            data: result,
+            metrics,
+        }
     }
 
-    /// Flat maps elements in parallel, flattening the results into a single collection.
+    /// Folds the elements into a per-key summary map in one parallel pass.
     ///
-    /// Applies the transformation function to each element, which returns an iterator,
-    /// then flattens all results into a single Vec. Uses parallel processing when the
-    /// dataset exceeds the configured threshold.
+    /// `init` turns each element into an accumulator for its key, and `fold` combines two
+    /// accumulators of the same key, both within a thread's own map and when the thread maps
+    /// are merged. Only one accumulator per key is ever kept per thread, so this is lighter
+    /// than [`par_group_by`](Self::par_group_by) followed by a fold. `fold` should be
+    /// associative and commutative, since combination order is unspecified. See
+    /// [`par_reduce_by_key`] for the equivalent over a `Vec`.
     ///
     /// # Examples
     ///
     /// ```
     /// let config = ParallelConfig::default();
-    /// let data = vec![vec![1, 2], vec![3, 4], vec![5]];
-    /// let result = data.into_iter().par_flat_map(&config, |v| v.into_iter());
-    /// assert_eq!(result.data, vec![1, 2, 3, 4, 5]);
+    /// let counts = vec!["a", "b", "a"]
+    ///     .into_iter()
+    ///     .par_fold_map(&config, |s| *s, |_| 1usize, |x, y| x + y);
+    /// assert_eq!(counts.data[&"a"], 2);
     /// ```
-    fn par_flat_map<F, U, I>(self, config: &ParallelConfig, f: F) -> ParallelResult<Vec<U>>
+    fn par_fold_map<K, V, KeyFn, Acc, Red>(
+        self,
+        config: &ParallelConfig,
+        key: KeyFn,
+        init: Acc,
+        fold: Red,
+    ) -> ParallelResult<HashMap<K, V>>
     where
-        F: Fn(T) -> I + Send + Sync,
-        I: IntoIterator<Item = U> + Send,
-        I::IntoIter: Send,
-        U: Send,
-        T: Send,
+        K: std::hash::Hash + Eq + Send,
+        V: Send,
+        KeyFn: Fn(&T) -> K + Send + Sync,
+        Acc: Fn(T) -> V + Send + Sync,
+        Red: Fn(V, V) -> V + Send + Sync,
+        Self: Sized,
+    {
+        par_reduce_by_key(self.collect(), key, init, fold, config)
+    }
+
+    /// Removes duplicate elements, keeping the first occurrence of each in input order.
+    ///
+    /// Deciding which occurrence comes first needs a single ordered pass, so only the
+    /// hashing is parallel: element hashes are computed across threads, then one sequential
+    /// pass walks them in order and compares elements only on hash collisions. The win is
+    /// therefore limited to expensive-to-hash elements; for cheap ones the sequential pass
+    /// dominates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let config = ParallelConfig::default();
+    /// let unique = vec![3, 1, 3, 2, 1].into_iter().par_unique(&config);
+    /// assert_eq!(unique.data, vec![3, 1, 2]);
+    /// ```
+    fn par_unique(self, config: &ParallelConfig) -> ParallelResult<Vec<T>>
+    where
+        T: Eq + std::hash::Hash + Send + Sync,
         Self: Sized,
     {
+        use std::hash::BuildHasher;
+
         let start_time = Instant::now();
         let data: Vec<T> = self.collect();
         let data_len = data.len();
+        let hasher = std::collections::hash_map::RandomState::new();
+        let parallel = data_len >= config.min_parallel_size;
+
+        let hashes: Vec<u64> = if parallel {
+            data.par_iter()
+                .with_min_len(config.chunk_size.max(1))
+                .map(|item| hasher.hash_one(item))
+                .collect()
+        } else {
+            data.iter().map(|item| hasher.hash_one(item)).collect()
+        };
 
-        if data_len < config.min_parallel_size {
-            // Sequential flat_map for small datasets
-            let result: Vec<U> = data.into_iter().flat_map(f).collect();
-            let elapsed = start_time.elapsed();
-            let metrics = ParallelMetrics {
-                total_time: elapsed,
-                thread_count: 1,
-                throughput: (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64,
-                memory_usage: (data_len * std::mem::size_of::<T>()
-                    + result.len() * std::mem::size_of::<U>()) as u64,
-                efficiency: 1.0,
-                work_stealing_metrics: WorkStealingMetrics::default(),
-                load_balancing_metrics: LoadBalancingMetrics::default(),
-            };
-            return ParallelResult {
-                data: result,
-                metrics,
-            };
+        // Ordered pass: indices of kept elements per hash, compared only on collision
+        let mut kept_by_hash: HashMap<u64, Vec<usize>> = HashMap::with_capacity(data_len);
+        let mut keep = vec![false; data_len];
+        for (index, hash) in hashes.into_iter().enumerate() {
+            let kept = kept_by_hash.entry(hash).or_default();
+            if !kept.iter().any(|&earlier| data[earlier] == data[index]) {
+                kept.push(index);
+                keep[index] = true;
+            }
         }
 
-        // Parallel flat_map - flatten lazily without intermediate allocations
-        let result: Vec<U> = data
-            .into_par_iter()
-            .flat_map_iter(|item| f(item))
+        let unique: Vec<T> = data
+            .into_iter()
+            .zip(keep)
+            .filter_map(|(item, keep)| keep.then_some(item))
             .collect();
 
         let elapsed = start_time.elapsed();
-        let thread_count = rayon::current_num_threads();
+        let thread_count = if parallel {
+            rayon::current_num_threads()
+        } else {
+            1
+        };
         let throughput = (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64;
-        let efficiency = (throughput as f64 / (data_len as f64 / elapsed.as_secs_f64())).min(1.0);
-
+        let efficiency = if parallel {
+            (throughput as f64 / (data_len as f64 / elapsed.as_secs_f64())).min(1.0)
+        } else {
+            1.0
+        };
+
+        let metrics = ParallelMetrics {
+            total_time: elapsed,
+            thread_count,
+            throughput,
+            memory_usage: (data_len * std::mem::size_of::<u64>()
+                + unique.len() * std::mem::size_of::<T>()) as u64,
+            efficiency,
+            work_stealing_metrics: WorkStealingMetrics::default(),
+            load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
+        };
+
+        ParallelResult {
+            data: unique,
+            metrics,
+        }
+    }
+
+    /// Sorts the elements of the iterator in parallel.
+    ///
+    /// The elements must implement `Ord` for comparison. This method collects the iterator
+    /// into a vector and sorts it using Rayon's parallel sort when the dataset size exceeds
+    /// the configured threshold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let config = ParallelConfig::default();
+    /// let data = vec![3, 1, 4, 1, 5];
+    /// let result = data.into_iter().par_sort(&config);
+    /// assert_eq!(result.data, vec![1, 1, 3, 4, 5]);
+    /// ```
+    fn par_sort(self, config: &ParallelConfig) -> ParallelResult<Vec<T>>
+    where
+        T: Ord + Send + Clone,
+        Self: Sized,
+    {
+        let start_time = Instant::now();
+        let mut data: Vec<T> = self.collect();
+        let data_len = data.len();
+
+        if data_len < config.min_parallel_size {
+            // Sequential sort for small datasets
+            data.sort();
+            let elapsed = start_time.elapsed();
+            let metrics = ParallelMetrics {
+                total_time: elapsed,
+                thread_count: 1,
+                throughput: (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64,
+                memory_usage: (data_len * std::mem::size_of::<T>()) as u64,
+                efficiency: 1.0,
+                work_stealing_metrics: WorkStealingMetrics::default(),
+                load_balancing_metrics: LoadBalancingMetrics::default(),
+                timed_out: false,
+                chunk_timings: Vec::new(),
+            };
+            return ParallelResult { data, metrics };
+        }
+
+        // Parallel sort for large datasets
+        data.par_sort();
+
+        let elapsed = start_time.elapsed();
+        let thread_count = rayon::current_num_threads();
+        let throughput = (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64;
+
+        // Estimate parallel efficiency
+        let efficiency = (throughput as f64 / (data_len as f64 / elapsed.as_secs_f64())).min(1.0);
+
+        let metrics = ParallelMetrics {
+            total_time: elapsed,
+            thread_count,
+            throughput,
+            memory_usage: (data_len * std::mem::size_of::<T>()) as u64,
+            efficiency,
+            work_stealing_metrics: WorkStealingMetrics::default(),
+            load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
+        };
+
+        ParallelResult { data, metrics }
+    }
+
+    /// Flat maps elements in parallel, flattening the results into a single collection.
+    ///
+    /// Applies the transformation function to each element, which returns an iterator,
+    /// then flattens all results into a single Vec. Uses parallel processing when the
+    /// dataset exceeds the configured threshold; the parallel path honours `config.timeout`
+    /// (see [`ParallelConfig::timeout`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let config = ParallelConfig::default();
+    /// let data = vec![vec![1, 2], vec![3, 4], vec![5]];
+    /// let result = data.into_iter().par_flat_map(&config, |v| v.into_iter());
+    /// assert_eq!(result.data, vec![1, 2, 3, 4, 5]);
+    /// ```
+    fn par_flat_map<F, U, I>(self, config: &ParallelConfig, f: F) -> ParallelResult<Vec<U>>
+    where
+        F: Fn(T) -> I + Send + Sync,
+        I: IntoIterator<Item = U> + Send,
+        I::IntoIter: Send,
+        U: Send,
+        T: Send,
+        Self: Sized,
+    {
+        let start_time = Instant::now();
+        let data: Vec<T> = self.collect();
+        let data_len = data.len();
+
+        if data_len < config.min_parallel_size {
+            // Sequential flat_map for small datasets
+            let result: Vec<U> = data.into_iter().flat_map(f).collect();
+            let elapsed = start_time.elapsed();
+            let metrics = ParallelMetrics {
+                total_time: elapsed,
+                thread_count: 1,
+                throughput: (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64,
+                memory_usage: (data_len * std::mem::size_of::<T>()
+                    + result.len() * std::mem::size_of::<U>()) as u64,
+                efficiency: 1.0,
+                work_stealing_metrics: WorkStealingMetrics::default(),
+                load_balancing_metrics: LoadBalancingMetrics::default(),
+                timed_out: false,
+                chunk_timings: Vec::new(),
+            };
+            return ParallelResult {
+                data: result,
+                metrics,
+            };
+        }
+
+        // Parallel flat_map - flatten lazily without intermediate allocations
+        let skipped = AtomicBool::new(false);
+        let mut result: Vec<U> = run_with_watchdog(config.timeout, |cancelled| {
+            data.into_par_iter()
+                .flat_map_iter(|item| {
+                    if cancelled.load(Ordering::Relaxed) {
+                        skipped.store(true, Ordering::Relaxed);
+                        None
+                    } else {
+                        Some(f(item))
+                    }
+                    .into_iter()
+                    .flatten()
+                })
+                .collect()
+        });
+        let timed_out = skipped.load(Ordering::Relaxed);
+        if timed_out {
+            result.clear();
+        }
+
+        let elapsed = start_time.elapsed();
+        let thread_count = rayon::current_num_threads();
+        let throughput = (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64;
+        let efficiency = (throughput as f64 / (data_len as f64 / elapsed.as_secs_f64())).min(1.0);
+
         let metrics = ParallelMetrics {
             total_time: elapsed,
             thread_count,
@@ -940,6 +1601,8 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
             efficiency,
             work_stealing_metrics: WorkStealingMetrics::default(),
             load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out,
+            chunk_timings: Vec::new(),
         };
 
         ParallelResult {
@@ -988,6 +1651,8 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
                 efficiency: 1.0,
                 work_stealing_metrics: WorkStealingMetrics::default(),
                 load_balancing_metrics: LoadBalancingMetrics::default(),
+                timed_out: false,
+                chunk_timings: Vec::new(),
             };
             return ParallelResult {
                 data: (matching, non_matching),
@@ -1031,6 +1696,8 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
             efficiency,
             work_stealing_metrics: WorkStealingMetrics::default(),
             load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
         };
 
         ParallelResult {
@@ -1042,7 +1709,8 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
     /// Finds the first element matching a predicate in parallel.
     ///
     /// Returns Some(element) if found, None otherwise. Uses parallel search
-    /// when the dataset exceeds the configured threshold.
+    /// when the dataset exceeds the configured threshold; the parallel path honours
+    /// `config.timeout` (see [`ParallelConfig::timeout`]).
     ///
     /// # Examples
     ///
@@ -1074,6 +1742,8 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
                 efficiency: 1.0,
                 work_stealing_metrics: WorkStealingMetrics::default(),
                 load_balancing_metrics: LoadBalancingMetrics::default(),
+                timed_out: false,
+                chunk_timings: Vec::new(),
             };
             return ParallelResult {
                 data: result,
@@ -1081,8 +1751,19 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
             };
         }
 
-        // Parallel find
-        let result = data.into_par_iter().find_any(predicate);
+        // Parallel find; `None` is only conclusive if no element was skipped
+        let skipped = AtomicBool::new(false);
+        let result = run_with_watchdog(config.timeout, |cancelled| {
+            data.into_par_iter().find_any(|item| {
+                if cancelled.load(Ordering::Relaxed) {
+                    skipped.store(true, Ordering::Relaxed);
+                    false
+                } else {
+                    predicate(item)
+                }
+            })
+        });
+        let timed_out = result.is_none() && skipped.load(Ordering::Relaxed);
 
         let elapsed = start_time.elapsed();
         let thread_count = rayon::current_num_threads();
@@ -1097,6 +1778,8 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
             efficiency,
             work_stealing_metrics: WorkStealingMetrics::default(),
             load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out,
+            chunk_timings: Vec::new(),
         };
 
         ParallelResult {
@@ -1104,6 +1787,92 @@ pub trait ParallelIteratorExt<T: Send + Sync>: Iterator<Item = T> + Send + Sync
             metrics,
         }
     }
+
+    /// Counts the elements matching a predicate in parallel.
+    ///
+    /// Unlike `par_filter`, the matching elements are never materialized or cloned;
+    /// only the count is produced. Falls back to a sequential count when the dataset
+    /// is below the configured threshold; the parallel path honours `config.timeout`
+    /// (see [`ParallelConfig::timeout`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let config = ParallelConfig::default();
+    /// let result = (0..10).par_count(&config, |&x| x % 2 == 0);
+    /// assert_eq!(result.data, 5);
+    /// ```
+    fn par_count<F>(self, config: &ParallelConfig, predicate: F) -> ParallelResult<usize>
+    where
+        F: Fn(&T) -> bool + Send + Sync,
+        Self: Sized,
+    {
+        let start_time = Instant::now();
+        let data: Vec<T> = self.collect();
+        let data_len = data.len();
+
+        if data_len < config.min_parallel_size {
+            // Sequential count for small datasets
+            let count = data.iter().filter(|item| predicate(item)).count();
+            let elapsed = start_time.elapsed();
+            let metrics = ParallelMetrics {
+                total_time: elapsed,
+                thread_count: 1,
+                throughput: (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64,
+                memory_usage: (data_len * std::mem::size_of::<T>()) as u64,
+                efficiency: 1.0,
+                work_stealing_metrics: WorkStealingMetrics::default(),
+                load_balancing_metrics: LoadBalancingMetrics::default(),
+                timed_out: false,
+                chunk_timings: Vec::new(),
+            };
+            return ParallelResult {
+                data: count,
+                metrics,
+            };
+        }
+
+        // Parallel count over borrowed elements
+        let skipped = AtomicBool::new(false);
+        let mut count = run_with_watchdog(config.timeout, |cancelled| {
+            data.par_iter()
+                .filter(|item| {
+                    if cancelled.load(Ordering::Relaxed) {
+                        skipped.store(true, Ordering::Relaxed);
+                        false
+                    } else {
+                        predicate(item)
+                    }
+                })
+                .count()
+        });
+        let timed_out = skipped.load(Ordering::Relaxed);
+        if timed_out {
+            count = 0;
+        }
+
+        let elapsed = start_time.elapsed();
+        let thread_count = rayon::current_num_threads();
+        let throughput = (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64;
+        let efficiency = (throughput as f64 / (data_len as f64 / elapsed.as_secs_f64())).min(1.0);
+
+        let metrics = ParallelMetrics {
+            total_time: elapsed,
+            thread_count,
+            throughput,
+            memory_usage: (data_len * std::mem::size_of::<T>()) as u64,
+            efficiency,
+            work_stealing_metrics: WorkStealingMetrics::default(),
+            load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out,
+            chunk_timings: Vec::new(),
+        };
+
+        ParallelResult {
+            data: count,
+            metrics,
+        }
+    }
 }
 
 /// Result wrapper for parallel operations with performance metrics
@@ -1130,6 +1899,49 @@ impl<T> ParallelResult<T> {
     }
 }
 
+impl<T: Clone> ParallelResult<Vec<T>> {
+    /// Moves the result data into a [`PersistentVector`], returning it with the metrics.
+    ///
+    /// Saves callers the intermediate `into_inner` + `PersistentVector::from_vec` step
+    /// when results are fed back into tenant state.
+    pub fn into_persistent_vector(self) -> (PersistentVector<T>, ParallelMetrics) {
+        (PersistentVector::from_vec(self.data), self.metrics)
+    }
+}
+
+impl<T: Serialize> ParallelResult<Vec<T>> {
+    /// Writes the result as `{"data": [...], "metrics": {...}}` JSON to `writer`.
+    ///
+    /// Elements are serialized one at a time straight into `writer`, so unlike
+    /// `serde_json::to_string` no second in-memory copy of a large result is built. The
+    /// metrics follow the data. Writes are not buffered; wrap files and sockets in a
+    /// `BufWriter`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let result = (1..=3).par_map(&ParallelConfig::default(), |x| x * 2);
+    /// let mut out = Vec::new();
+    /// result.write_json(&mut out)?;
+    /// assert!(out.starts_with(br#"{"data":[2,4,6],"metrics":"#));
+    /// ```
+    pub fn write_json(&self, writer: impl std::io::Write) -> serde_json::Result<()> {
+        #[derive(Serialize)]
+        struct JsonView<'a, T> {
+            data: &'a [T],
+            metrics: &'a ParallelMetrics,
+        }
+
+        serde_json::to_writer(
+            writer,
+            &JsonView {
+                data: &self.data,
+                metrics: &self.metrics,
+            },
+        )
+    }
+}
+
 impl fmt::Display for ParallelMetrics {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -1228,6 +2040,8 @@ where
             efficiency: 1.0,
             work_stealing_metrics: WorkStealingMetrics::default(),
             load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
         };
         return ParallelResult {
             data: result,
@@ -1256,6 +2070,8 @@ where
         efficiency,
         work_stealing_metrics: WorkStealingMetrics::default(),
         load_balancing_metrics: LoadBalancingMetrics::default(),
+        timed_out: false,
+        chunk_timings: Vec::new(),
     };
 
     ParallelResult {
@@ -1307,6 +2123,8 @@ where
             efficiency: 1.0,
             work_stealing_metrics: WorkStealingMetrics::default(),
             load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
         };
     }
 
@@ -1328,7 +2146,44 @@ where
         efficiency,
         work_stealing_metrics: WorkStealingMetrics::default(),
         load_balancing_metrics: LoadBalancingMetrics::default(),
+        timed_out: false,
+        chunk_timings: Vec::new(),
+    }
+}
+
+/// Parallel map that writes into a caller-provided buffer instead of allocating a new `Vec`.
+///
+/// `out` is cleared and then filled with `f` applied to each element of `data`, in input
+/// order. Reusing the same buffer across calls keeps its capacity, so hot loops over many
+/// small batches avoid a fresh allocation per batch once the buffer is large enough.
+///
+/// # Examples
+///
+/// ```
+/// let cfg = ParallelConfig::default();
+/// let mut out = Vec::with_capacity(4);
+/// par_map_into(vec![1, 2, 3], &mut out, &cfg, |n| n * 10);
+/// assert_eq!(out, vec![10, 20, 30]);
+/// ```
+#[allow(dead_code)]
+pub fn par_map_into<T, U, F>(data: Vec<T>, out: &mut Vec<U>, config: &ParallelConfig, f: F)
+where
+    T: Send,
+    U: Send,
+    F: Fn(T) -> U + Send + Sync,
+{
+    out.clear();
+
+    if data.len() < config.min_parallel_size {
+        out.extend(data.into_iter().map(f));
+        return;
     }
+
+    out.par_extend(
+        data.into_par_iter()
+            .with_min_len(config.chunk_size.max(1))
+            .map(f),
+    );
 }
 
 /// Memory-efficient parallel chunk processing
@@ -1361,6 +2216,8 @@ where
             efficiency: 1.0,
             work_stealing_metrics: WorkStealingMetrics::default(),
             load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
         };
         return ParallelResult {
             data: result,
@@ -1399,6 +2256,8 @@ where
         efficiency,
         work_stealing_metrics: WorkStealingMetrics::default(),
         load_balancing_metrics: LoadBalancingMetrics::default(),
+        timed_out: false,
+        chunk_timings: Vec::new(),
     };
 
     ParallelResult {
@@ -1407,42 +2266,638 @@ where
     }
 }
 
-/// Estimates a suggested number of worker threads based on the input dataset size.
+/// Parallel map over a borrowed slice
 ///
-/// The function returns `1` for very small datasets (< 1,000), `2` for small datasets
-/// (1,000..10,000), `4` for medium datasets (10,000..100,000), and `0` to indicate
-/// that the caller should delegate thread-count selection to Rayon for large datasets
-/// (>= 100,000).
+/// Like `par_map` but iterates `data.par_iter()`, so elements are neither moved nor cloned
+/// and `f` may return values borrowing from `data`. Output preserves input order.
 ///
 /// # Examples
 ///
 /// ```
-/// assert_eq!(estimate_thread_count(500), 1);
-/// assert_eq!(estimate_thread_count(5_000), 2);
-/// assert_eq!(estimate_thread_count(50_000), 4);
-/// assert_eq!(estimate_thread_count(200_000), 0);
+/// let cfg = ParallelConfig::default();
+/// let words = vec!["alpha".to_string(), "be".to_string()];
+/// let lens = par_map_ref(&words, &cfg, |w| w.len());
+/// assert_eq!(lens.into_inner(), vec![5, 2]);
 /// ```
 #[allow(dead_code)]
-pub fn estimate_thread_count(data_size: usize) -> usize {
-    if data_size < 1000 {
-        1
-    } else if data_size < 10000 {
-        2
-    } else if data_size < 100000 {
-        4
-    } else {
-        // Let rayon determine optimal count for large datasets
-        0
-    }
-}
+pub fn par_map_ref<'a, T, U, F>(
+    data: &'a [T],
+    config: &ParallelConfig,
+    f: F,
+) -> ParallelResult<Vec<U>>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&'a T) -> U + Send + Sync,
+{
+    let start_time = Instant::now();
+    let data_len = data.len();
 
-/// Create a ParallelConfig tuned to the given dataset size.
-///
-/// For inputs smaller than 1000, `min_parallel_size` is set to `usize::MAX` to
-/// force sequential execution; otherwise `min_parallel_size` is `data_size / 10`.
-/// `thread_pool_size` is estimated by `estimate_thread_count(data_size)`,
-/// `enable_work_stealing` is `true`, and `chunk_size` is `max(data_size / max(thread_count, 1), 100)`.
-///
+    if data_len < config.min_parallel_size {
+        let result: Vec<U> = data.iter().map(&f).collect();
+        let elapsed = start_time.elapsed();
+        let metrics = ParallelMetrics {
+            total_time: elapsed,
+            thread_count: 1,
+            throughput: (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64,
+            memory_usage: (result.len() * std::mem::size_of::<U>()) as u64,
+            efficiency: 1.0,
+            work_stealing_metrics: WorkStealingMetrics::default(),
+            load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
+        };
+        return ParallelResult {
+            data: result,
+            metrics,
+        };
+    }
+
+    let result: Vec<U> = data
+        .par_iter()
+        .with_min_len(config.chunk_size.max(1))
+        .map(&f)
+        .collect();
+
+    let elapsed = start_time.elapsed();
+    let thread_count = rayon::current_num_threads();
+    let throughput = (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64;
+
+    // Estimate parallel efficiency
+    let efficiency = (throughput as f64 / (data_len as f64 / elapsed.as_secs_f64())).min(1.0);
+
+    let metrics = ParallelMetrics {
+        total_time: elapsed,
+        thread_count,
+        throughput,
+        memory_usage: (result.len() * std::mem::size_of::<U>()) as u64,
+        efficiency,
+        work_stealing_metrics: WorkStealingMetrics::default(),
+        load_balancing_metrics: LoadBalancingMetrics::default(),
+        timed_out: false,
+        chunk_timings: Vec::new(),
+    };
+
+    ParallelResult {
+        data: result,
+        metrics,
+    }
+}
+
+/// Scrambles a per-element hash (splitmix64 finalizer) before it is summed by [`par_hash`],
+/// so structured inputs such as sequential ids do not cancel out.
+fn mix_hash(hash: u64) -> u64 {
+    let mut z = hash.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Order-independent checksum of `data` for integrity checks on large batches
+///
+/// Each element is hashed with `hash_one`, mixed and combined with wrapping addition.
+/// The combination is commutative and associative, so the result is the same whatever
+/// the work-stealing split and whether the input runs sequentially or in parallel; it
+/// is also the same for any permutation of `data`.
+///
+/// # Examples
+///
+/// ```
+/// let cfg = ParallelConfig::default();
+/// let a = par_hash(&[1u64, 2, 3], &cfg, |x| *x).into_inner();
+/// let b = par_hash(&[3u64, 1, 2], &cfg, |x| *x).into_inner();
+/// assert_eq!(a, b);
+/// ```
+#[allow(dead_code)]
+pub fn par_hash<T, F>(data: &[T], config: &ParallelConfig, hash_one: F) -> ParallelResult<u64>
+where
+    T: Sync,
+    F: Fn(&T) -> u64 + Send + Sync,
+{
+    let start_time = Instant::now();
+    let data_len = data.len();
+    let parallel = data_len >= config.min_parallel_size;
+
+    let hash = if parallel {
+        data.par_iter()
+            .with_min_len(config.chunk_size.max(1))
+            .map(|item| mix_hash(hash_one(item)))
+            .reduce(|| 0, u64::wrapping_add)
+    } else {
+        data.iter()
+            .map(|item| mix_hash(hash_one(item)))
+            .fold(0, u64::wrapping_add)
+    };
+
+    let elapsed = start_time.elapsed();
+    let throughput = (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64;
+    let (thread_count, efficiency) = if parallel {
+        (
+            rayon::current_num_threads(),
+            (throughput as f64 / (data_len as f64 / elapsed.as_secs_f64())).min(1.0),
+        )
+    } else {
+        (1, 1.0)
+    };
+
+    ParallelResult {
+        data: hash,
+        metrics: ParallelMetrics {
+            total_time: elapsed,
+            thread_count,
+            throughput,
+            memory_usage: std::mem::size_of::<u64>() as u64,
+            efficiency,
+            work_stealing_metrics: WorkStealingMetrics::default(),
+            load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
+        },
+    }
+}
+
+/// Sliding-window parallel aggregation
+///
+/// Applies `f` to every contiguous window of `window` elements, producing one output per
+/// window in input order. A zero-width window or one wider than `data` yields no output.
+///
+/// # Examples
+///
+/// ```
+/// let cfg = ParallelConfig::default();
+/// let sums = par_windows(&[1, 2, 3, 4], 2, &cfg, |w| w.iter().sum::<i32>());
+/// assert_eq!(sums.into_inner(), vec![3, 5, 7]);
+/// ```
+#[allow(dead_code)]
+pub fn par_windows<T, U, F>(
+    data: &[T],
+    window: usize,
+    config: &ParallelConfig,
+    f: F,
+) -> ParallelResult<Vec<U>>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&[T]) -> U + Send + Sync,
+{
+    let start_time = Instant::now();
+    let window_count = if window == 0 || window > data.len() {
+        0
+    } else {
+        data.len() - window + 1
+    };
+
+    if window_count < config.min_parallel_size {
+        // Sequential processing (also covers the degenerate window sizes)
+        let result: Vec<U> = if window_count == 0 {
+            Vec::new()
+        } else {
+            data.windows(window).map(&f).collect()
+        };
+        let elapsed = start_time.elapsed();
+        let metrics = ParallelMetrics {
+            total_time: elapsed,
+            thread_count: 1,
+            throughput: (window_count as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64,
+            memory_usage: (result.len() * std::mem::size_of::<U>()) as u64,
+            efficiency: 1.0,
+            work_stealing_metrics: WorkStealingMetrics::default(),
+            load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
+        };
+        return ParallelResult {
+            data: result,
+            metrics,
+        };
+    }
+
+    let result: Vec<U> = data
+        .par_windows(window)
+        .with_min_len(config.chunk_size.max(1))
+        .map(&f)
+        .collect();
+
+    let elapsed = start_time.elapsed();
+    let thread_count = rayon::current_num_threads();
+    let throughput = (window_count as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64;
+
+    // Estimate parallel efficiency
+    let efficiency = (throughput as f64 / (window_count as f64 / elapsed.as_secs_f64())).min(1.0);
+
+    let metrics = ParallelMetrics {
+        total_time: elapsed,
+        thread_count,
+        throughput,
+        memory_usage: (result.len() * std::mem::size_of::<U>()) as u64,
+        efficiency,
+        work_stealing_metrics: WorkStealingMetrics::default(),
+        load_balancing_metrics: LoadBalancingMetrics::default(),
+        timed_out: false,
+        chunk_timings: Vec::new(),
+    };
+
+    ParallelResult {
+        data: result,
+        metrics,
+    }
+}
+
+/// Parallel hash inner join
+///
+/// Builds a hash index of `right` keyed by `right_key` (in parallel), then probes it for
+/// every `left` item in parallel, emitting one `(L, R)` pair per match. Output follows
+/// `left` order, and matches for one left item follow `right` order, so the result equals
+/// a sequential nested-loop join.
+///
+/// # Examples
+///
+/// ```
+/// let cfg = ParallelConfig::default();
+/// let joined = par_join(vec![(1, "a"), (2, "b")], vec![(1, 10)], |l| l.0, |r| r.0, &cfg);
+/// assert_eq!(joined.into_inner(), vec![((1, "a"), (1, 10))]);
+/// ```
+#[allow(dead_code)]
+pub fn par_join<K, L, R, LK, RK>(
+    left: Vec<L>,
+    right: Vec<R>,
+    left_key: LK,
+    right_key: RK,
+    config: &ParallelConfig,
+) -> ParallelResult<Vec<(L, R)>>
+where
+    K: std::hash::Hash + Eq + Send + Sync,
+    L: Clone + Send + Sync,
+    R: Clone + Send + Sync,
+    LK: Fn(&L) -> K + Send + Sync,
+    RK: Fn(&R) -> K + Send + Sync,
+{
+    let start_time = Instant::now();
+    let input_len = left.len() + right.len();
+
+    if input_len < config.min_parallel_size {
+        let mut index: HashMap<K, Vec<&R>> = HashMap::new();
+        for item in &right {
+            index.entry(right_key(item)).or_default().push(item);
+        }
+        let result: Vec<(L, R)> = left
+            .iter()
+            .flat_map(|l| {
+                index
+                    .get(&left_key(l))
+                    .into_iter()
+                    .flatten()
+                    .map(move |r| (l.clone(), (*r).clone()))
+            })
+            .collect();
+
+        let elapsed = start_time.elapsed();
+        let metrics = ParallelMetrics {
+            total_time: elapsed,
+            thread_count: 1,
+            throughput: (input_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64,
+            memory_usage: (result.len() * std::mem::size_of::<(L, R)>()) as u64,
+            efficiency: 1.0,
+            work_stealing_metrics: WorkStealingMetrics::default(),
+            load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
+        };
+        return ParallelResult {
+            data: result,
+            metrics,
+        };
+    }
+
+    // Build the index per split and merge adjacent splits in order, keeping `right` order
+    let index: HashMap<K, Vec<&R>> = right
+        .par_iter()
+        .fold(HashMap::new, |mut index: HashMap<K, Vec<&R>>, item| {
+            index.entry(right_key(item)).or_default().push(item);
+            index
+        })
+        .reduce(HashMap::new, |mut merged, other| {
+            for (key, items) in other {
+                merged.entry(key).or_default().extend(items);
+            }
+            merged
+        });
+
+    let result: Vec<(L, R)> = left
+        .par_iter()
+        .with_min_len(config.chunk_size.max(1))
+        .flat_map_iter(|l| {
+            index
+                .get(&left_key(l))
+                .into_iter()
+                .flatten()
+                .map(move |r| (l.clone(), (*r).clone()))
+        })
+        .collect();
+
+    let elapsed = start_time.elapsed();
+    let thread_count = rayon::current_num_threads();
+    let throughput = (input_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64;
+
+    // Estimate parallel efficiency
+    let efficiency = (throughput as f64 / (input_len as f64 / elapsed.as_secs_f64())).min(1.0);
+
+    let metrics = ParallelMetrics {
+        total_time: elapsed,
+        thread_count,
+        throughput,
+        memory_usage: (result.len() * std::mem::size_of::<(L, R)>()) as u64,
+        efficiency,
+        work_stealing_metrics: WorkStealingMetrics::default(),
+        load_balancing_metrics: LoadBalancingMetrics::default(),
+        timed_out: false,
+        chunk_timings: Vec::new(),
+    };
+
+    ParallelResult {
+        data: result,
+        metrics,
+    }
+}
+
+/// Inserts `value` under `key`, combining it with an existing value through `reduce`.
+fn merge_reduced<K, V, RedFn>(map: &mut HashMap<K, V>, key: K, value: V, reduce: &RedFn)
+where
+    K: std::hash::Hash + Eq,
+    RedFn: Fn(V, V) -> V,
+{
+    let merged = match map.remove(&key) {
+        Some(existing) => reduce(existing, value),
+        None => value,
+    };
+    map.insert(key, merged);
+}
+
+/// Parallel keyed reduction
+///
+/// Maps each element to a key with `key_fn` and a value with `val_fn`, combining values that
+/// share a key with `reduce`. Each worker folds into its own partial map and the partial maps
+/// are merged with `reduce` on key collisions, so per-key value lists are never materialized.
+/// `reduce` should be associative and commutative, since combination order is unspecified.
+///
+/// # Examples
+///
+/// ```
+/// let cfg = ParallelConfig::default();
+/// let sales = vec![("a", 10), ("b", 5), ("a", 7)];
+/// let totals = par_reduce_by_key(sales, |s| s.0, |s| s.1, |x, y| x + y, &cfg);
+/// assert_eq!(totals.data[&"a"], 17);
+/// ```
+#[allow(dead_code)]
+pub fn par_reduce_by_key<T, K, V, KeyFn, ValFn, RedFn>(
+    data: Vec<T>,
+    key_fn: KeyFn,
+    val_fn: ValFn,
+    reduce: RedFn,
+    config: &ParallelConfig,
+) -> ParallelResult<HashMap<K, V>>
+where
+    T: Send,
+    K: std::hash::Hash + Eq + Send,
+    V: Send,
+    KeyFn: Fn(&T) -> K + Send + Sync,
+    ValFn: Fn(T) -> V + Send + Sync,
+    RedFn: Fn(V, V) -> V + Send + Sync,
+{
+    let start_time = Instant::now();
+    let data_len = data.len();
+
+    if data_len < config.min_parallel_size {
+        let mut result = HashMap::new();
+        for item in data {
+            let key = key_fn(&item);
+            merge_reduced(&mut result, key, val_fn(item), &reduce);
+        }
+        let elapsed = start_time.elapsed();
+        let metrics = ParallelMetrics {
+            total_time: elapsed,
+            thread_count: 1,
+            throughput: (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64,
+            memory_usage: (result.len() * std::mem::size_of::<(K, V)>()) as u64,
+            efficiency: 1.0,
+            work_stealing_metrics: WorkStealingMetrics::default(),
+            load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
+        };
+        return ParallelResult {
+            data: result,
+            metrics,
+        };
+    }
+
+    let result = data
+        .into_par_iter()
+        .with_min_len(config.chunk_size.max(1))
+        .fold(HashMap::new, |mut partial: HashMap<K, V>, item| {
+            let key = key_fn(&item);
+            merge_reduced(&mut partial, key, val_fn(item), &reduce);
+            partial
+        })
+        .reduce(
+            HashMap::new,
+            |acc: HashMap<K, V>, partial: HashMap<K, V>| {
+                // Merge the smaller map into the larger one
+                let (mut larger, smaller) = if acc.len() >= partial.len() {
+                    (acc, partial)
+                } else {
+                    (partial, acc)
+                };
+                for (key, value) in smaller {
+                    merge_reduced(&mut larger, key, value, &reduce);
+                }
+                larger
+            },
+        );
+
+    let elapsed = start_time.elapsed();
+    let thread_count = rayon::current_num_threads();
+    let throughput = (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64;
+
+    // Estimate parallel efficiency
+    let efficiency = (throughput as f64 / (data_len as f64 / elapsed.as_secs_f64())).min(1.0);
+
+    let metrics = ParallelMetrics {
+        total_time: elapsed,
+        thread_count,
+        throughput,
+        memory_usage: (result.len() * std::mem::size_of::<(K, V)>()) as u64,
+        efficiency,
+        work_stealing_metrics: WorkStealingMetrics::default(),
+        load_balancing_metrics: LoadBalancingMetrics::default(),
+        timed_out: false,
+        chunk_timings: Vec::new(),
+    };
+
+    ParallelResult {
+        data: result,
+        metrics,
+    }
+}
+
+/// Output of [`par_validate_transform`]: transformed valid elements, and rejected elements
+/// with their validation errors
+pub type ValidatedSplit<T, U> = (Vec<U>, Vec<(T, Vec<ValidationError>)>);
+
+/// Parallel validate-then-transform pass for imports
+///
+/// Each element is checked with `validate`; elements with no errors are consumed by
+/// `transform` in the same pass, the rest are returned untouched alongside their errors.
+/// Both output vectors keep the input order.
+///
+/// # Returns
+/// `(transformed, rejected)`: the outputs of the valid elements, and each invalid element
+/// paired with its validation errors
+///
+/// # Examples
+///
+/// ```
+/// let cfg = ParallelConfig::default();
+/// let result = par_validate_transform(
+///     vec![4, -1, 9],
+///     |n: &i32| if *n < 0 { vec![ValidationError::new("n", "NEGATIVE", "n is negative")] } else { vec![] },
+///     |n| n * 10,
+///     &cfg,
+/// );
+/// let (valid, invalid) = result.into_inner();
+/// assert_eq!(valid, vec![40, 90]);
+/// assert_eq!(invalid[0].0, -1);
+/// ```
+#[allow(dead_code)]
+pub fn par_validate_transform<T, U, V, F>(
+    data: Vec<T>,
+    validate: V,
+    transform: F,
+    config: &ParallelConfig,
+) -> ParallelResult<ValidatedSplit<T, U>>
+where
+    T: Send,
+    U: Send,
+    V: Fn(&T) -> Vec<ValidationError> + Send + Sync,
+    F: Fn(T) -> U + Send + Sync,
+{
+    let start_time = Instant::now();
+    let data_len = data.len();
+    let parallel = data_len >= config.min_parallel_size;
+
+    let split = |item: T| {
+        let errors = validate(&item);
+        if errors.is_empty() {
+            rayon::iter::Either::Left(transform(item))
+        } else {
+            rayon::iter::Either::Right((item, errors))
+        }
+    };
+
+    let (valid, invalid): ValidatedSplit<T, U> = if parallel {
+        data.into_par_iter()
+            .with_min_len(config.chunk_size.max(1))
+            .partition_map(split)
+    } else {
+        let mut valid = Vec::new();
+        let mut invalid = Vec::new();
+        for item in data {
+            match split(item) {
+                rayon::iter::Either::Left(output) => valid.push(output),
+                rayon::iter::Either::Right(rejected) => invalid.push(rejected),
+            }
+        }
+        (valid, invalid)
+    };
+
+    let elapsed = start_time.elapsed();
+    let throughput = (data_len as u64 * 1_000_000) / elapsed.as_micros().max(1) as u64;
+    let (thread_count, efficiency) = if parallel {
+        (
+            rayon::current_num_threads(),
+            (throughput as f64 / (data_len as f64 / elapsed.as_secs_f64())).min(1.0),
+        )
+    } else {
+        (1, 1.0)
+    };
+    let memory_usage = (valid.len() * std::mem::size_of::<U>()
+        + invalid.len() * std::mem::size_of::<(T, Vec<ValidationError>)>())
+        as u64;
+
+    ParallelResult {
+        data: (valid, invalid),
+        metrics: ParallelMetrics {
+            total_time: elapsed,
+            thread_count,
+            throughput,
+            memory_usage,
+            efficiency,
+            work_stealing_metrics: WorkStealingMetrics::default(),
+            load_balancing_metrics: LoadBalancingMetrics::default(),
+            timed_out: false,
+            chunk_timings: Vec::new(),
+        },
+    }
+}
+
+/// Estimates a suggested number of worker threads based on the input dataset size,
+/// clamped to the parallelism available on this machine.
+///
+/// See [`estimate_thread_count_for`] for the tiers; the available parallelism is read
+/// from `std::thread::available_parallelism()` and falls back to `1` when unknown.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(estimate_thread_count(500), 1);
+/// assert_eq!(estimate_thread_count(200_000), 0);
+/// ```
+#[allow(dead_code)]
+pub fn estimate_thread_count(data_size: usize) -> usize {
+    let available = std::thread::available_parallelism().map_or(1, |n| n.get());
+    estimate_thread_count_for(data_size, available)
+}
+
+/// Estimates a suggested number of worker threads for `data_size` items on a machine
+/// with `available` hardware threads.
+///
+/// The tiers are `1` for very small datasets (< 1,000), `2` for small datasets
+/// (1,000..10,000) and `4` for medium datasets (10,000..100,000), each capped at
+/// `available` (treated as at least `1`) to avoid oversubscription. Large datasets
+/// (>= 100,000) return `0`, delegating thread-count selection to Rayon, which already
+/// sizes its pool to the available parallelism.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(estimate_thread_count_for(5_000, 8), 2);
+/// assert_eq!(estimate_thread_count_for(50_000, 8), 4);
+/// assert_eq!(estimate_thread_count_for(50_000, 2), 2);
+/// assert_eq!(estimate_thread_count_for(200_000, 2), 0);
+/// ```
+pub fn estimate_thread_count_for(data_size: usize, available: usize) -> usize {
+    let tier = if data_size < 1000 {
+        1
+    } else if data_size < 10000 {
+        2
+    } else if data_size < 100000 {
+        4
+    } else {
+        // Let rayon determine optimal count for large datasets
+        return 0;
+    };
+
+    tier.min(available.max(1))
+}
+
+/// Create a ParallelConfig tuned to the given dataset size.
+///
+/// For inputs smaller than 1000, `min_parallel_size` is set to `usize::MAX` to
+/// force sequential execution; otherwise `min_parallel_size` is `data_size / 10`.
+/// `thread_pool_size` is estimated by `estimate_thread_count(data_size)` (clamped to the
+/// available parallelism),
+/// `enable_work_stealing` is `true`, and `chunk_size` is `max(data_size / max(thread_count, 1), 100)`.
+///
 /// # Examples
 ///
 /// ```
@@ -1459,14 +2914,141 @@ pub fn optimized_config(data_size: usize) -> ParallelConfig {
         data_size / 10
     };
 
-    ParallelConfig {
-        thread_pool_size: thread_count,
-        min_parallel_size,
-        enable_work_stealing: true,
-        chunk_size: (data_size / thread_count.max(1)).max(100),
-        adaptive_chunk_sizing: true,
-        max_chunk_size: (data_size / 4).max(4096).min(16384),
-    }
+    ParallelConfig {
+        thread_pool_size: thread_count,
+        min_parallel_size,
+        enable_work_stealing: true,
+        chunk_size: (data_size / thread_count.max(1)).max(100),
+        adaptive_chunk_sizing: true,
+        max_chunk_size: (data_size / 4).max(4096).min(16384),
+        history_namespace: None,
+        timeout: None,
+        collect_chunk_timings: false,
+    }
+}
+
+/// Maps `data` with `f` using a config chosen by [`optimized_config`] for its length.
+///
+/// Small inputs run sequentially and large ones in parallel without per-call tuning;
+/// use `par_map` with an explicit `ParallelConfig` when the defaults don't fit.
+///
+/// # Examples
+///
+/// ```
+/// let result = auto_map(vec![1, 2, 3], |x| x * 2);
+/// assert_eq!(result.into_inner(), vec![2, 4, 6]);
+/// ```
+#[allow(dead_code)]
+pub fn auto_map<T, U, F>(data: Vec<T>, f: F) -> ParallelResult<Vec<U>>
+where
+    T: Send + Sync,
+    U: Send,
+    F: Fn(T) -> U + Send + Sync,
+{
+    let config = optimized_config(data.len());
+    data.into_iter().par_map(&config, f)
+}
+
+/// Filters `data` with `predicate` using a config chosen by [`optimized_config`].
+///
+/// # Examples
+///
+/// ```
+/// let result = auto_filter(vec![1, 2, 3, 4], |x| x % 2 == 0);
+/// assert_eq!(result.into_inner(), vec![2, 4]);
+/// ```
+#[allow(dead_code)]
+pub fn auto_filter<T, F>(data: Vec<T>, predicate: F) -> ParallelResult<Vec<T>>
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T) -> bool + Send + Sync,
+{
+    let config = optimized_config(data.len());
+    data.into_iter().par_filter(&config, predicate)
+}
+
+/// Sorts `data` using a config chosen by [`optimized_config`].
+///
+/// # Examples
+///
+/// ```
+/// let result = auto_sort(vec![3, 1, 2]);
+/// assert_eq!(result.into_inner(), vec![1, 2, 3]);
+/// ```
+#[allow(dead_code)]
+pub fn auto_sort<T>(data: Vec<T>) -> ParallelResult<Vec<T>>
+where
+    T: Ord + Clone + Send + Sync,
+{
+    let config = optimized_config(data.len());
+    data.into_iter().par_sort(&config)
+}
+
+/// [`parallel_transform`] with a config chosen by [`optimized_config`] for `data.len()`.
+///
+/// # Examples
+///
+/// ```
+/// let result = parallel_transform_auto(vec![1, 2, 3], |n| n * 2);
+/// assert_eq!(result.into_inner(), vec![2, 4, 6]);
+/// ```
+#[allow(dead_code)]
+pub fn parallel_transform_auto<T, U, F>(data: Vec<T>, transform: F) -> ParallelResult<Vec<U>>
+where
+    T: Send + Sync,
+    U: Send,
+    F: Fn(T) -> U + Send + Sync,
+{
+    let config = optimized_config(data.len());
+    parallel_transform(data, transform, &config)
+}
+
+/// [`parallel_aggregate`] with a config chosen by [`optimized_config`] for `data.len()`.
+///
+/// # Examples
+///
+/// ```
+/// let result = parallel_aggregate_auto((1..=100u32).collect(), 0u32, |acc, x| acc + x, |a, b| a + b);
+/// assert_eq!(result.into_inner(), 5050);
+/// ```
+#[allow(dead_code)]
+pub fn parallel_aggregate_auto<T, B, F, C>(
+    data: Vec<T>,
+    init: B,
+    aggregate: F,
+    combine: C,
+) -> ParallelResult<B>
+where
+    T: Send + Sync,
+    B: Send + Clone + Sync,
+    F: Fn(B, T) -> B + Send + Sync,
+    C: Fn(B, B) -> B + Send + Sync,
+{
+    let config = optimized_config(data.len());
+    parallel_aggregate(data, init, aggregate, combine, &config)
+}
+
+/// [`parallel_filter`] with a config chosen by [`optimized_config`] for `data.len()`.
+#[allow(dead_code)]
+pub fn parallel_filter_auto<T, F>(data: Vec<T>, predicate: F) -> ParallelResult<Vec<T>>
+where
+    T: Send + Sync + Clone,
+    F: Fn(&T) -> bool + Send + Sync,
+{
+    let config = optimized_config(data.len());
+    parallel_filter(data, predicate, &config)
+}
+
+/// [`parallel_transform_inplace`] with a config chosen by [`optimized_config`] for
+/// `data.len()`.
+#[allow(dead_code)]
+pub fn parallel_transform_inplace_auto<T, F>(data: &mut [T], transform: F) -> ParallelMetrics
+where
+    T: Send + Sync,
+    F: Fn(&mut T) + Send + Sync,
+{
+    let config = optimized_config(data.len());
+    parallel_transform_inplace(data, &config, transform)
 }
 
 /// Concurrent pipeline for chaining multiple parallel operations
@@ -1731,6 +3313,12 @@ impl<T: Send + Sync + Clone + 'static> ParallelPipeline<T> {
                 min_thread_work,
                 balancing_efficiency: avg_balancing_efficiency,
             },
+            timed_out: self.metrics_history.iter().any(|m| m.timed_out),
+            chunk_timings: self
+                .metrics_history
+                .iter()
+                .flat_map(|m| m.chunk_timings.iter().copied())
+                .collect(),
         }
     }
 }
@@ -1818,6 +3406,272 @@ mod tests {
         assert!(config.chunk_size > 0);
     }
 
+    #[test]
+    fn test_auto_map_handles_small_and_large_inputs() {
+        let small = auto_map((0..100u64).collect(), |x| x * 3);
+        assert_eq!(small.metrics.thread_count, 1);
+        assert_eq!(small.data, (0..100u64).map(|x| x * 3).collect::<Vec<_>>());
+
+        let large = auto_map((0..50_000u64).collect(), |x| x * 3);
+        assert_eq!(large.metrics.thread_count, rayon::current_num_threads());
+        assert_eq!(
+            large.data,
+            (0..50_000u64).map(|x| x * 3).collect::<Vec<_>>()
+        );
+
+        let evens = auto_filter((0..5_000u64).collect(), |x| x % 2 == 0);
+        assert_eq!(evens.data, (0..5_000u64).step_by(2).collect::<Vec<_>>());
+        let sorted = auto_sort((0..5_000u64).rev().collect());
+        assert_eq!(sorted.data, (0..5_000u64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_auto_dispatch_config_scales_with_input_size() {
+        let small = optimized_config(100);
+        let medium = optimized_config(10_000);
+        let large = optimized_config(1_000_000);
+
+        assert_eq!(small.min_parallel_size, usize::MAX);
+        assert!(medium.min_parallel_size < large.min_parallel_size);
+        assert!(medium.min_parallel_size < 10_000);
+        assert!(large.min_parallel_size < 1_000_000);
+        assert!(small.chunk_size <= medium.chunk_size);
+        assert!(medium.chunk_size <= large.chunk_size);
+        assert!(medium.max_chunk_size <= large.max_chunk_size);
+    }
+
+    #[test]
+    fn test_parallel_auto_variants_match_explicit_config() {
+        for len in [100u64, 20_000] {
+            let data: Vec<u64> = (0..len).collect();
+            let config = optimized_config(data.len());
+
+            assert_eq!(
+                parallel_transform_auto(data.clone(), |x| x * 7 + 1).data,
+                parallel_transform(data.clone(), |x| x * 7 + 1, &config).data
+            );
+            assert_eq!(
+                parallel_aggregate_auto(data.clone(), 0u64, |acc, x| acc + x, |a, b| a + b).data,
+                parallel_aggregate(data.clone(), 0u64, |acc, x| acc + x, |a, b| a + b, &config)
+                    .data
+            );
+            assert_eq!(
+                parallel_filter_auto(data.clone(), |x| x % 3 == 0).data,
+                parallel_filter(data.clone(), |x| x % 3 == 0, &config).data
+            );
+
+            let mut auto = data.clone();
+            let mut explicit = data;
+            parallel_transform_inplace_auto(&mut auto, |x| *x *= 2);
+            parallel_transform_inplace(&mut explicit, &config, |x| *x *= 2);
+            assert_eq!(auto, explicit);
+        }
+    }
+
+    #[test]
+    fn test_parallel_timeout_sets_timed_out() {
+        let config = ParallelConfig {
+            min_parallel_size: 1,
+            chunk_size: 1,
+            adaptive_chunk_sizing: false,
+            timeout: Some(Duration::from_millis(20)),
+            ..ParallelConfig::default()
+        };
+        let slow = |x: &i32| {
+            std::thread::sleep(Duration::from_millis(50));
+            *x % 2 == 0
+        };
+
+        // Four elements per pool thread, so only the first wave can start before the deadline
+        let items = rayon::current_num_threads() as i32 * 4;
+
+        let start = Instant::now();
+        let filtered = (0..items).par_filter(&config, slow);
+        assert!(filtered.metrics.timed_out);
+        assert!(filtered.data.is_empty());
+
+        let mapped = (0..items).par_map(&config, |x| slow(&x));
+        assert!(mapped.metrics.timed_out);
+        assert!(mapped.data.is_empty());
+        // Four waves of 50ms per call if nothing was skipped
+        assert!(start.elapsed() < Duration::from_millis(2 * 4 * 50));
+
+        let relaxed = ParallelConfig {
+            timeout: Some(Duration::from_secs(10)),
+            ..config
+        };
+        let completed = (0..8).par_map(&relaxed, |x| x * 2);
+        assert!(!completed.metrics.timed_out);
+        assert_eq!(completed.data, vec![0, 2, 4, 6, 8, 10, 12, 14]);
+    }
+
+    #[test]
+    fn test_timeout_covers_fold_flat_map_find_and_count() {
+        let config = ParallelConfig {
+            min_parallel_size: 1,
+            chunk_size: 1,
+            adaptive_chunk_sizing: false,
+            timeout: Some(Duration::from_millis(20)),
+            ..ParallelConfig::default()
+        };
+        let slow = |x: &i32| {
+            std::thread::sleep(Duration::from_millis(50));
+            *x
+        };
+        let items = rayon::current_num_threads() as i32 * 4;
+
+        let folded = (0..items).par_fold(&config, 0, |acc, x| acc + slow(&x), |a, b| a + b);
+        assert!(folded.metrics.timed_out);
+        assert_eq!(folded.data, 0);
+
+        let flattened = (0..items).par_flat_map(&config, |x| vec![slow(&x)]);
+        assert!(flattened.metrics.timed_out);
+        assert!(flattened.data.is_empty());
+
+        let found = (0..items).par_find(&config, |x| slow(x) < 0);
+        assert!(found.metrics.timed_out);
+        assert_eq!(found.data, None);
+
+        let counted = (0..items).par_count(&config, |x| slow(x) >= 0);
+        assert!(counted.metrics.timed_out);
+        assert_eq!(counted.data, 0);
+
+        let relaxed = ParallelConfig {
+            timeout: Some(Duration::from_secs(10)),
+            ..config
+        };
+        let folded = (0..8).par_fold(&relaxed, 0, |acc, x| acc + x, |a, b| a + b);
+        assert!(!folded.metrics.timed_out);
+        assert_eq!(folded.data, 28);
+        let flattened = (0..4).par_flat_map(&relaxed, |x| vec![x, x]);
+        assert!(!flattened.metrics.timed_out);
+        assert_eq!(flattened.data, vec![0, 0, 1, 1, 2, 2, 3, 3]);
+        let found = (0..8).par_find(&relaxed, |&x| x == 5);
+        assert!(!found.metrics.timed_out);
+        assert_eq!(found.data, Some(5));
+        let missing = (0..8).par_find(&relaxed, |&x| x > 100);
+        assert!(!missing.metrics.timed_out);
+        assert_eq!(missing.data, None);
+        let counted = (0..8).par_count(&relaxed, |&x| x % 2 == 0);
+        assert!(!counted.metrics.timed_out);
+        assert_eq!(counted.data, 4);
+    }
+
+    #[test]
+    fn test_par_map_collects_chunk_timings() {
+        let config = ParallelConfig::builder()
+            .min_parallel_size(1)
+            .chunk_size(100)
+            .adaptive(false)
+            .collect_chunk_timings(true)
+            .build()
+            .unwrap();
+
+        let result = (0..1050).par_map(&config, |x| x * 2);
+        assert_eq!(result.data.len(), 1050);
+        assert_eq!(result.data[1049], 2098);
+        assert_eq!(result.metrics.chunk_timings.len(), 11);
+
+        let untimed = (0..1050).par_map(&ParallelConfig::default(), |x| x * 2);
+        assert!(untimed.metrics.chunk_timings.is_empty());
+    }
+
+    #[test]
+    fn test_into_persistent_vector() {
+        let config = ParallelConfig {
+            min_parallel_size: 1,
+            ..ParallelConfig::default()
+        };
+        let (vector, metrics) = (0..100)
+            .par_map(&config, |x| x * 3)
+            .into_persistent_vector();
+
+        assert_eq!(vector.len(), 100);
+        assert_eq!(vector.to_vec(), (0..100).map(|x| x * 3).collect::<Vec<_>>());
+        assert!(!metrics.timed_out);
+    }
+
+    #[test]
+    fn test_write_json_streams_data_and_metrics() {
+        let config = ParallelConfig::builder()
+            .min_parallel_size(1)
+            .chunk_size(100)
+            .adaptive(false)
+            .collect_chunk_timings(true)
+            .build()
+            .unwrap();
+        let result = (0..1_000u32).par_map(&config, |x| format!("nfe-{}", x));
+
+        let mut buffer = Vec::new();
+        result.write_json(&mut buffer).unwrap();
+
+        let parsed: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+        let data = parsed["data"].as_array().unwrap();
+        assert_eq!(data.len(), 1_000);
+        assert_eq!(data[999], "nfe-999");
+
+        let metrics: ParallelMetrics = serde_json::from_value(parsed["metrics"].clone()).unwrap();
+        assert_eq!(metrics.total_time, result.metrics.total_time);
+        assert_eq!(metrics.thread_count, result.metrics.thread_count);
+        assert_eq!(metrics.throughput, result.metrics.throughput);
+        assert_eq!(metrics.chunk_timings, result.metrics.chunk_timings);
+        assert_eq!(metrics.chunk_timings.len(), 10);
+    }
+
+    #[test]
+    fn test_parallel_config_builder_valid() {
+        let config = ParallelConfig::builder()
+            .min_parallel_size(10)
+            .chunk_size(256)
+            .max_chunk_size(1024)
+            .adaptive(false)
+            .thread_pool_size(2)
+            .work_stealing(false)
+            .history_namespace("reports")
+            .build()
+            .unwrap();
+
+        assert_eq!(config.min_parallel_size, 10);
+        assert_eq!(config.chunk_size, 256);
+        assert_eq!(config.max_chunk_size, 1024);
+        assert!(!config.adaptive_chunk_sizing);
+        assert_eq!(config.thread_pool_size, 2);
+        assert!(!config.enable_work_stealing);
+        assert_eq!(config.history_namespace.as_deref(), Some("reports"));
+
+        let defaults = ParallelConfigBuilder::new().build().unwrap();
+        assert_eq!(defaults.chunk_size, ParallelConfig::default().chunk_size);
+    }
+
+    #[test]
+    fn test_parallel_config_builder_rejects_chunk_above_max() {
+        let err = ParallelConfig::builder()
+            .chunk_size(4096)
+            .max_chunk_size(1024)
+            .build()
+            .unwrap_err();
+        assert!(err.contains("chunk_size (4096)"));
+
+        assert!(ParallelConfig::builder().chunk_size(0).build().is_err());
+    }
+
+    #[test]
+    fn test_estimate_thread_count_respects_available_parallelism() {
+        for available in [0, 1, 2, 3] {
+            for data_size in [0, 999, 1_000, 9_999, 10_000, 99_999] {
+                let threads = estimate_thread_count_for(data_size, available);
+                assert!(threads >= 1);
+                assert!(threads <= available.max(1));
+            }
+            assert_eq!(estimate_thread_count_for(100_000, available), 0);
+        }
+        assert_eq!(estimate_thread_count_for(50_000, 2), 2);
+        assert_eq!(estimate_thread_count_for(50_000, 16), 4);
+
+        let available = std::thread::available_parallelism().map_or(1, |n| n.get());
+        assert!(optimized_config(50_000).thread_pool_size <= available);
+    }
+
     #[test]
     fn test_performance_metrics() {
         let data = (0..1000).collect::<Vec<_>>();
@@ -1861,6 +3715,35 @@ mod tests {
         assert_eq!(result.data, Some(15));
     }
 
+    #[test]
+    fn test_par_reduce_checkpointed_sequential_running_sum() {
+        let data: Vec<u32> = (1..=10).collect();
+        let config = ParallelConfig::default();
+
+        let result = data
+            .into_iter()
+            .par_reduce_checkpointed(&config, |a, b| a + b, 3);
+
+        assert_eq!(result.data, (Some(55), vec![6, 21, 45]));
+        assert_eq!(result.metrics.thread_count, 1);
+    }
+
+    #[test]
+    fn test_par_reduce_checkpointed_parallel_matches_sequential() {
+        let data: Vec<u64> = (0..5000).collect();
+        let config = ParallelConfig::default();
+        let expected: Vec<u64> = (1..=5000u64 / 700)
+            .map(|chunks| (0..chunks * 700).sum())
+            .collect();
+
+        let result = data
+            .into_iter()
+            .par_reduce_checkpointed(&config, |a, b| a + b, 700);
+
+        assert_eq!(result.data.0, Some((0..5000u64).sum()));
+        assert_eq!(result.data.1, expected);
+    }
+
     // Tests for ParallelPipeline metrics accumulation
 
     #[test]
@@ -2156,6 +4039,459 @@ mod tests {
         assert!(result.data.is_none());
     }
 
+    #[test]
+    fn test_par_count_matches_reference() {
+        let data: Vec<i32> = (0..5000).collect();
+        let config = ParallelConfig::default();
+        let expected = data.iter().filter(|&&x| x % 3 == 0).count();
+
+        let result = data.into_iter().par_count(&config, |&x| x % 3 == 0);
+
+        assert_eq!(result.data, expected);
+    }
+
+    #[test]
+    fn test_par_count_sequential_path() {
+        let data = vec![1, 2, 3, 4, 5];
+        let config = ParallelConfig::default();
+
+        let result = data.into_iter().par_count(&config, |&x| x > 2);
+
+        assert_eq!(result.data, 3);
+        assert_eq!(result.metrics.thread_count, 1);
+    }
+
+    #[test]
+    fn test_par_windows_sums_match_sequential_reference() {
+        let data: Vec<u64> = (0..5000).collect();
+        let config = ParallelConfig::default();
+        let expected: Vec<u64> = data.windows(7).map(|w| w.iter().sum()).collect();
+
+        let result = par_windows(&data, 7, &config, |w| w.iter().sum::<u64>());
+
+        assert_eq!(result.data, expected);
+        assert_eq!(result.data.len(), data.len() - 6);
+    }
+
+    #[test]
+    fn test_par_windows_sequential_path() {
+        let data = vec![1, 2, 3, 4, 5];
+        let config = ParallelConfig::default();
+
+        let result = par_windows(&data, 3, &config, |w| w.iter().sum::<i32>());
+
+        assert_eq!(result.data, vec![6, 9, 12]);
+        assert_eq!(result.metrics.thread_count, 1);
+    }
+
+    #[test]
+    fn test_par_windows_degenerate_window_sizes() {
+        let data = vec![1, 2, 3];
+        let config = ParallelConfig::default();
+
+        assert!(par_windows(&data, 0, &config, |w| w.len()).data.is_empty());
+        assert!(par_windows(&data, 4, &config, |w| w.len()).data.is_empty());
+        assert_eq!(par_windows(&data, 3, &config, |w| w.len()).data, vec![3]);
+    }
+
+    fn nested_loop_join(left: &[(u32, u32)], right: &[(u32, u32)]) -> Vec<((u32, u32), (u32, u32))> {
+        left.iter()
+            .flat_map(|l| {
+                right
+                    .iter()
+                    .filter(move |r| r.0 == l.0)
+                    .map(move |r| (*l, *r))
+            })
+            .collect()
+    }
+
+    /// Deliberately not `Clone`, so `par_map_ref` can only ever borrow it
+    #[derive(Debug)]
+    struct Ledger {
+        id: u32,
+        entries: Vec<i64>,
+    }
+
+    #[test]
+    fn test_par_map_ref_borrows_non_clone_elements() {
+        let config = ParallelConfig {
+            min_parallel_size: 16,
+            chunk_size: 8,
+            ..ParallelConfig::default()
+        };
+        let ledgers: Vec<Ledger> = (0..200u32)
+            .map(|id| Ledger {
+                id,
+                entries: (0..id as i64 % 7).collect(),
+            })
+            .collect();
+        let expected: Vec<(u32, i64)> = ledgers
+            .iter()
+            .map(|l| (l.id, l.entries.iter().sum()))
+            .collect();
+
+        let totals = par_map_ref(&ledgers, &config, |l| (l.id, l.entries.iter().sum::<i64>()));
+        assert_eq!(totals.data, expected);
+
+        let entries: Vec<&[i64]> = par_map_ref(&ledgers, &config, |l| l.entries.as_slice()).data;
+        assert!(entries
+            .iter()
+            .zip(&ledgers)
+            .all(|(borrowed, l)| std::ptr::eq(borrowed.as_ptr(), l.entries.as_ptr())));
+
+        let small = par_map_ref(&ledgers[..4], &config, |l| l.id);
+        assert_eq!(small.metrics.thread_count, 1);
+        assert_eq!(small.data, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_par_hash_is_deterministic_across_runs_and_thresholds() {
+        let data: Vec<u64> = (0..10_000).collect();
+        let sequential = ParallelConfig {
+            min_parallel_size: usize::MAX,
+            ..ParallelConfig::default()
+        };
+        let parallel = ParallelConfig {
+            min_parallel_size: 1,
+            chunk_size: 64,
+            ..ParallelConfig::default()
+        };
+
+        let expected = par_hash(&data, &sequential, |x| *x);
+        assert_eq!(expected.metrics.thread_count, 1);
+        for _ in 0..5 {
+            assert_eq!(par_hash(&data, &parallel, |x| *x).data, expected.data);
+        }
+
+        let mut reversed = data.clone();
+        reversed.reverse();
+        assert_eq!(par_hash(&reversed, &parallel, |x| *x).data, expected.data);
+        assert_ne!(par_hash(&data[1..], &parallel, |x| *x).data, expected.data);
+    }
+
+    #[test]
+    fn test_par_map_into_reuses_output_buffer() {
+        let config = ParallelConfig {
+            min_parallel_size: 8,
+            chunk_size: 4,
+            ..ParallelConfig::default()
+        };
+        let mut out: Vec<u64> = Vec::with_capacity(64);
+        let initial_capacity = out.capacity();
+        let buffer_ptr = out.as_ptr();
+
+        par_map_into((0..64u64).collect(), &mut out, &config, |x| x * 2);
+        assert_eq!(out, (0..64u64).map(|x| x * 2).collect::<Vec<_>>());
+
+        par_map_into((100..132u64).collect(), &mut out, &config, |x| x + 1);
+        assert_eq!(out, (101..133u64).collect::<Vec<_>>());
+
+        par_map_into(vec![7u64, 8, 9], &mut out, &config, |x| x * x);
+        assert_eq!(out, vec![49, 64, 81]);
+
+        assert_eq!(out.capacity(), initial_capacity);
+        assert_eq!(out.as_ptr(), buffer_ptr);
+    }
+
+    #[test]
+    fn test_par_join_matches_sequential_inner_join() {
+        // Duplicate keys on both sides, plus keys present on only one side
+        let left: Vec<(u32, u32)> = (0..4000).map(|i| (i % 700, i)).collect();
+        let right: Vec<(u32, u32)> = (0..3000).map(|i| ((i * 7) % 900, i)).collect();
+        let config = ParallelConfig::default();
+
+        let result = par_join(left.clone(), right.clone(), |l| l.0, |r| r.0, &config);
+
+        assert_eq!(result.data, nested_loop_join(&left, &right));
+        assert!(result.metrics.thread_count >= 1);
+    }
+
+    #[test]
+    fn test_par_join_is_independent_of_input_order() {
+        let left: Vec<(u32, u32)> = (0..3000).map(|i| (i % 300, i)).collect();
+        let right: Vec<(u32, u32)> = (0..2000).map(|i| (i % 450, i)).collect();
+        let config = ParallelConfig::default();
+
+        let mut forward = par_join(left.clone(), right.clone(), |l| l.0, |r| r.0, &config).data;
+        let mut reversed = par_join(
+            left.into_iter().rev().collect(),
+            right.into_iter().rev().collect(),
+            |l| l.0,
+            |r| r.0,
+            &config,
+        )
+        .data;
+
+        forward.sort_unstable();
+        reversed.sort_unstable();
+        assert_eq!(forward, reversed);
+        assert!(!forward.is_empty());
+    }
+
+    #[test]
+    fn test_par_join_sequential_path() {
+        let left = vec![(1, 1), (2, 2), (3, 3)];
+        let right = vec![(2, 20), (1, 10), (2, 21), (4, 40)];
+        let config = ParallelConfig::default();
+
+        let result = par_join(left.clone(), right.clone(), |l| l.0, |r| r.0, &config);
+
+        assert_eq!(
+            result.data,
+            vec![((1, 1), (1, 10)), ((2, 2), (2, 20)), ((2, 2), (2, 21))]
+        );
+        assert_eq!(result.data, nested_loop_join(&left, &right));
+        assert_eq!(result.metrics.thread_count, 1);
+    }
+
+    #[test]
+    fn test_par_bucket_by_splits_into_disjoint_buckets() {
+        let config = ParallelConfig {
+            min_parallel_size: 10,
+            chunk_size: 16,
+            ..ParallelConfig::default()
+        };
+        let ufs = ["SP", "RJ", "MG"];
+        let docs: Vec<(&str, u32)> = (0..3_000u32).map(|i| (ufs[(i % 3) as usize], i)).collect();
+
+        let buckets = docs.into_iter().par_bucket_by(&config, |doc| doc.0, false);
+        assert_eq!(buckets.data.len(), 3);
+        for (index, uf) in ufs.iter().enumerate() {
+            let mut ids: Vec<u32> = buckets.data[uf].iter().map(|doc| doc.1).collect();
+            assert!(buckets.data[uf].iter().all(|doc| doc.0 == *uf));
+            ids.sort_unstable();
+            let expected: Vec<u32> = (0..3_000u32).filter(|i| *i as usize % 3 == index).collect();
+            assert_eq!(ids, expected);
+        }
+    }
+
+    #[test]
+    fn test_par_bucket_by_stable_keeps_input_order() {
+        let docs: Vec<(&str, u32)> = (0..3_000u32)
+            .map(|i| (["SP", "RJ", "MG"][(i * 7 % 3) as usize], 3_000 - i))
+            .collect();
+        let expected = |uf: &str| -> Vec<(&str, u32)> {
+            docs.iter().filter(|doc| doc.0 == uf).cloned().collect()
+        };
+
+        for min_parallel_size in [10, usize::MAX] {
+            let config = ParallelConfig {
+                min_parallel_size,
+                chunk_size: 16,
+                ..ParallelConfig::default()
+            };
+            let buckets = docs
+                .clone()
+                .into_iter()
+                .par_bucket_by(&config, |doc| doc.0, true);
+            for uf in ["SP", "RJ", "MG"] {
+                assert_eq!(buckets.data[uf], expected(uf));
+            }
+        }
+    }
+
+    #[test]
+    fn test_par_fold_map_counts_match_sequential_reference() {
+        let data: Vec<u32> = (0..20_000u32).map(|i| i * 31 % 97).collect();
+        let mut expected: HashMap<u32, usize> = HashMap::new();
+        for value in &data {
+            *expected.entry(value % 13).or_default() += 1;
+        }
+
+        for min_parallel_size in [1, usize::MAX] {
+            let config = ParallelConfig {
+                min_parallel_size,
+                chunk_size: 64,
+                ..ParallelConfig::default()
+            };
+            let counts = data.clone().into_iter().par_fold_map(
+                &config,
+                |value| value % 13,
+                |_| 1usize,
+                |x, y| x + y,
+            );
+            assert_eq!(counts.data, expected);
+        }
+    }
+
+    #[test]
+    fn test_par_unique_keeps_first_occurrence_order() {
+        for min_parallel_size in [1, usize::MAX] {
+            let config = ParallelConfig {
+                min_parallel_size,
+                chunk_size: 1,
+                ..ParallelConfig::default()
+            };
+            let unique = vec![3, 1, 3, 2, 1].into_iter().par_unique(&config);
+            assert_eq!(unique.data, vec![3, 1, 2]);
+
+            let words: Vec<String> = (0..5_000)
+                .map(|i| format!("cnpj-{}", (i * 7) % 1_000))
+                .collect();
+            let expected: Vec<String> = (0..1_000)
+                .map(|i| format!("cnpj-{}", (i * 7) % 1_000))
+                .collect();
+            assert_eq!(words.into_iter().par_unique(&config).data, expected);
+        }
+    }
+
+    #[test]
+    fn test_par_reduce_by_key_matches_sequential_sums() {
+        let records: Vec<(String, u64)> = (0..10_000u64)
+            .map(|i| (format!("cnpj-{:02}", i % 37), i * 3 + 1))
+            .collect();
+        let mut expected: HashMap<String, u64> = HashMap::new();
+        for (cnpj, value) in &records {
+            *expected.entry(cnpj.clone()).or_default() += value;
+        }
+
+        let parallel_config = ParallelConfig {
+            min_parallel_size: 100,
+            chunk_size: 64,
+            ..ParallelConfig::default()
+        };
+        let parallel = par_reduce_by_key(
+            records.clone(),
+            |r| r.0.clone(),
+            |r| r.1,
+            |a, b| a + b,
+            &parallel_config,
+        );
+        assert_eq!(parallel.data, expected);
+
+        let sequential_config = ParallelConfig {
+            min_parallel_size: usize::MAX,
+            ..ParallelConfig::default()
+        };
+        let sequential = par_reduce_by_key(
+            records,
+            |r| r.0.clone(),
+            |r| r.1,
+            |a, b| a + b,
+            &sequential_config,
+        );
+        assert_eq!(sequential.metrics.thread_count, 1);
+        assert_eq!(sequential.data, expected);
+    }
+
+    #[test]
+    fn test_par_validate_transform_splits_valid_and_invalid() {
+        // (numero, valor_total); odd numbers are missing their total, multiples of 7 are negative
+        let records: Vec<(u32, Option<i64>)> = (0..2_000u32)
+            .map(|i| {
+                let valor = match i {
+                    i if i % 2 == 1 => None,
+                    i if i % 7 == 0 => Some(-(i as i64)),
+                    i => Some(i as i64 * 100),
+                };
+                (i, valor)
+            })
+            .collect();
+        let validate = |record: &(u32, Option<i64>)| -> Vec<ValidationError> {
+            match record.1 {
+                None => vec![ValidationError::new(
+                    "valor_total",
+                    "REQUIRED",
+                    "valor_total is required",
+                )],
+                Some(valor) if valor < 0 => vec![ValidationError::new(
+                    "valor_total",
+                    "NEGATIVE",
+                    "valor_total must not be negative",
+                )],
+                Some(_) => Vec::new(),
+            }
+        };
+        let transform =
+            |record: (u32, Option<i64>)| format!("NF-{}:{}", record.0, record.1.unwrap());
+
+        let expected_valid: Vec<String> = records
+            .iter()
+            .filter(|r| validate(r).is_empty())
+            .map(|r| transform(*r))
+            .collect();
+        let expected_invalid: Vec<u32> = records
+            .iter()
+            .filter(|r| !validate(r).is_empty())
+            .map(|r| r.0)
+            .collect();
+
+        for min_parallel_size in [64, usize::MAX] {
+            let config = ParallelConfig {
+                min_parallel_size,
+                chunk_size: 32,
+                ..ParallelConfig::default()
+            };
+            let (valid, invalid) =
+                par_validate_transform(records.clone(), validate, transform, &config).data;
+
+            assert_eq!(valid, expected_valid);
+            assert_eq!(
+                invalid.iter().map(|(r, _)| r.0).collect::<Vec<_>>(),
+                expected_invalid
+            );
+            assert!(invalid.iter().all(|(r, errors)| {
+                let code = match r.1 {
+                    None => "REQUIRED",
+                    Some(_) => "NEGATIVE",
+                };
+                errors.len() == 1 && errors[0].code == code
+            }));
+        }
+    }
+
+    /// Serializes tests that read or reset the global performance history.
+    static HISTORY_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn test_history_namespaces_are_isolated() {
+        let _guard = HISTORY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let config_a = ParallelConfig {
+            min_parallel_size: 10,
+            history_namespace: Some("test_ns_a".to_string()),
+            ..ParallelConfig::default()
+        };
+        let config_b = ParallelConfig {
+            history_namespace: Some("test_ns_b".to_string()),
+            ..config_a.clone()
+        };
+        let key_a = performance_history_key::<u64>(&config_a, "par_map");
+        let key_b = performance_history_key::<u64>(&config_b, "par_map");
+        assert_ne!(key_a, key_b);
+
+        let data: Vec<u64> = (0..2000).collect();
+        let _ = data.clone().into_iter().par_map(&config_a, |x| x + 1);
+        let _ = data.clone().into_iter().par_map(&config_a, |x| x + 1);
+        let _ = data.into_iter().par_map(&config_b, |x| x * 2);
+
+        let history = get_performance_history();
+        let map = history.read().unwrap();
+        assert_eq!(map.get(&key_a).map(Vec::len), Some(2));
+        assert_eq!(map.get(&key_b).map(Vec::len), Some(1));
+    }
+
+    #[test]
+    fn test_clear_performance_history() {
+        let _guard = HISTORY_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let config = ParallelConfig {
+            min_parallel_size: 10,
+            history_namespace: Some("test_clear_history".to_string()),
+            ..ParallelConfig::default()
+        };
+
+        let key = performance_history_key::<u64>(&config, "par_map");
+        let recorded = || get_performance_history().read().unwrap().contains_key(&key);
+
+        let data: Vec<u64> = (0..2000).collect();
+        let _ = data.into_iter().par_map(&config, |x| x + 1);
+        assert!(recorded());
+        assert!(performance_history_len() > 0);
+
+        // Other tests may record history concurrently, so only this test's key is checked
+        clear_performance_history();
+        assert!(!recorded());
+    }
+
     #[test]
     fn test_dynamic_load_balancer_basic() {
         let balancer = DynamicLoadBalancer::new(0.8);
@@ -552,7 +552,8 @@ fn escape_xml(input: &str) -> String {
     input.replace("&", "&amp;").replace("<", "&lt;").replace(">", "&gt;").replace("\"", "&quot;").replace("'", "&apos;")
 }
 
-fn escape_csv_field(field: &str) -> String {
+/// Quotes a CSV field per RFC 4180 when it contains a comma, quote or line break.
+pub(crate) fn escape_csv_field(field: &str) -> String {
     if field.contains(',') || field.contains('"') || field.contains('\n') || field.contains('\r') {
         format!("\"{}\"", field.replace("\"", "\"\""))
     } else {
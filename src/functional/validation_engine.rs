@@ -7,8 +7,12 @@
 #![allow(dead_code)]
 
 use std::collections::HashMap;
+use std::sync::Arc;
+
+use rust_decimal::Decimal;
 
 use crate::functional::validation_rules::{ValidationError, ValidationResult, ValidationRule};
+use crate::models::nfe_document::money::parse_money;
 
 /// Validation pipeline configuration
 #[derive(Debug, Clone)]
@@ -17,6 +21,9 @@ pub struct ValidationConfig {
     pub fail_fast: bool,
     /// Maximum number of validation errors to collect
     pub max_errors: Option<usize>,
+    /// Maximum number of errors a single `validate_field` call reports, so one field
+    /// cannot flood a multi-field response
+    pub max_errors_per_field: Option<usize>,
     /// Enable parallel validation for large datasets
     pub parallel_validation: bool,
 }
@@ -27,6 +34,7 @@ impl Default for ValidationConfig {
     /// The defaults are:
     /// - `fail_fast = true`
     /// - `max_errors = Some(10)`
+    /// - `max_errors_per_field = None`
     /// - `parallel_validation = false`
     ///
     /// # Examples
@@ -35,12 +43,14 @@ impl Default for ValidationConfig {
     /// let cfg = ValidationConfig::default();
     /// assert!(cfg.fail_fast);
     /// assert_eq!(cfg.max_errors, Some(10));
+    /// assert_eq!(cfg.max_errors_per_field, None);
     /// assert!(!cfg.parallel_validation);
     /// ```
     fn default() -> Self {
         Self {
             fail_fast: true,
             max_errors: Some(10),
+            max_errors_per_field: None,
             parallel_validation: false,
         }
     }
@@ -217,11 +227,158 @@ impl<T> ValidationOutcome<T> {
         }
         self
     }
+
+    /// Converts the outcome into a `Result` so it can be propagated with `?`.
+    ///
+    /// Returns `Ok(value)` when the outcome is valid and carries a value, `Err(errors)` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// assert_eq!(ValidationOutcome::success(42).into_result(), Ok(42));
+    /// assert!(ValidationOutcome::<i32>::failure(vec![]).into_result().is_err());
+    /// ```
+    pub fn into_result(self) -> Result<T, Vec<ValidationError>> {
+        match self.value {
+            Some(value) if self.is_valid => Ok(value),
+            _ => Err(self.errors),
+        }
+    }
+
+    /// Returns the value of a valid outcome, discarding any errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// assert_eq!(ValidationOutcome::success(42).ok(), Some(42));
+    /// assert_eq!(ValidationOutcome::<i32>::failure(vec![]).ok(), None);
+    /// ```
+    pub fn ok(self) -> Option<T> {
+        self.into_result().ok()
+    }
+}
+
+/// Read access to the errors of a validation result regardless of its value type
+///
+/// Lets outcomes over different `T` be aggregated through `&dyn HasErrors`, see
+/// [`aggregate_errors`] and [`all_valid`].
+pub trait HasErrors {
+    /// Errors collected by this result
+    fn errors(&self) -> &[ValidationError];
+    /// Whether this result passed validation
+    fn is_valid(&self) -> bool;
+}
+
+impl<T> HasErrors for ValidationOutcome<T> {
+    fn errors(&self) -> &[ValidationError] {
+        &self.errors
+    }
+
+    fn is_valid(&self) -> bool {
+        self.is_valid
+    }
+}
+
+/// Collects the errors of outcomes with different value types into one list, in order.
+///
+/// # Examples
+///
+/// ```
+/// let name = ValidationOutcome::success("Ana".to_string());
+/// let age = ValidationOutcome::<i32>::failure(vec![ValidationError::new("age", "RANGE", "age out of range")]);
+/// let errors = aggregate_errors(&[&name, &age]);
+/// assert_eq!(errors.len(), 1);
+/// ```
+pub fn aggregate_errors(outcomes: &[&dyn HasErrors]) -> Vec<ValidationError> {
+    outcomes
+        .iter()
+        .flat_map(|outcome| outcome.errors().iter().cloned())
+        .collect()
+}
+
+/// Whether every outcome passed validation; `true` for an empty slice.
+pub fn all_valid(outcomes: &[&dyn HasErrors]) -> bool {
+    outcomes.iter().all(|outcome| outcome.is_valid())
+}
+
+/// Domain types that validate themselves as a whole
+///
+/// Implementations report errors with field paths built from `ctx` (see
+/// [`ValidationContext::child`]), so a type can validate nested members by passing them a
+/// child context.
+pub trait Validate {
+    fn validate(&self, ctx: &ValidationContext) -> ValidationOutcome<()>;
+}
+
+/// Localized error-message templates keyed by error code and locale
+///
+/// Templates use `{}` as the placeholder for the failing field path, like
+/// [`Custom`](crate::functional::validation_rules::Custom) messages. Lookups use the catalog's
+/// active locale.
+#[derive(Debug, Clone)]
+pub struct MessageCatalog {
+    locale: String,
+    templates: HashMap<(String, String), String>,
+}
+
+impl MessageCatalog {
+    /// Creates an empty catalog rendering messages in `locale` (e.g. `"pt-BR"`).
+    pub fn new(locale: &str) -> Self {
+        Self {
+            locale: locale.to_string(),
+            templates: HashMap::new(),
+        }
+    }
+
+    /// Registers the template used for `code` in `locale`, replacing any previous one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let catalog = MessageCatalog::new("pt-BR")
+    ///     .with_message("pt-BR", "REQUIRED", "{} é obrigatório")
+    ///     .with_message("en-US", "REQUIRED", "{} is required");
+    /// ```
+    pub fn with_message(mut self, locale: &str, code: &str, template: &str) -> Self {
+        self.templates
+            .insert((code.to_string(), locale.to_string()), template.to_string());
+        self
+    }
+
+    /// Switches the locale used for lookups.
+    pub fn with_locale(mut self, locale: &str) -> Self {
+        self.locale = locale.to_string();
+        self
+    }
+
+    /// The locale used for lookups.
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Renders the active-locale template for `code` with `field_path` interpolated, if one
+    /// is registered.
+    pub fn render(&self, code: &str, field_path: &str) -> Option<String> {
+        self.templates
+            .get(&(code.to_string(), self.locale.clone()))
+            .map(|template| template.replace("{}", field_path))
+    }
+
+    /// Replaces the message of `error` with its localized template, keeping the rule's
+    /// built-in message when the catalog has no entry for its code.
+    pub fn localize(&self, mut error: ValidationError) -> ValidationError {
+        if let Some(message) = self.render(&error.code, &error.field) {
+            error.message = message;
+        }
+        error
+    }
 }
 
 /// Iterator-based validation engine
 pub struct ValidationEngine<T> {
     config: ValidationConfig,
+    /// Localized messages applied to reported errors, see [`ValidationEngine::with_catalog`]
+    catalog: Option<Arc<MessageCatalog>>,
     _phantom: std::marker::PhantomData<T>,
 }
 
@@ -238,6 +395,7 @@ impl<T> ValidationEngine<T> {
     pub fn new() -> Self {
         Self {
             config: ValidationConfig::default(),
+            catalog: None,
             _phantom: std::marker::PhantomData,
         }
     }
@@ -250,6 +408,7 @@ impl<T> ValidationEngine<T> {
     /// let cfg = ValidationConfig {
     ///     fail_fast: false,
     ///     max_errors: Some(5),
+    ///     max_errors_per_field: Some(2),
     ///     parallel_validation: true,
     /// };
     /// /// let engine: ValidationEngine<String> = ValidationEngine::with_config(cfg);
@@ -258,15 +417,43 @@ impl<T> ValidationEngine<T> {
     pub fn with_config(config: ValidationConfig) -> Self {
         Self {
             config,
+            catalog: None,
             _phantom: std::marker::PhantomData,
         }
     }
 
+    /// Localizes the messages of reported errors with `catalog`.
+    ///
+    /// When a rule fails, the engine looks up the catalog template for the error code in the
+    /// catalog's locale and interpolates the field path; errors without an entry keep the
+    /// rule's built-in message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let catalog = MessageCatalog::new("pt-BR").with_message("pt-BR", "REQUIRED", "{} é obrigatório");
+    /// let engine = ValidationEngine::<String>::new().with_catalog(catalog);
+    /// let outcome = engine.validate_field(&String::new(), "nome", vec![Required]);
+    /// assert_eq!(outcome.errors[0].message, "nome é obrigatório");
+    /// ```
+    pub fn with_catalog(mut self, catalog: MessageCatalog) -> Self {
+        self.catalog = Some(Arc::new(catalog));
+        self
+    }
+
+    /// Applies the configured catalog, if any, to `error`.
+    fn localize(&self, error: ValidationError) -> ValidationError {
+        match &self.catalog {
+            Some(catalog) => catalog.localize(error),
+            None => error,
+        }
+    }
+
     /// Validate a single field against an iterator of validation rules and collect any errors.
     ///
     /// This applies each provided rule to `value` using a context for `field_name`. Collected errors
     /// are returned if any rules fail; validation honors the engine configuration (stopping early if
-    /// `fail_fast` is true and respecting `max_errors` when set).
+    /// `fail_fast` is true and respecting `max_errors` and `max_errors_per_field` when set).
     ///
     /// # Examples
     ///
@@ -300,7 +487,7 @@ impl<T> ValidationEngine<T> {
                     // Rule passed, continue
                 }
                 Err(error) => {
-                    errors.push(error);
+                    errors.push(self.localize(error));
 
                     // Check if we should stop on first error
                     if self.config.fail_fast {
@@ -313,6 +500,13 @@ impl<T> ValidationEngine<T> {
                             break;
                         }
                     }
+
+                    // Check if this field has used up its own error budget
+                    if let Some(max) = self.config.max_errors_per_field {
+                        if errors.len() >= max {
+                            break;
+                        }
+                    }
                 }
             }
         }
@@ -372,6 +566,93 @@ impl<T> ValidationEngine<T> {
             ValidationOutcome::success(results)
         }
     }
+
+    /// Validate a [`Validate`] value from the root context.
+    ///
+    /// The errors it reports are trimmed to honor the engine configuration: only the first
+    /// error is kept when `fail_fast` is set, and at most `max_errors` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let engine = validator::<()>();
+    /// let outcome = engine.run(&new_document);
+    /// if !outcome.is_valid {
+    ///     println!("errors: {:?}", outcome.errors);
+    /// }
+    /// ```
+    pub fn run<V: Validate>(&self, value: &V) -> ValidationOutcome<()> {
+        let mut outcome = value.validate(&ValidationContext::new(""));
+
+        let limit = if self.config.fail_fast {
+            Some(1)
+        } else {
+            self.config.max_errors
+        };
+        if let Some(limit) = limit {
+            outcome.errors.truncate(limit);
+        }
+        if self.catalog.is_some() {
+            outcome.errors = outcome
+                .errors
+                .into_iter()
+                .map(|error| self.localize(error))
+                .collect();
+        }
+
+        outcome
+    }
+
+    /// Validate every entry of a field map with rules chosen per key.
+    ///
+    /// `rule_for` receives each key and returns the rules for that entry; keys are visited in
+    /// sorted order so the aggregated errors are deterministic. Each error carries the map key
+    /// as its field path. Honors `fail_fast` and `max_errors` like [`validate_fields`](Self::validate_fields).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let engine = validator::<String>();
+    /// let mut fields = HashMap::new();
+    /// fields.insert("email".to_string(), "user@example.com".to_string());
+    /// let outcome = engine.validate_map(&fields, |_| vec![Email]);
+    /// assert!(outcome.is_valid);
+    /// ```
+    pub fn validate_map<R>(
+        &self,
+        map: &HashMap<String, T>,
+        rule_for: impl Fn(&str) -> Vec<R>,
+    ) -> ValidationOutcome<()>
+    where
+        R: ValidationRule<T>,
+    {
+        let mut keys: Vec<&String> = map.keys().collect();
+        keys.sort();
+
+        let mut all_errors = Vec::new();
+
+        for key in keys {
+            let field_result = self.validate_field(&map[key], key, rule_for(key));
+            all_errors.extend(field_result.errors);
+
+            if self.config.fail_fast && !all_errors.is_empty() {
+                break;
+            }
+
+            if let Some(max) = self.config.max_errors {
+                if all_errors.len() >= max {
+                    all_errors.truncate(max);
+                    break;
+                }
+            }
+        }
+
+        if all_errors.is_empty() {
+            ValidationOutcome::success(())
+        } else {
+            ValidationOutcome::failure(all_errors)
+        }
+    }
 }
 
 /// Creates a validation rule that applies the provided rules only when a predicate is true.
@@ -687,14 +968,154 @@ where
     )
 }
 
+/// Rule produced by [`compare_numeric_fields`]
+struct NumericComparisonRule<F> {
+    field1: String,
+    field2: String,
+    comparator: F,
+}
+
+impl<F> ValidationRule<HashMap<String, String>> for NumericComparisonRule<F>
+where
+    F: Fn(Decimal, Decimal) -> bool,
+{
+    fn validate(&self, value: &HashMap<String, String>, field_name: &str) -> ValidationResult<()> {
+        let (Some(raw1), Some(raw2)) = (value.get(&self.field1), value.get(&self.field2)) else {
+            // If either field is missing, comparison can't fail
+            return Ok(());
+        };
+
+        let parse = |field: &str, raw: &str| {
+            parse_money(raw).map_err(|_| {
+                ValidationError::new(
+                    field_name,
+                    "NON_NUMERIC_FIELD",
+                    &format!("Field {} value '{}' is not numeric", field, raw),
+                )
+            })
+        };
+        let val1 = parse(&self.field1, raw1)?;
+        let val2 = parse(&self.field2, raw2)?;
+
+        if (self.comparator)(val1, val2) {
+            Ok(())
+        } else {
+            Err(ValidationError::new(
+                field_name,
+                "FIELD_COMPARISON_FAILED",
+                &format!(
+                    "Field {} does not satisfy comparison with {}",
+                    self.field1, self.field2
+                ),
+            ))
+        }
+    }
+}
+
+/// Like [`compare_fields`] for maps holding every value as a string: both fields are parsed
+/// as `Decimal` with [`parse_money`] before `comparator` is applied.
+///
+/// Fails with `NON_NUMERIC_FIELD` when either present value can't be parsed and with
+/// `FIELD_COMPARISON_FAILED` when the comparison doesn't hold; missing fields pass.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// // Ensure valor_desconto <= valor_produtos
+/// let rule = compare_numeric_fields("valor_desconto", "valor_produtos", |d, p| d <= p);
+/// ```
+pub fn compare_numeric_fields<F>(
+    field1: &str,
+    field2: &str,
+    comparator: F,
+) -> impl ValidationRule<HashMap<String, String>>
+where
+    F: Fn(Decimal, Decimal) -> bool,
+{
+    NumericComparisonRule {
+        field1: field1.to_string(),
+        field2: field2.to_string(),
+        comparator,
+    }
+}
+
+/// Rule adapter produced by [`optional`]
+struct OptionalRule<R> {
+    rule: R,
+}
+
+impl<T, R> ValidationRule<Option<T>> for OptionalRule<R>
+where
+    R: ValidationRule<T>,
+{
+    fn validate(&self, value: &Option<T>, field_name: &str) -> ValidationResult<()> {
+        match value {
+            Some(inner) => self.rule.validate(inner, field_name),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Lifts a rule over `T` to an `Option<T>` field: validated if present, skipped if `None`.
+///
+/// Errors from the inner rule are returned unchanged.
+///
+/// # Examples
+///
+/// ```
+/// let rule = optional(Email);
+/// assert!(rule.validate(&None, "email").is_ok());
+/// assert!(rule.validate(&Some("a@b.com".to_string()), "email").is_ok());
+/// assert!(rule.validate(&Some("bad".to_string()), "email").is_err());
+/// ```
+pub fn optional<T, R>(rule: R) -> impl ValidationRule<Option<T>>
+where
+    R: ValidationRule<T>,
+{
+    OptionalRule { rule }
+}
+
+/// Creates a rule that fails with code `REQUIRED` when an `Option` field is `None`.
+///
+/// # Examples
+///
+/// ```
+/// let rule = required_some::<String>();
+/// assert!(rule.validate(&Some("x".to_string()), "name").is_ok());
+/// assert!(rule.validate(&None, "name").is_err());
+/// ```
+pub fn required_some<T>() -> impl ValidationRule<Option<T>> {
+    crate::functional::validation_rules::Custom::new(
+        |value: &Option<T>| value.is_some(),
+        "REQUIRED",
+        "{} is required",
+    )
+}
+
+/// Progress callback invoked with `(processed, errors_so_far)`
+pub type ValidationProgressCallback = Arc<dyn Fn(usize, usize) + Send + Sync>;
+
+/// Boxed check run by a [`ValidationPipeline`] validator
+type PipelineCheck<T> = Box<dyn Fn(&T) -> ValidationResult<()> + Send + Sync>;
+
+/// A pipeline validator and whether its errors let the item through
+struct PipelineValidator<T> {
+    check: PipelineCheck<T>,
+    /// Fail-open (advisory) validators only log and count their errors
+    fail_open: bool,
+}
+
 /// Iterator-based validation pipeline for processing streams of data
 pub struct ValidationPipeline<T, I>
 where
     I: Iterator<Item = T>,
 {
     iterator: I,
-    validators: Vec<Box<dyn Fn(&T) -> ValidationResult<()>>>,
+    validators: Vec<PipelineValidator<T>>,
     config: ValidationConfig,
+    progress: Option<ValidationProgressCallback>,
+    report_every: usize,
 }
 
 impl<T, I> ValidationPipeline<T, I>
@@ -717,6 +1138,8 @@ where
             iterator,
             validators: Vec::new(),
             config: ValidationConfig::default(),
+            progress: None,
+            report_every: 1,
         }
     }
 
@@ -737,9 +1160,37 @@ where
     /// ```
     pub fn add_validator<F>(mut self, validator: F) -> Self
     where
-        F: Fn(&T) -> ValidationResult<()> + 'static,
+        F: Fn(&T) -> ValidationResult<()> + Send + Sync + 'static,
     {
-        self.validators.push(Box::new(validator));
+        self.validators.push(PipelineValidator {
+            check: Box::new(validator),
+            fail_open: false,
+        });
+        self
+    }
+
+    /// Add an advisory validator whose errors never reject an item.
+    ///
+    /// When the validator returns `Err`, the error is logged and counted in
+    /// `advisory_errors`, but the item stays eligible for `valid_items`. Use this for
+    /// best-effort checks where a buggy rule must not block good data; validators added
+    /// with [`add_validator`](Self::add_validator) remain fail-closed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let pipeline = ValidationPipeline::new(rows.into_iter())
+    ///     .add_validator(|row: &Row| Required.validate(&row.id, "id"))
+    ///     .add_validator_fail_open(|row: &Row| lookup_rule(row));
+    /// ```
+    pub fn add_validator_fail_open<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&T) -> ValidationResult<()> + Send + Sync + 'static,
+    {
+        self.validators.push(PipelineValidator {
+            check: Box::new(validator),
+            fail_open: true,
+        });
         self
     }
 
@@ -749,7 +1200,7 @@ where
     ///
     /// ```
     /// let data = vec![1, 2, 3].into_iter();
-    /// /// let config = ValidationConfig { fail_fast: false, max_errors: Some(5), parallel_validation: false };
+    /// /// let config = ValidationConfig { fail_fast: false, max_errors: Some(5), max_errors_per_field: None, parallel_validation: false };
     /// let pipeline = ValidationPipeline::new(data).with_config(config);
     /// ```
     pub fn with_config(mut self, config: ValidationConfig) -> Self {
@@ -757,6 +1208,29 @@ where
         self
     }
 
+    /// Registers a progress callback invoked every `report_every` processed items.
+    ///
+    /// The callback receives `(processed, errors_so_far)`, letting long-running imports emit
+    /// progress events. A `report_every` of zero is treated as one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let pipeline = ValidationPipeline::new(rows.into_iter())
+    ///     .with_progress(1_000, Arc::new(|processed, errors| {
+    ///         log::info!("validated {} rows, {} errors", processed, errors);
+    ///     }));
+    /// ```
+    pub fn with_progress(
+        mut self,
+        report_every: usize,
+        callback: ValidationProgressCallback,
+    ) -> Self {
+        self.progress = Some(callback);
+        self.report_every = report_every.max(1);
+        self
+    }
+
     /// Processes each item from the pipeline's iterator with all configured validators and collects passing items, failing items with their errors, and summary totals.
     ///
     /// The pipeline honors its `fail_fast` and `max_errors` configuration while validating items; items that pass all validators are returned in `valid_items`, items that fail are returned in `invalid_items` paired with their validation errors, and `total_processed`/`total_errors` report counts collected during execution.
@@ -777,14 +1251,19 @@ where
         let mut valid_items = Vec::new();
         let mut invalid_items = Vec::new();
         let mut total_errors = 0;
+        let mut advisory_errors = 0;
 
         for item in self.iterator {
             let mut item_errors = Vec::new();
 
             // Apply all validators to this item
             for validator in &self.validators {
-                match validator(&item) {
+                match (validator.check)(&item) {
                     Ok(()) => {}
+                    Err(error) if validator.fail_open => {
+                        log_advisory_error(&error);
+                        advisory_errors += 1;
+                    }
                     Err(error) => {
                         item_errors.push(error);
 
@@ -808,6 +1287,13 @@ where
                 invalid_items.push((item, item_errors));
             }
 
+            report_progress(
+                self.progress.as_ref(),
+                self.report_every,
+                valid_items.len() + invalid_items.len(),
+                total_errors,
+            );
+
             // Check global error limit
             if let Some(max) = self.config.max_errors {
                 if total_errors >= max {
@@ -822,6 +1308,7 @@ where
             invalid_items,
             total_processed,
             total_errors,
+            advisory_errors,
         }
     }
 
@@ -858,23 +1345,41 @@ where
         // Use itertools for advanced validation patterns
         let mut valid_items = Vec::new();
         let mut invalid_items = Vec::new();
+        let mut advisory_errors = 0;
 
         // Group items by validation status using itertools
         let grouped = self.iterator.map(|item| {
             let errors: Vec<_> = validators
                 .iter()
-                .filter_map(|validator| validator(&item).err())
+                .filter_map(|validator| match (validator.check)(&item) {
+                    Ok(()) => None,
+                    Err(error) if validator.fail_open => {
+                        log_advisory_error(&error);
+                        advisory_errors += 1;
+                        None
+                    }
+                    Err(error) => Some(error),
+                })
                 .collect();
 
             (item, errors)
         });
 
+        let mut errors_so_far = 0;
         for (item, errors) in grouped {
             if errors.is_empty() {
                 valid_items.push(item);
             } else {
+                errors_so_far += errors.len();
                 invalid_items.push((item, errors));
             }
+
+            report_progress(
+                self.progress.as_ref(),
+                self.report_every,
+                valid_items.len() + invalid_items.len(),
+                errors_so_far,
+            );
         }
 
         let total_processed = valid_items.len() + invalid_items.len();
@@ -885,6 +1390,31 @@ where
             invalid_items,
             total_processed,
             total_errors,
+            advisory_errors,
+        }
+    }
+}
+
+/// Logs an error raised by a fail-open validator.
+fn log_advisory_error(error: &ValidationError) {
+    log::warn!(
+        "Advisory validator failed open on '{}': {} ({})",
+        error.field,
+        error.message,
+        error.code
+    );
+}
+
+/// Invokes `progress` when `processed` lands on a `report_every` boundary.
+fn report_progress(
+    progress: Option<&ValidationProgressCallback>,
+    report_every: usize,
+    processed: usize,
+    errors_so_far: usize,
+) {
+    if let Some(callback) = progress {
+        if processed % report_every == 0 {
+            callback(processed, errors_so_far);
         }
     }
 }
@@ -900,6 +1430,8 @@ pub struct ValidationPipelineResult<T> {
     pub total_processed: usize,
     /// Total number of validation errors
     pub total_errors: usize,
+    /// Errors from fail-open validators, which did not reject their items
+    pub advisory_errors: usize,
 }
 
 impl<T> ValidationPipelineResult<T> {
@@ -917,6 +1449,7 @@ impl<T> ValidationPipelineResult<T> {
     ///     invalid_items: Vec::new(),
     ///     total_processed: 1,
     ///     total_errors: 0,
+    ///     advisory_errors: 0,
     /// };
     /// assert!(result.is_all_valid());
     /// ```
@@ -937,6 +1470,7 @@ impl<T> ValidationPipelineResult<T> {
     ///     invalid_items: vec![],
     ///     total_processed: 2,
     ///     total_errors: 0,
+    ///     advisory_errors: 0,
     /// };
     /// assert_eq!(result.success_rate(), 100.0);
     /// ```
@@ -960,6 +1494,7 @@ impl<T> ValidationPipelineResult<T> {
     ///     invalid_items: vec![(1, Vec::<ValidationError>::new())],
     ///     total_processed: 1,
     ///     total_errors: 0,
+    ///     advisory_errors: 0,
     /// };
     /// assert!(result.all_errors().is_empty());
     /// ```
@@ -995,6 +1530,31 @@ impl<T> ValidationPipelineResult<T> {
 
         grouped
     }
+
+    /// Combines this result with another, e.g. from a separately validated shard.
+    ///
+    /// Items are concatenated with `self`'s first. `total_processed` and `total_errors` are
+    /// recomputed from the merged items rather than summed, so both reflect exactly what the
+    /// merged result holds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let merged = first_shard.merge(second_shard);
+    /// assert_eq!(merged.total_processed, merged.valid_items.len() + merged.invalid_items.len());
+    /// ```
+    pub fn merge(mut self, other: ValidationPipelineResult<T>) -> ValidationPipelineResult<T> {
+        self.valid_items.extend(other.valid_items);
+        self.invalid_items.extend(other.invalid_items);
+        self.total_processed = self.valid_items.len() + self.invalid_items.len();
+        self.total_errors = self
+            .invalid_items
+            .iter()
+            .map(|(_, errors)| errors.len())
+            .sum();
+        self.advisory_errors += other.advisory_errors;
+        self
+    }
 }
 
 /// Lazy validation iterator for processing large datasets
@@ -1122,7 +1682,7 @@ pub fn validator<T>() -> ValidationEngine<T> {
 /// # Examples
 ///
 /// ```
-/// /// let config = ValidationConfig { fail_fast: false, max_errors: Some(5), parallel_validation: false };
+/// /// let config = ValidationConfig { fail_fast: false, max_errors: Some(5), max_errors_per_field: None, parallel_validation: false };
 /// /// let engine: ValidationEngine<String> = validator_with_config(config);
 /// ```
 pub fn validator_with_config<T>(config: ValidationConfig) -> ValidationEngine<T> {
@@ -1199,6 +1759,85 @@ mod tests {
         assert!(!result.is_valid);
     }
 
+    #[test]
+    fn test_outcome_into_result_success() {
+        let engine = ValidationEngine::new();
+        let value = "test@example.com".to_string();
+
+        let outcome = engine.validate_field(&value, "email", vec![Email]);
+        assert_eq!(outcome.into_result(), Ok(&value));
+        assert_eq!(ValidationOutcome::success(7).ok(), Some(7));
+    }
+
+    #[test]
+    fn test_outcome_into_result_failure() {
+        fn handler(value: &String) -> Result<String, Vec<ValidationError>> {
+            let value = ValidationEngine::new()
+                .validate_field(value, "email", vec![Email])
+                .into_result()?;
+            Ok(value.to_uppercase())
+        }
+
+        let errors = handler(&"not-an-email".to_string()).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "email");
+        assert_eq!(handler(&"a@b.com".to_string()), Ok("A@B.COM".to_string()));
+
+        let failed = ValidationOutcome::success(7).add_error(ValidationError::new(
+            "age",
+            "INVALID",
+            "age is invalid",
+        ));
+        assert_eq!(failed.ok(), None);
+    }
+
+    #[test]
+    fn test_aggregate_errors_across_outcome_types() {
+        let email_error = ValidationError::new("email", "INVALID_EMAIL", "email is invalid");
+        let email_outcome: ValidationOutcome<String> =
+            ValidationOutcome::failure(vec![email_error]);
+        let age_outcome: ValidationOutcome<i32> = ValidationOutcome::success(30).add_error(
+            ValidationError::new("age", "RANGE", "age must be at most 25"),
+        );
+
+        let errors = aggregate_errors(&[&email_outcome, &age_outcome]);
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["email", "age"]);
+        assert!(!all_valid(&[&email_outcome, &age_outcome]));
+
+        let name = ValidationOutcome::success("Ana".to_string());
+        let count = ValidationOutcome::success(3i32);
+        assert!(aggregate_errors(&[&name, &count]).is_empty());
+        assert!(all_valid(&[&name, &count]));
+    }
+
+    #[test]
+    fn test_catalog_localizes_required_message() {
+        let catalog = MessageCatalog::new("pt-BR")
+            .with_message("pt-BR", "REQUIRED", "O campo {} é obrigatório")
+            .with_message("en-US", "REQUIRED", "Field {} is mandatory");
+        let engine = ValidationEngine::new().with_catalog(catalog.clone());
+        let empty = String::new();
+        let invalid_email = "invalid".to_string();
+
+        let outcome = engine.validate_field(&empty, "razao_social", vec![Required]);
+        assert_eq!(outcome.errors.len(), 1);
+        assert_eq!(outcome.errors[0].code, "REQUIRED");
+        assert_eq!(
+            outcome.errors[0].message,
+            "O campo razao_social é obrigatório"
+        );
+
+        // Codes without a catalog entry keep the rule's built-in message
+        let builtin = Email.validate(&invalid_email, "email").unwrap_err();
+        let outcome = engine.validate_field(&invalid_email, "email", vec![Email]);
+        assert_eq!(outcome.errors[0].message, builtin.message);
+
+        let english = ValidationEngine::new().with_catalog(catalog.with_locale("en-US"));
+        let outcome = english.validate_field(&empty, "name", vec![Required]);
+        assert_eq!(outcome.errors[0].message, "Field name is mandatory");
+    }
+
     #[test]
     fn test_multiple_field_validation() {
         let engine = ValidationEngine::new();
@@ -1556,6 +2195,32 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_compare_numeric_fields_parses_string_values() {
+        let rule = compare_numeric_fields("valor_desconto", "valor_produtos", |d, p| d <= p);
+        let mut data = HashMap::new();
+        data.insert("valor_desconto".to_string(), "100.00".to_string());
+        data.insert("valor_produtos".to_string(), "200.00".to_string());
+        assert!(rule.validate(&data, "totals").is_ok());
+
+        data.insert("valor_desconto".to_string(), "200.00".to_string());
+        data.insert("valor_produtos".to_string(), "100.00".to_string());
+        let err = rule.validate(&data, "totals").unwrap_err();
+        assert_eq!(err.code, "FIELD_COMPARISON_FAILED");
+    }
+
+    #[test]
+    fn test_compare_numeric_fields_rejects_non_numeric_value() {
+        let rule = compare_numeric_fields("valor_desconto", "valor_produtos", |d, p| d <= p);
+        let mut data = HashMap::new();
+        data.insert("valor_desconto".to_string(), "abc".to_string());
+        data.insert("valor_produtos".to_string(), "200.00".to_string());
+
+        let err = rule.validate(&data, "totals").unwrap_err();
+        assert_eq!(err.code, "NON_NUMERIC_FIELD");
+        assert!(err.message.contains("valor_desconto"));
+    }
+
     #[test]
     fn test_cross_field_validation_composition() {
         // Test combining multiple cross-field rules
@@ -1593,4 +2258,234 @@ mod tests {
         let result2 = rule2.validate(&data, "cross_field");
         assert!(result2.is_err()); // rule2 fails because not all address fields are present
     }
+
+    #[test]
+    fn test_optional_email_rejects_invalid_some() {
+        let rule = optional(Email);
+
+        let error = rule
+            .validate(&Some("bad".to_string()), "email")
+            .unwrap_err();
+        assert_eq!(error.code, "INVALID_EMAIL");
+        assert_eq!(error.field, "email");
+    }
+
+    #[test]
+    fn test_optional_email_accepts_valid_some() {
+        let rule = optional(Email);
+
+        assert!(rule.validate(&Some("a@b.com".to_string()), "email").is_ok());
+    }
+
+    #[test]
+    fn test_optional_email_skips_none() {
+        let rule = optional(Email);
+
+        assert!(rule.validate(&None, "email").is_ok());
+    }
+
+    #[test]
+    fn test_required_some() {
+        let rule = required_some::<String>();
+
+        assert!(rule.validate(&Some(String::new()), "name").is_ok());
+        let error = rule.validate(&None, "name").unwrap_err();
+        assert_eq!(error.code, "REQUIRED");
+        assert_eq!(error.message, "name is required");
+    }
+
+    #[test]
+    fn test_validate_map_applies_per_key_rules() {
+        use crate::functional::validation_rules::Custom;
+        type StringRule = Custom<fn(&String) -> bool>;
+
+        let engine = validator_with_config::<String>(ValidationConfig {
+            fail_fast: false,
+            max_errors: None,
+            max_errors_per_field: None,
+            parallel_validation: false,
+        });
+
+        let mut fields = HashMap::new();
+        fields.insert("email".to_string(), "not-an-email".to_string());
+        fields.insert("name".to_string(), String::new());
+        fields.insert("nickname".to_string(), String::new());
+        fields.insert("zip".to_string(), "01310-100".to_string());
+
+        let outcome = engine.validate_map(&fields, |key| -> Vec<StringRule> {
+            match key {
+                "email" => vec![Custom::new(
+                    |v: &String| v.contains('@'),
+                    "INVALID_EMAIL",
+                    "{} must be a valid email address",
+                )],
+                "name" => vec![Custom::new(
+                    |v: &String| !v.is_empty(),
+                    "REQUIRED",
+                    "{} is required",
+                )],
+                "zip" => vec![Custom::new(
+                    |v: &String| v.len() == 9,
+                    "INVALID_ZIP",
+                    "{} must be a valid zip code",
+                )],
+                _ => Vec::new(),
+            }
+        });
+
+        assert!(!outcome.is_valid);
+        let failures: Vec<(&str, &str)> = outcome
+            .errors
+            .iter()
+            .map(|e| (e.field.as_str(), e.code.as_str()))
+            .collect();
+        assert_eq!(
+            failures,
+            vec![("email", "INVALID_EMAIL"), ("name", "REQUIRED")]
+        );
+        assert_eq!(outcome.errors[1].message, "name is required");
+    }
+
+    #[test]
+    fn test_max_errors_per_field_caps_each_field() {
+        use crate::functional::validation_rules::Custom;
+        type StringRule = Custom<fn(&String) -> bool>;
+
+        let failing = || -> Vec<StringRule> {
+            (1..=5)
+                .map(|i| {
+                    Custom::new(
+                        (|_: &String| false) as fn(&String) -> bool,
+                        &format!("RULE_{}", i),
+                        "{} is invalid",
+                    )
+                })
+                .collect()
+        };
+        let engine = validator_with_config::<String>(ValidationConfig {
+            fail_fast: false,
+            max_errors: None,
+            max_errors_per_field: Some(2),
+            parallel_validation: false,
+        });
+        let value = "x".to_string();
+
+        let outcome = engine.validate_field(&value, "name", failing());
+        let codes: Vec<&str> = outcome.errors.iter().map(|e| e.code.as_str()).collect();
+        assert_eq!(codes, vec!["RULE_1", "RULE_2"]);
+
+        let outcome = engine.validate_fields(vec![
+            ("name".to_string(), &value, failing()),
+            ("email".to_string(), &value, failing()),
+        ]);
+        assert_eq!(outcome.errors.len(), 4);
+    }
+
+    #[test]
+    fn test_validation_pipeline_reports_progress() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Mutex;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let last = Arc::new(Mutex::new((0, 0)));
+        let data: Vec<String> = (0..25)
+            .map(|i| {
+                if i % 5 == 0 {
+                    "invalid-email".to_string()
+                } else {
+                    format!("user{}@example.com", i)
+                }
+            })
+            .collect();
+
+        let callback_calls = Arc::clone(&calls);
+        let callback_last = Arc::clone(&last);
+        let result = ValidationPipeline::new(data.into_iter())
+            .add_validator(|email: &String| Email.validate(email, "email"))
+            .with_config(ValidationConfig {
+                fail_fast: false,
+                max_errors: None,
+                max_errors_per_field: None,
+                parallel_validation: false,
+            })
+            .with_progress(
+                10,
+                Arc::new(move |processed, errors| {
+                    callback_calls.fetch_add(1, Ordering::SeqCst);
+                    *callback_last.lock().unwrap() = (processed, errors);
+                }),
+            )
+            .validate();
+
+        assert_eq!(result.total_processed, 25);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(*last.lock().unwrap(), (20, 4));
+    }
+
+    #[test]
+    fn test_validation_pipeline_result_merge() {
+        let validate_shard = |shard: Vec<String>| {
+            ValidationPipeline::new(shard.into_iter())
+                .add_validator(|email: &String| Email.validate(email, "email"))
+                .add_validator(|email: &String| Required.validate(email, "email"))
+                .with_config(ValidationConfig {
+                    fail_fast: false,
+                    max_errors: None,
+                    max_errors_per_field: None,
+                    parallel_validation: false,
+                })
+                .validate()
+        };
+
+        let first = validate_shard(vec![
+            "a@example.com".to_string(),
+            "invalid".to_string(),
+            "b@example.com".to_string(),
+        ]);
+        let second = validate_shard(vec!["".to_string(), "c@example.com".to_string()]);
+
+        let merged = first.merge(second);
+
+        assert_eq!(merged.valid_items.len(), 3);
+        assert_eq!(merged.invalid_items.len(), 2);
+        assert_eq!(merged.total_processed, 5);
+        assert_eq!(merged.total_errors, 3);
+        assert_eq!(merged.success_rate(), 60.0);
+
+        let by_code = merged.errors_by_code();
+        assert_eq!(by_code["INVALID_EMAIL"].len(), 2);
+        assert_eq!(by_code["REQUIRED"].len(), 1);
+    }
+
+    #[test]
+    fn test_validation_pipeline_fail_open_validator_does_not_reject() {
+        let data = vec!["a@example.com".to_string(), "".to_string()];
+        let pipeline = || {
+            ValidationPipeline::new(data.clone().into_iter())
+                // Advisory rule that errors on every item
+                .add_validator_fail_open(|email: &String| {
+                    Email.validate(&format!("{}@", email), "email")
+                })
+                .add_validator(|email: &String| Required.validate(email, "email"))
+                .with_config(ValidationConfig {
+                    fail_fast: false,
+                    max_errors: None,
+                    max_errors_per_field: None,
+                    parallel_validation: false,
+                })
+        };
+
+        for result in [pipeline().validate(), pipeline().validate_with_itertools()] {
+            assert_eq!(result.valid_items, vec!["a@example.com".to_string()]);
+            assert_eq!(result.invalid_items.len(), 1);
+            let codes: Vec<&str> = result.invalid_items[0]
+                .1
+                .iter()
+                .map(|e| e.code.as_str())
+                .collect();
+            assert_eq!(codes, vec!["REQUIRED"]);
+            assert_eq!(result.total_errors, 1);
+            assert_eq!(result.advisory_errors, 2);
+        }
+    }
 }
@@ -649,6 +649,7 @@ where
 /// One-of validation for enums or allowed values
 pub struct OneOf<T: Clone + PartialEq> {
     allowed_values: Vec<T>,
+    error_code: String,
 }
 
 impl<T: Clone + PartialEq> OneOf<T> {
@@ -664,15 +665,32 @@ impl<T: Clone + PartialEq> OneOf<T> {
     /// assert!(rule.validate(&"cherry".to_string(), "fruit").is_err());
     /// ```
     pub fn new(allowed_values: Vec<T>) -> Self {
-        Self { allowed_values }
+        Self {
+            allowed_values,
+            error_code: "INVALID_VALUE".to_string(),
+        }
+    }
+
+    /// Replaces the default `"INVALID_VALUE"` error code reported for disallowed values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let rule = OneOf::new(vec!["1".to_string(), "2".to_string()]).with_error_code("INVALID_FINALIDADE");
+    /// let err = rule.validate(&"9".to_string(), "finalidade").unwrap_err();
+    /// assert_eq!(err.code, "INVALID_FINALIDADE");
+    /// ```
+    pub fn with_error_code(mut self, error_code: &str) -> Self {
+        self.error_code = error_code.to_string();
+        self
     }
 }
 
 impl<T: Clone + PartialEq> ValidationRule<T> for OneOf<T> {
     /// Validates that the provided value is contained in the rule's allowed values.
     ///
-    /// Returns `Ok(())` if `value` is equal to one of the allowed values, `Err(ValidationError)` with code
-    /// `"INVALID_VALUE"` and a message indicating the field otherwise.
+    /// Returns `Ok(())` if `value` is equal to one of the allowed values, `Err(ValidationError)` with the
+    /// rule's error code (`"INVALID_VALUE"` unless overridden) and a message indicating the field otherwise.
     ///
     /// # Examples
     ///
@@ -687,7 +705,7 @@ impl<T: Clone + PartialEq> ValidationRule<T> for OneOf<T> {
         if !self.allowed_values.contains(value) {
             return Err(ValidationError::new(
                 field_name,
-                "INVALID_VALUE",
+                &self.error_code,
                 &format!("{} must be one of the allowed values", field_name),
             ));
         }
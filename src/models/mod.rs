@@ -13,7 +13,9 @@
 
 pub mod filters;
 pub mod login_history;
+pub mod nfe_audit;
 pub mod nfe_cofins;
+pub mod nfe_correction;
 pub mod nfe_document;
 pub mod nfe_emitter;
 pub mod nfe_icms;
@@ -0,0 +1,161 @@
+//! NFE Audit Trail
+//!
+//! Append-only record of NFE document status changes. Status updates made
+//! through `record_status_change` are written together with their audit row
+//! in a single transaction, so the document and its trail never diverge.
+
+use chrono::{DateTime, Utc};
+use diesel::{prelude::*, Connection as _};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::db::Connection,
+    schema::{nfe_audit_log, nfe_documents},
+};
+
+#[derive(Queryable, Identifiable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = nfe_audit_log)]
+pub struct AuditEntry {
+    pub id: i32,
+    pub tenant_id: String,
+    pub nfe_id: String,
+    pub from_status: String,
+    pub to_status: String,
+    pub changed_by: String,
+    pub reason: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = nfe_audit_log)]
+struct NewAuditEntry<'a> {
+    tenant_id: &'a str,
+    nfe_id: &'a str,
+    from_status: &'a str,
+    to_status: &'a str,
+    changed_by: &'a str,
+    reason: Option<&'a str>,
+}
+
+/// Moves a document from `from_status` to `to_status` and records the change.
+///
+/// The update only matches a document of `tenant_id` that is currently in
+/// `from_status`; the update and the audit insert share one transaction.
+///
+/// # Returns
+///
+/// `Ok(())` once both the status update and the audit row are committed.
+/// `Err(diesel::result::Error::NotFound)` if no document matched (wrong tenant,
+/// unknown access key or a status that already changed), with nothing written.
+pub fn record_status_change(
+    conn: &mut Connection,
+    tenant_id: &str,
+    nfe_id: &str,
+    from_status: &str,
+    to_status: &str,
+    changed_by: &str,
+    reason: Option<&str>,
+) -> QueryResult<()> {
+    conn.transaction(|conn| {
+        let updated = diesel::update(
+            nfe_documents::table
+                .filter(nfe_documents::tenant_id.eq(tenant_id))
+                .filter(nfe_documents::nfe_id.eq(nfe_id))
+                .filter(nfe_documents::status.eq(from_status)),
+        )
+        .set((
+            nfe_documents::status.eq(to_status),
+            nfe_documents::updated_at.eq(Utc::now()),
+        ))
+        .execute(conn)?;
+
+        if updated == 0 {
+            return Err(diesel::result::Error::NotFound);
+        }
+
+        diesel::insert_into(nfe_audit_log::table)
+            .values(NewAuditEntry {
+                tenant_id,
+                nfe_id,
+                from_status,
+                to_status,
+                changed_by,
+                reason,
+            })
+            .execute(conn)?;
+
+        Ok(())
+    })
+}
+
+/// Returns the audit trail of a document, oldest change first.
+pub fn fetch_audit_trail(
+    conn: &mut Connection,
+    tenant_id: &str,
+    nfe_id: &str,
+) -> QueryResult<Vec<AuditEntry>> {
+    nfe_audit_log::table
+        .filter(nfe_audit_log::tenant_id.eq(tenant_id))
+        .filter(nfe_audit_log::nfe_id.eq(nfe_id))
+        .order((nfe_audit_log::changed_at.asc(), nfe_audit_log::id.asc()))
+        .load::<AuditEntry>(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::nfe_document::operations::create_nfe_document;
+    use crate::models::nfe_document::test_support::{new_document, nfe_test_db};
+    use testcontainers::clients;
+
+    #[test]
+    fn test_status_change_writes_exactly_one_audit_row() {
+        let docker = clients::Cli::default();
+        let Some(mut db) = nfe_test_db(&docker, "test_status_change_writes_exactly_one_audit_row")
+        else {
+            return;
+        };
+
+        let key = "35250112345678000190550010000000011000000010";
+        create_nfe_document(new_document("tenant-a", key), &mut db.conn).unwrap();
+
+        record_status_change(
+            &mut db.conn,
+            "tenant-a",
+            key,
+            "draft",
+            "authorized",
+            "auditor",
+            Some("SEFAZ approval"),
+        )
+        .unwrap();
+
+        let trail = fetch_audit_trail(&mut db.conn, "tenant-a", key).unwrap();
+        assert_eq!(trail.len(), 1);
+        assert_eq!(trail[0].from_status, "draft");
+        assert_eq!(trail[0].to_status, "authorized");
+        assert_eq!(trail[0].changed_by, "auditor");
+        assert_eq!(trail[0].reason.as_deref(), Some("SEFAZ approval"));
+
+        // A stale `from_status` matches nothing and must not leave an audit row behind
+        let stale = record_status_change(
+            &mut db.conn,
+            "tenant-a",
+            key,
+            "draft",
+            "cancelled",
+            "auditor",
+            None,
+        );
+        assert!(matches!(stale, Err(diesel::result::Error::NotFound)));
+        assert_eq!(
+            fetch_audit_trail(&mut db.conn, "tenant-a", key)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(fetch_audit_trail(&mut db.conn, "tenant-b", key)
+            .unwrap()
+            .is_empty());
+    }
+}
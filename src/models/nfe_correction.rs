@@ -0,0 +1,242 @@
+//! NFE Correction Letters (CC-e)
+//!
+//! A carta de correção eletrônica amends fields of an authorized NFe without
+//! cancelling it. Letters of a document are numbered by `sequencia` (the SEFAZ
+//! `nSeqEvento`), and each new letter must carry a higher number than every
+//! letter already recorded for that document.
+
+use chrono::{DateTime, Utc};
+use diesel::{dsl::max, prelude::*, Connection as _};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::db::Connection,
+    schema::{nfe_correction_events, nfe_documents},
+};
+
+/// Minimum length, in characters, of a correction text
+pub const MIN_CORRECTION_LEN: usize = 15;
+/// Maximum length, in characters, of a correction text
+pub const MAX_CORRECTION_LEN: usize = 1000;
+
+#[derive(Queryable, Identifiable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = nfe_correction_events)]
+pub struct CorrectionEvent {
+    pub id: i32,
+    pub tenant_id: String,
+    pub nfe_id: String,
+    pub sequencia: i32,
+    pub texto_correcao: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = nfe_correction_events)]
+struct NewCorrectionEvent<'a> {
+    tenant_id: &'a str,
+    nfe_id: &'a str,
+    sequencia: i32,
+    texto_correcao: &'a str,
+}
+
+/// Errors raised by `record_correction`
+#[derive(Debug, thiserror::Error)]
+pub enum CorrectionError {
+    #[error(
+        "Correction text has {len} characters; it must have between {} and {}",
+        MIN_CORRECTION_LEN,
+        MAX_CORRECTION_LEN
+    )]
+    InvalidTextLength { len: usize },
+
+    #[error("Correction sequence {sequencia} must be greater than the last sequence {last}")]
+    SequenceOutOfOrder { sequencia: i32, last: i32 },
+
+    #[error("NFE document {0} not found")]
+    DocumentNotFound(String),
+
+    #[error("Failed to record NFE correction: {0}")]
+    Database(#[from] diesel::result::Error),
+}
+
+/// Checks that `texto` has between 15 and 1000 characters, ignoring surrounding whitespace.
+pub fn validate_correction_text(texto: &str) -> Result<(), CorrectionError> {
+    let len = texto.trim().chars().count();
+    if (MIN_CORRECTION_LEN..=MAX_CORRECTION_LEN).contains(&len) {
+        Ok(())
+    } else {
+        Err(CorrectionError::InvalidTextLength { len })
+    }
+}
+
+/// Checks that `sequencia` follows `last`, the highest sequence already recorded.
+///
+/// Without a previous letter the sequence only has to be positive.
+fn check_sequence(last: Option<i32>, sequencia: i32) -> Result<(), CorrectionError> {
+    let last = last.unwrap_or(0);
+    if sequencia > last {
+        Ok(())
+    } else {
+        Err(CorrectionError::SequenceOutOfOrder { sequencia, last })
+    }
+}
+
+/// Records a correction letter for a tenant's document.
+///
+/// The text is stored trimmed. The sequence check and the insert share one
+/// transaction, and the `(tenant_id, nfe_id, sequencia)` unique constraint
+/// rejects a concurrent letter that raced past the check.
+///
+/// # Returns
+///
+/// `Ok(())` once the event is committed.
+/// `Err(CorrectionError::InvalidTextLength)` if the text is shorter than 15 or
+/// longer than 1000 characters, checked before touching the database.
+/// `Err(CorrectionError::SequenceOutOfOrder)` if `sequencia` is not greater than
+/// the document's last recorded sequence.
+/// `Err(CorrectionError::DocumentNotFound)` if the tenant has no document with `nfe_id`.
+pub fn record_correction(
+    conn: &mut Connection,
+    tenant_id: &str,
+    nfe_id: &str,
+    sequencia: i32,
+    texto_correcao: &str,
+) -> Result<(), CorrectionError> {
+    validate_correction_text(texto_correcao)?;
+
+    conn.transaction(|conn| {
+        let document = nfe_documents::table
+            .filter(nfe_documents::tenant_id.eq(tenant_id))
+            .filter(nfe_documents::nfe_id.eq(nfe_id))
+            .select(nfe_documents::id)
+            .first::<i32>(conn)
+            .optional()?;
+
+        if document.is_none() {
+            return Err(CorrectionError::DocumentNotFound(nfe_id.to_string()));
+        }
+
+        let last = nfe_correction_events::table
+            .filter(nfe_correction_events::tenant_id.eq(tenant_id))
+            .filter(nfe_correction_events::nfe_id.eq(nfe_id))
+            .select(max(nfe_correction_events::sequencia))
+            .first::<Option<i32>>(conn)?;
+        check_sequence(last, sequencia)?;
+
+        diesel::insert_into(nfe_correction_events::table)
+            .values(NewCorrectionEvent {
+                tenant_id,
+                nfe_id,
+                sequencia,
+                texto_correcao: texto_correcao.trim(),
+            })
+            .execute(conn)?;
+
+        Ok(())
+    })
+}
+
+/// Returns the correction letters of a document, lowest sequence first.
+pub fn fetch_corrections(
+    conn: &mut Connection,
+    tenant_id: &str,
+    nfe_id: &str,
+) -> QueryResult<Vec<CorrectionEvent>> {
+    nfe_correction_events::table
+        .filter(nfe_correction_events::tenant_id.eq(tenant_id))
+        .filter(nfe_correction_events::nfe_id.eq(nfe_id))
+        .order(nfe_correction_events::sequencia.asc())
+        .load::<CorrectionEvent>(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::nfe_document::operations::create_nfe_document;
+    use crate::models::nfe_document::test_support::{new_document, nfe_test_db};
+    use testcontainers::clients;
+
+    const VALID_TEXT: &str = "Onde se le CFOP 5102, leia-se CFOP 5405";
+
+    #[test]
+    fn test_validate_correction_text_bounds() {
+        assert!(matches!(
+            validate_correction_text("Texto curto"),
+            Err(CorrectionError::InvalidTextLength { len: 11 })
+        ));
+        // Padding does not count towards the minimum
+        assert!(validate_correction_text("   Texto curto    ").is_err());
+        assert!(validate_correction_text(&"a".repeat(MIN_CORRECTION_LEN)).is_ok());
+        assert!(validate_correction_text(&"ç".repeat(MAX_CORRECTION_LEN)).is_ok());
+        assert!(matches!(
+            validate_correction_text(&"a".repeat(MAX_CORRECTION_LEN + 1)),
+            Err(CorrectionError::InvalidTextLength { len: 1001 })
+        ));
+    }
+
+    #[test]
+    fn test_check_sequence_requires_increase() {
+        assert!(check_sequence(None, 1).is_ok());
+        assert!(check_sequence(None, 0).is_err());
+        assert!(check_sequence(Some(2), 3).is_ok());
+        assert!(check_sequence(Some(2), 5).is_ok());
+        assert!(matches!(
+            check_sequence(Some(2), 2),
+            Err(CorrectionError::SequenceOutOfOrder {
+                sequencia: 2,
+                last: 2
+            })
+        ));
+        assert!(check_sequence(Some(2), 1).is_err());
+    }
+
+    #[test]
+    fn test_record_correction_enforces_text_and_sequence() {
+        let docker = clients::Cli::default();
+        let Some(mut db) =
+            nfe_test_db(&docker, "test_record_correction_enforces_text_and_sequence")
+        else {
+            return;
+        };
+
+        let key = "35250112345678000190550010000000011000000010";
+        create_nfe_document(new_document("tenant-a", key), &mut db.conn).unwrap();
+
+        assert!(matches!(
+            record_correction(&mut db.conn, "tenant-a", key, 1, "Texto curto"),
+            Err(CorrectionError::InvalidTextLength { .. })
+        ));
+
+        record_correction(&mut db.conn, "tenant-a", key, 1, VALID_TEXT).unwrap();
+        record_correction(
+            &mut db.conn,
+            "tenant-a",
+            key,
+            2,
+            "Onde se le peso 10 kg, leia-se peso 12 kg",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            record_correction(&mut db.conn, "tenant-a", key, 2, VALID_TEXT),
+            Err(CorrectionError::SequenceOutOfOrder {
+                sequencia: 2,
+                last: 2
+            })
+        ));
+        assert!(matches!(
+            record_correction(&mut db.conn, "tenant-b", key, 1, VALID_TEXT),
+            Err(CorrectionError::DocumentNotFound(_))
+        ));
+
+        let corrections = fetch_corrections(&mut db.conn, "tenant-a", key).unwrap();
+        assert_eq!(
+            corrections.iter().map(|c| c.sequencia).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(corrections[0].texto_correcao, VALID_TEXT);
+        assert!(fetch_corrections(&mut db.conn, "tenant-b", key)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+//! Contingency emission for NFE documents
+//!
+//! When SEFAZ is unreachable a document is emitted in one of the contingency
+//! modes (`tpEmis` other than `1`) and must state why in
+//! `justificativa_contingencia` (`xJust`), which SEFAZ accepts with 15 to 256
+//! characters.
+
+use crate::models::nfe_document::{NfeDocument, UpdateNfeDocument};
+
+/// SEFAZ contingency emission codes (`tpEmis`): 2 = FS-IA, 3 = SCAN, 4 = EPEC,
+/// 5 = FS-DA, 6 = SVC-AN, 7 = SVC-RS, 9 = offline NFC-e
+pub const CONTINGENCY_TIPOS_EMISSAO: &[&str] = &["2", "3", "4", "5", "6", "7", "9"];
+
+/// Minimum length, in characters, of a contingency justification
+pub const MIN_JUSTIFICATIVA_LEN: usize = 15;
+/// Maximum length, in characters, of a contingency justification
+pub const MAX_JUSTIFICATIVA_LEN: usize = 256;
+
+/// Errors raised by contingency marking and validation
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ContingencyError {
+    #[error("'{0}' is not a contingency emission type")]
+    UnknownTipo(String),
+
+    #[error(
+        "Contingency justification has {len} characters; it must have between {} and {}",
+        MIN_JUSTIFICATIVA_LEN,
+        MAX_JUSTIFICATIVA_LEN
+    )]
+    InvalidJustificationLength { len: usize },
+
+    #[error("Document emitted in contingency (tipo_emissao {tipo}) has no justification")]
+    MissingJustification { tipo: String },
+}
+
+/// Whether `tipo` is one of [`CONTINGENCY_TIPOS_EMISSAO`].
+pub fn is_contingency(tipo: &str) -> bool {
+    CONTINGENCY_TIPOS_EMISSAO.contains(&tipo)
+}
+
+/// Sets `doc` to be emitted in contingency mode `tipo`, justified by `justificativa`.
+///
+/// The justification is stored trimmed and must have between 15 and 256 characters.
+/// `doc` is left unchanged if either argument is rejected.
+pub fn mark_contingency(
+    doc: &mut UpdateNfeDocument,
+    tipo: &str,
+    justificativa: &str,
+) -> Result<(), ContingencyError> {
+    if !is_contingency(tipo) {
+        return Err(ContingencyError::UnknownTipo(tipo.to_string()));
+    }
+
+    let justificativa = justificativa.trim();
+    let len = justificativa.chars().count();
+    if !(MIN_JUSTIFICATIVA_LEN..=MAX_JUSTIFICATIVA_LEN).contains(&len) {
+        return Err(ContingencyError::InvalidJustificationLength { len });
+    }
+
+    doc.tipo_emissao = Some(tipo.to_string());
+    doc.justificativa_contingencia = Some(justificativa.to_string());
+    Ok(())
+}
+
+/// Checks that a document emitted in contingency carries a non-blank justification.
+///
+/// Documents with a normal `tipo_emissao` always pass.
+pub fn validate_contingency(doc: &NfeDocument) -> Result<(), ContingencyError> {
+    let justified = doc
+        .justificativa_contingencia
+        .as_deref()
+        .is_some_and(|justificativa| !justificativa.trim().is_empty());
+
+    if is_contingency(&doc.tipo_emissao) && !justified {
+        return Err(ContingencyError::MissingJustification {
+            tipo: doc.tipo_emissao.clone(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::nfe_document::test_support::nfe_document;
+    use chrono::Utc;
+
+    const JUSTIFICATIVA: &str = "SEFAZ autorizadora fora do ar desde 14h";
+
+    fn sample_document(tipo_emissao: &str, justificativa: Option<&str>) -> NfeDocument {
+        NfeDocument {
+            tipo_emissao: tipo_emissao.to_string(),
+            justificativa_contingencia: justificativa.map(str::to_string),
+            ..nfe_document(Utc::now())
+        }
+    }
+
+    #[test]
+    fn test_mark_contingency_sets_fields() {
+        let mut doc: UpdateNfeDocument = serde_json::from_str("{}").unwrap();
+        mark_contingency(&mut doc, "6", &format!("  {}  ", JUSTIFICATIVA)).unwrap();
+
+        assert_eq!(doc.tipo_emissao.as_deref(), Some("6"));
+        assert_eq!(
+            doc.justificativa_contingencia.as_deref(),
+            Some(JUSTIFICATIVA)
+        );
+    }
+
+    #[test]
+    fn test_mark_contingency_rejects_invalid_input() {
+        let mut doc: UpdateNfeDocument = serde_json::from_str("{}").unwrap();
+
+        assert_eq!(
+            mark_contingency(&mut doc, "1", JUSTIFICATIVA),
+            Err(ContingencyError::UnknownTipo("1".to_string()))
+        );
+        assert_eq!(
+            mark_contingency(&mut doc, "4", "Sem internet"),
+            Err(ContingencyError::InvalidJustificationLength { len: 12 })
+        );
+        assert_eq!(
+            mark_contingency(&mut doc, "4", &"x".repeat(MAX_JUSTIFICATIVA_LEN + 1)),
+            Err(ContingencyError::InvalidJustificationLength { len: 257 })
+        );
+        assert!(doc.tipo_emissao.is_none());
+        assert!(doc.justificativa_contingencia.is_none());
+    }
+
+    #[test]
+    fn test_validate_contingency_requires_justification() {
+        assert_eq!(
+            validate_contingency(&sample_document("9", None)),
+            Err(ContingencyError::MissingJustification {
+                tipo: "9".to_string()
+            })
+        );
+        assert!(validate_contingency(&sample_document("9", Some("   "))).is_err());
+        assert!(validate_contingency(&sample_document("9", Some(JUSTIFICATIVA))).is_ok());
+        assert!(validate_contingency(&sample_document("1", None)).is_ok());
+    }
+}
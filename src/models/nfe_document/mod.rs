@@ -43,6 +43,8 @@ pub struct NfeDocument {
 	pub justificativa_contingencia: Option<String>,
 	pub created_at: DateTime<Utc>,
 	pub updated_at: DateTime<Utc>,
+	/// Optimistic-locking counter, incremented by a database trigger on every update
+	pub version: i32,
 }
 
 #[derive(Insertable, Serialize, Deserialize, Debug, Clone)]
@@ -52,11 +54,21 @@ pub struct NewNfeDocument {
 	pub nfe_id: String,
 	pub serie: String,
 	pub numero: String,
-	// Note: modelo, versao, status, tipo_operacao, tipo_emissao, finalidade,
-	// indicador_presencial, and data_emissao are NOT NULL DEFAULT in the database,
+	/// Document model code ("55" = NF-e, "65" = NFC-e); `None` inserts the DB default ("55")
+	pub modelo: Option<String>,
+	// Note: versao and status are NOT NULL DEFAULT in the database,
 	// so they are omitted here and Diesel will allow DB defaults to apply.
-	// If you need to override defaults, construct this struct with all fields explicitly
-	// or use UpdateNfeDocument for partial updates.
+	// Use UpdateNfeDocument to change them after insert.
+	/// Operation type code (`tpNF`); `None` inserts the DB default
+	pub tipo_operacao: Option<String>,
+	/// Emission type code (`tpEmis`); `None` inserts the DB default
+	pub tipo_emissao: Option<String>,
+	/// Purpose code (`finNFe`); `None` inserts the DB default
+	pub finalidade: Option<String>,
+	/// Buyer presence indicator (`indPres`); `None` inserts the DB default
+	pub indicador_presencial: Option<String>,
+	/// Emission date; `None` inserts the DB default (`NOW()`)
+	pub data_emissao: Option<DateTime<Utc>>,
 	pub data_saida_entrada: Option<DateTime<Utc>>,
 	pub data_autorizacao: Option<DateTime<Utc>>,
 	pub data_cancelamento: Option<DateTime<Utc>>,
@@ -116,5 +128,13 @@ pub struct UpdateNfeDocument {
 	pub updated_at: Option<DateTime<Utc>>,
 }
 
+pub mod contingency;
+pub mod money;
 pub mod operations;
+pub mod query;
+pub mod render;
+pub mod tax;
+#[cfg(test)]
+pub(crate) mod test_support;
 pub mod validators;
+pub mod xml;
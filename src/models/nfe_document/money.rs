@@ -0,0 +1,224 @@
+//! Overflow-safe arithmetic for NFE monetary values
+//!
+//! `Decimal` operators panic when a result leaves the representable range, which
+//! untrusted document totals can trigger. These helpers use checked arithmetic
+//! and report the offending operands instead.
+
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+
+/// Maximum number of decimal places allowed in fiscal monetary values
+pub const MAX_MONEY_DECIMAL_PLACES: usize = 2;
+
+/// Errors raised by monetary arithmetic
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum MoneyError {
+    #[error("Monetary overflow adding value #{index} ({value}) to running total {accumulated}")]
+    AdditionOverflow {
+        index: usize,
+        accumulated: Decimal,
+        value: Decimal,
+    },
+
+    #[error("Monetary overflow subtracting {subtrahend} from {minuend}")]
+    SubtractionOverflow {
+        minuend: Decimal,
+        subtrahend: Decimal,
+    },
+
+    #[error("Monetary overflow applying rate {rate}% to {value}")]
+    MultiplicationOverflow { value: Decimal, rate: Decimal },
+
+    #[error("Invalid monetary value '{raw}': expected forms like 1234.56 or 1.234,56")]
+    InvalidFormat { raw: String },
+
+    #[error("Monetary value '{raw}' has {places} decimal places; at most 2 are allowed")]
+    TooManyDecimalPlaces { raw: String, places: usize },
+}
+
+/// Sums monetary values, treating `None` as zero.
+///
+/// # Examples
+///
+/// ```
+/// let total = sum_money(&[Some(Decimal::new(1050, 2)), None, Some(Decimal::ONE)]).unwrap();
+/// assert_eq!(total, Decimal::new(1150, 2));
+/// ```
+pub fn sum_money(values: &[Option<Decimal>]) -> Result<Decimal, MoneyError> {
+    values
+        .iter()
+        .enumerate()
+        .try_fold(Decimal::ZERO, |accumulated, (index, value)| {
+            let value = value.unwrap_or_default();
+            accumulated
+                .checked_add(value)
+                .ok_or(MoneyError::AdditionOverflow {
+                    index,
+                    accumulated,
+                    value,
+                })
+        })
+}
+
+/// Subtracts `subtrahend` from `minuend`, treating `None` as zero.
+pub fn subtract_money(
+    minuend: Decimal,
+    subtrahend: Option<Decimal>,
+) -> Result<Decimal, MoneyError> {
+    let subtrahend = subtrahend.unwrap_or_default();
+    minuend
+        .checked_sub(subtrahend)
+        .ok_or(MoneyError::SubtractionOverflow {
+            minuend,
+            subtrahend,
+        })
+}
+
+/// Parses a monetary value in either plain ("1234.56") or Brazilian locale
+/// ("1.234,56") notation.
+///
+/// When a comma is present it is the decimal separator and dots must be valid
+/// thousands separators; otherwise a single dot is the decimal separator. Values
+/// with more than [`MAX_MONEY_DECIMAL_PLACES`] decimal places are rejected.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(parse_money("1.234,56").unwrap(), Decimal::new(123456, 2));
+/// assert_eq!(parse_money("1234.56").unwrap(), Decimal::new(123456, 2));
+/// ```
+pub fn parse_money(raw: &str) -> Result<Decimal, MoneyError> {
+    let invalid = || MoneyError::InvalidFormat {
+        raw: raw.to_string(),
+    };
+
+    let trimmed = raw.trim();
+    let (sign, unsigned) = match trimmed.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", trimmed),
+    };
+
+    let (integer, fraction) = match unsigned.split_once(',') {
+        Some((integer, fraction)) => (
+            strip_thousands(integer).ok_or_else(invalid)?,
+            Some(fraction),
+        ),
+        None => match unsigned.split_once('.') {
+            Some((integer, fraction)) => (integer.to_string(), Some(fraction)),
+            None => (unsigned.to_string(), None),
+        },
+    };
+
+    let is_digits = |value: &str| !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit());
+    if !is_digits(&integer) || fraction.is_some_and(|fraction| !is_digits(fraction)) {
+        return Err(invalid());
+    }
+
+    let fraction = fraction.unwrap_or("0");
+    if fraction.len() > MAX_MONEY_DECIMAL_PLACES {
+        return Err(MoneyError::TooManyDecimalPlaces {
+            raw: raw.to_string(),
+            places: fraction.len(),
+        });
+    }
+
+    Decimal::from_str(&format!("{}{}.{}", sign, integer, fraction)).map_err(|_| invalid())
+}
+
+/// Removes `.` thousands separators, requiring groups of exactly three digits
+/// after a leading group of one to three.
+fn strip_thousands(integer: &str) -> Option<String> {
+    let mut groups = integer.split('.');
+    let leading = groups.next()?;
+    if !(1..=3).contains(&leading.len()) && integer.contains('.') {
+        return None;
+    }
+
+    groups.try_fold(leading.to_string(), |mut digits, group| {
+        if group.len() != 3 {
+            return None;
+        }
+        digits.push_str(group);
+        Some(digits)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sum_money_treats_none_as_zero() {
+        assert_eq!(sum_money(&[]), Ok(Decimal::ZERO));
+        assert_eq!(
+            sum_money(&[Some(Decimal::new(1999, 2)), None, Some(Decimal::new(1, 2))]),
+            Ok(Decimal::new(2000, 2))
+        );
+    }
+
+    #[test]
+    fn test_sum_money_reports_overflow_near_max() {
+        let near_max = Decimal::MAX - Decimal::ONE;
+
+        let err = sum_money(&[Some(near_max), None, Some(Decimal::TEN)]).unwrap_err();
+        assert_eq!(
+            err,
+            MoneyError::AdditionOverflow {
+                index: 2,
+                accumulated: near_max,
+                value: Decimal::TEN,
+            }
+        );
+        assert!(err.to_string().contains("overflow"));
+
+        assert!(subtract_money(Decimal::MIN, Some(Decimal::ONE)).is_err());
+        assert_eq!(subtract_money(Decimal::TEN, None), Ok(Decimal::TEN));
+    }
+
+    #[test]
+    fn test_parse_money_accepts_plain_and_locale_formats() {
+        assert_eq!(parse_money("1234.56"), Ok(Decimal::new(123456, 2)));
+        assert_eq!(parse_money("1.234,56"), Ok(Decimal::new(123456, 2)));
+        assert_eq!(parse_money(" 1.234.567,8 "), Ok(Decimal::new(12345678, 1)));
+        assert_eq!(parse_money("1234,5"), Ok(Decimal::new(12345, 1)));
+        assert_eq!(parse_money("-0.99"), Ok(Decimal::new(-99, 2)));
+        assert_eq!(parse_money("42"), Ok(Decimal::new(42, 0)));
+    }
+
+    #[test]
+    fn test_parse_money_rejects_three_decimal_places() {
+        assert_eq!(
+            parse_money("10.125"),
+            Err(MoneyError::TooManyDecimalPlaces {
+                raw: "10.125".to_string(),
+                places: 3,
+            })
+        );
+        assert!(matches!(
+            parse_money("1.234,567"),
+            Err(MoneyError::TooManyDecimalPlaces { places: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_money_rejects_garbage() {
+        for raw in [
+            "", "abc", "12a.50", "1.23.45", "12,34,56", "1.23,45", "1e5", "12.", ",50",
+        ] {
+            let err = parse_money(raw).unwrap_err();
+            assert_eq!(
+                err,
+                MoneyError::InvalidFormat {
+                    raw: raw.to_string()
+                },
+                "{:?}",
+                raw
+            );
+        }
+        assert!(parse_money("R$ 10,00")
+            .unwrap_err()
+            .to_string()
+            .contains("R$ 10,00"));
+    }
+}
@@ -3,12 +3,20 @@
 //! This module contains all database and business logic operations for NFE Documents,
 //! implemented as pure functions with functional composition patterns.
 
-use diesel::{prelude::*, result::DatabaseErrorKind};
+use std::collections::HashSet;
+
+use diesel::{pg::Pg, prelude::*, result::DatabaseErrorKind};
 
 use crate::{
     config::db::Connection,
     error::ServiceError,
-    models::nfe_document::{NewNfeDocument, NfeDocument, UpdateNfeDocument},
+    models::nfe_document::{
+        validators::{
+            validate_access_key, validate_dates, validate_identifiers, validate_new_nfe,
+            validate_totals,
+        },
+        NewNfeDocument, NfeDocument, UpdateNfeDocument,
+    },
     schema::nfe_documents::dsl::*,
 };
 
@@ -161,6 +169,69 @@ pub fn update_nfe_document(
         })
 }
 
+/// Errors raised by `update_document_optimistic`
+#[derive(Debug, thiserror::Error)]
+pub enum UpdateError {
+    #[error("NFE document {0} not found")]
+    NotFound(String),
+
+    #[error("NFE document version conflict: expected {expected}, found {actual}")]
+    VersionConflict { expected: i32, actual: i32 },
+
+    #[error("Failed to update NFE document: {0}")]
+    Database(#[from] diesel::result::Error),
+}
+
+/// Applies `changes` to a tenant's document only if its stored `version` still
+/// equals `expected_version`.
+///
+/// The `nfe_documents_version` trigger bumps `version` on every UPDATE, so a change
+/// made through any other path since `expected_version` was read is a conflict too.
+///
+/// # Returns
+///
+/// `Ok(NfeDocument)` with the updated document on success.
+/// `Err(UpdateError::VersionConflict)` if another writer updated the row first.
+/// `Err(UpdateError::NotFound)` if the tenant has no document with `nfe_id_str`.
+pub fn update_document_optimistic(
+    conn: &mut Connection,
+    tenant_id_str: &str,
+    nfe_id_str: &str,
+    expected_version: i32,
+    changes: UpdateNfeDocument,
+) -> Result<NfeDocument, UpdateError> {
+    diesel::Connection::transaction(conn, |conn| {
+        let updated = diesel::update(
+            nfe_documents
+                .filter(tenant_id.eq(tenant_id_str))
+                .filter(nfe_id.eq(nfe_id_str))
+                .filter(version.eq(expected_version)),
+        )
+        .set(changes)
+        .get_result::<NfeDocument>(conn)
+        .optional()?;
+
+        if let Some(document) = updated {
+            return Ok(document);
+        }
+
+        let actual = nfe_documents
+            .filter(tenant_id.eq(tenant_id_str))
+            .filter(nfe_id.eq(nfe_id_str))
+            .select(version)
+            .first::<i32>(conn)
+            .optional()?;
+
+        Err(match actual {
+            Some(actual) => UpdateError::VersionConflict {
+                expected: expected_version,
+                actual,
+            },
+            None => UpdateError::NotFound(nfe_id_str.to_string()),
+        })
+    })
+}
+
 /// Deletes an NFE document by its ID.
 ///
 /// # Returns
@@ -205,3 +276,403 @@ pub fn count_nfe_documents_by_tenant(
                 .with_context(|ctx| ctx.with_tag("nfe").with_detail(err.to_string()))
         })
 }
+
+/// Outcome counts of a simulated batch insert, see [`validate_batch_insert`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchInsertPreview {
+    /// Documents that pass validation and collide with no existing or earlier batch row
+    pub would_insert: usize,
+    /// Valid documents rejected by a unique key (`nfe_id` or `(tenant_id, serie, numero)`)
+    pub would_conflict: usize,
+    /// Documents failing at least one validator
+    pub invalid: usize,
+}
+
+/// Dry run of inserting `docs`: runs every validator and simulates unique-key conflicts
+/// without writing to the database.
+///
+/// Conflicts are checked against existing rows and against earlier documents of the same
+/// batch, on the `nfe_id` access key (unique across tenants) and on
+/// `(tenant_id, serie, numero)`.
+///
+/// # Returns
+///
+/// `Ok(BatchInsertPreview)` with the per-outcome counts on success.
+/// `Err(ServiceError::InternalServerError)` for database errors.
+pub fn validate_batch_insert(
+    docs: &[NewNfeDocument],
+    conn: &mut Connection,
+) -> Result<BatchInsertPreview, ServiceError> {
+    let (valid, invalid): (Vec<&NewNfeDocument>, Vec<&NewNfeDocument>) =
+        docs.iter().partition(|doc| {
+            validate_new_nfe(doc).is_ok()
+                && validate_totals(doc).is_ok()
+                && validate_dates(*doc).is_ok()
+                && validate_identifiers(doc).is_ok()
+        });
+
+    let map_err = |err: diesel::result::Error| {
+        log::error!("Failed to check NFE batch conflicts: {}", err);
+        ServiceError::internal_server_error("Failed to check NFE batch conflicts".to_string())
+            .with_context(|ctx| ctx.with_tag("nfe").with_detail(err.to_string()))
+    };
+
+    let mut taken_ids: HashSet<String> = nfe_documents
+        .filter(nfe_id.eq_any(valid.iter().map(|doc| doc.nfe_id.as_str())))
+        .select(nfe_id)
+        .load::<String>(conn)
+        .map_err(map_err)?
+        .into_iter()
+        .collect();
+    let mut taken_numbers: HashSet<(String, String, String)> = nfe_documents
+        .filter(tenant_id.eq_any(valid.iter().map(|doc| doc.tenant_id.as_str())))
+        .filter(numero.eq_any(valid.iter().map(|doc| doc.numero.as_str())))
+        .select((tenant_id, serie, numero))
+        .load::<(String, String, String)>(conn)
+        .map_err(map_err)?
+        .into_iter()
+        .collect();
+
+    let would_insert = valid
+        .iter()
+        .filter(|doc| {
+            let number = (doc.tenant_id.clone(), doc.serie.clone(), doc.numero.clone());
+            if taken_ids.contains(&doc.nfe_id) || taken_numbers.contains(&number) {
+                return false;
+            }
+            taken_ids.insert(doc.nfe_id.clone());
+            taken_numbers.insert(number);
+            true
+        })
+        .count();
+
+    Ok(BatchInsertPreview {
+        would_insert,
+        would_conflict: valid.len() - would_insert,
+        invalid: invalid.len(),
+    })
+}
+
+/// Escapes `\`, `%` and `_` so user input is matched literally inside a LIKE pattern.
+fn escape_like_pattern(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+fn search_documents_query(
+    tenant_id_str: &str,
+    query: &str,
+    limit: i64,
+) -> crate::schema::nfe_documents::BoxedQuery<'static, Pg> {
+    let safe_limit = if limit <= 0 { 50 } else { limit.min(500) };
+    let pattern = format!("%{}%", escape_like_pattern(query));
+
+    nfe_documents
+        .filter(tenant_id.eq(tenant_id_str.to_owned()))
+        .filter(
+            informacoes_adicionais
+                .ilike(pattern.clone())
+                .escape('\\')
+                .or(informacoes_fisco.ilike(pattern).escape('\\')),
+        )
+        .order(data_emissao.desc())
+        .limit(safe_limit)
+        .into_boxed()
+}
+
+/// Case-insensitive search over `informacoes_adicionais` and `informacoes_fisco`
+/// for a tenant, newest emission first.
+///
+/// `%` and `_` in `query` are matched literally; `limit` is clamped like
+/// `find_nfe_documents_by_tenant`.
+pub fn search_documents(
+    conn: &mut Connection,
+    tenant_id_str: &str,
+    query: &str,
+    limit: i64,
+) -> QueryResult<Vec<NfeDocument>> {
+    search_documents_query(tenant_id_str, query, limit).load::<NfeDocument>(conn)
+}
+
+/// Looks up a tenant's document by its 44-digit access key, with or without the
+/// `NFe` prefix stored in `nfe_id`.
+///
+/// Malformed keys are rejected with `QueryBuilderError` before any query runs;
+/// a well-formed key with no matching document yields `Ok(None)`.
+pub fn find_by_access_key(
+    conn: &mut Connection,
+    tenant_id_str: &str,
+    access_key: &str,
+) -> QueryResult<Option<NfeDocument>> {
+    validate_access_key(access_key)
+        .map_err(|err| diesel::result::Error::QueryBuilderError(err.message.into()))?;
+    let digits = access_key.strip_prefix("NFe").unwrap_or(access_key);
+
+    nfe_documents
+        .filter(tenant_id.eq(tenant_id_str))
+        .filter(nfe_id.eq(format!("NFe{}", digits)))
+        .first::<NfeDocument>(conn)
+        .optional()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::nfe_document::test_support::{self, nfe_test_db};
+    use diesel::query_builder::debug_query;
+    use rust_decimal::Decimal;
+    use testcontainers::clients;
+
+    fn new_document(tenant: &str, numero_str: &str, info: Option<&str>) -> NewNfeDocument {
+        NewNfeDocument {
+            numero: numero_str.to_string(),
+            informacoes_adicionais: info.map(str::to_string),
+            ..test_support::new_document(tenant, &format!("{}-{}", tenant, numero_str))
+        }
+    }
+
+    #[test]
+    fn test_search_query_escapes_wildcards() {
+        let query = search_documents_query("tenant-a", "50%_off", 1_000);
+        let sql = debug_query::<Pg, _>(&query).to_string();
+
+        assert!(sql.contains("ILIKE"), "{}", sql);
+        assert!(sql.contains("\"informacoes_fisco\""), "{}", sql);
+        assert!(
+            sql.contains("ORDER BY \"nfe_documents\".\"data_emissao\" DESC"),
+            "{}",
+            sql
+        );
+        assert!(sql.contains("%50\\\\%\\\\_off%"), "{}", sql);
+        assert!(sql.contains("500]"), "{}", sql);
+    }
+
+    #[test]
+    fn test_search_documents_matches_substring_and_treats_wildcards_literally() {
+        let docker = clients::Cli::default();
+        let Some(mut db) = nfe_test_db(
+            &docker,
+            "test_search_documents_matches_substring_and_treats_wildcards_literally",
+        ) else {
+            return;
+        };
+
+        for doc in [
+            new_document("tenant-a", "1", Some("Entrega URGENTE no deposito")),
+            new_document("tenant-a", "2", Some("Desconto de 100% aplicado")),
+            new_document("tenant-a", "3", None),
+            new_document("tenant-b", "1", Some("entrega urgente")),
+        ] {
+            create_nfe_document(doc, &mut db.conn).unwrap();
+        }
+
+        let found = search_documents(&mut db.conn, "tenant-a", "urgente", 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].numero, "1");
+
+        let literal = search_documents(&mut db.conn, "tenant-a", "%", 10).unwrap();
+        assert_eq!(literal.len(), 1);
+        assert_eq!(literal[0].numero, "2");
+
+        assert!(search_documents(&mut db.conn, "tenant-a", "_", 10)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_validate_batch_insert_reports_counts_without_writing() {
+        let docker = clients::Cli::default();
+        let Some(mut db) = nfe_test_db(
+            &docker,
+            "test_validate_batch_insert_reports_counts_without_writing",
+        ) else {
+            return;
+        };
+
+        create_nfe_document(new_document("tenant-a", "1", None), &mut db.conn).unwrap();
+
+        let mut invalid_total = new_document("tenant-a", "4", None);
+        invalid_total.valor_total = Decimal::ZERO;
+        let mut same_number = new_document("tenant-a", "2", None);
+        same_number.nfe_id = "tenant-a-2-copy".to_string();
+        let batch = vec![
+            new_document("tenant-a", "1", None),
+            new_document("tenant-a", "2", None),
+            same_number,
+            new_document("tenant-a", "3", None),
+            new_document("tenant-b", "1", None),
+            invalid_total,
+        ];
+
+        let preview = validate_batch_insert(&batch, &mut db.conn).unwrap();
+        assert_eq!(
+            preview,
+            BatchInsertPreview {
+                would_insert: 3,
+                would_conflict: 2,
+                invalid: 1,
+            }
+        );
+        let stored_a = count_nfe_documents_by_tenant("tenant-a", &mut db.conn).unwrap();
+        let stored_b = count_nfe_documents_by_tenant("tenant-b", &mut db.conn).unwrap();
+        assert_eq!((stored_a, stored_b), (1, 0));
+    }
+
+    fn empty_update() -> UpdateNfeDocument {
+        UpdateNfeDocument {
+            modelo: None,
+            versao: None,
+            status: None,
+            tipo_operacao: None,
+            tipo_emissao: None,
+            finalidade: None,
+            indicador_presencial: None,
+            data_emissao: None,
+            data_saida_entrada: None,
+            data_autorizacao: None,
+            data_cancelamento: None,
+            valor_total: None,
+            valor_desconto: None,
+            valor_frete: None,
+            valor_seguro: None,
+            valor_outras_despesas: None,
+            valor_produtos: None,
+            valor_impostos: None,
+            pedido_compra: None,
+            contrato: None,
+            informacoes_adicionais: None,
+            informacoes_fisco: None,
+            protocolo_autorizacao: None,
+            motivo_cancelamento: None,
+            justificativa_contingencia: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_update_document_optimistic_rejects_stale_version() {
+        let docker = clients::Cli::default();
+        let Some(mut db) = nfe_test_db(
+            &docker,
+            "test_update_document_optimistic_rejects_stale_version",
+        ) else {
+            return;
+        };
+
+        let created =
+            create_nfe_document(new_document("tenant-a", "1", None), &mut db.conn).unwrap();
+        assert_eq!(created.version, 1);
+
+        let first = UpdateNfeDocument {
+            informacoes_adicionais: Some("primeira".to_string()),
+            ..empty_update()
+        };
+        let updated =
+            update_document_optimistic(&mut db.conn, "tenant-a", &created.nfe_id, 1, first)
+                .unwrap();
+        assert_eq!(updated.version, 2);
+
+        let stale = UpdateNfeDocument {
+            informacoes_adicionais: Some("obsoleta".to_string()),
+            ..empty_update()
+        };
+        let err = update_document_optimistic(&mut db.conn, "tenant-a", &created.nfe_id, 1, stale)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            UpdateError::VersionConflict {
+                expected: 1,
+                actual: 2
+            }
+        ));
+
+        let stored = find_nfe_document_by_id(created.id, &mut db.conn).unwrap();
+        assert_eq!(stored.version, 2);
+        assert_eq!(stored.informacoes_adicionais.as_deref(), Some("primeira"));
+
+        let missing = update_document_optimistic(
+            &mut db.conn,
+            "tenant-b",
+            &created.nfe_id,
+            2,
+            empty_update(),
+        )
+        .unwrap_err();
+        assert!(matches!(missing, UpdateError::NotFound(_)));
+    }
+
+    #[test]
+    fn test_update_document_optimistic_conflicts_with_plain_update() {
+        let docker = clients::Cli::default();
+        let Some(mut db) = nfe_test_db(
+            &docker,
+            "test_update_document_optimistic_conflicts_with_plain_update",
+        ) else {
+            return;
+        };
+
+        let created =
+            create_nfe_document(new_document("tenant-a", "1", None), &mut db.conn).unwrap();
+        let read_version = created.version;
+
+        let plain = UpdateNfeDocument {
+            informacoes_adicionais: Some("direta".to_string()),
+            ..empty_update()
+        };
+        let bumped = update_nfe_document(created.id, plain, &mut db.conn).unwrap();
+        assert_eq!(bumped.version, read_version + 1);
+
+        let optimistic = UpdateNfeDocument {
+            informacoes_adicionais: Some("otimista".to_string()),
+            ..empty_update()
+        };
+        let err = update_document_optimistic(
+            &mut db.conn,
+            "tenant-a",
+            &created.nfe_id,
+            read_version,
+            optimistic,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            UpdateError::VersionConflict {
+                expected: 1,
+                actual: 2
+            }
+        ));
+
+        let stored = find_nfe_document_by_id(created.id, &mut db.conn).unwrap();
+        assert_eq!(stored.informacoes_adicionais.as_deref(), Some("direta"));
+    }
+
+    #[test]
+    fn test_find_by_access_key_hit_miss_and_malformed() {
+        let docker = clients::Cli::default();
+        let Some(mut db) = nfe_test_db(&docker, "test_find_by_access_key_hit_miss_and_malformed")
+        else {
+            return;
+        };
+
+        let key = "35240112345678000190550010000000011000000010";
+        let mut doc = new_document("tenant-a", "1", None);
+        doc.nfe_id = format!("NFe{}", key);
+        let created = create_nfe_document(doc, &mut db.conn).unwrap();
+
+        let hit = find_by_access_key(&mut db.conn, "tenant-a", key).unwrap();
+        assert_eq!(hit.map(|doc| doc.id), Some(created.id));
+
+        let other_key = "35240112345678000190550010000000021000000020";
+        assert!(find_by_access_key(&mut db.conn, "tenant-a", other_key)
+            .unwrap()
+            .is_none());
+        assert!(find_by_access_key(&mut db.conn, "tenant-b", key)
+            .unwrap()
+            .is_none());
+
+        let err = find_by_access_key(&mut db.conn, "tenant-a", "NFe-not-a-key").unwrap_err();
+        assert!(matches!(err, diesel::result::Error::QueryBuilderError(_)));
+    }
+}
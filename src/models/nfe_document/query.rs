@@ -0,0 +1,227 @@
+//! Composable query builder for NFE documents
+//!
+//! `NfeQuery` collects optional filters and turns them into a boxed Diesel
+//! query, so callers can combine status, value range, emission window and
+//! ordering without a dedicated function for every combination.
+
+use chrono::{DateTime, Utc};
+use diesel::{pg::Pg, prelude::*};
+use rust_decimal::Decimal;
+
+use crate::{
+    config::db::Connection, error::ServiceError, models::nfe_document::NfeDocument,
+    schema::nfe_documents,
+};
+
+/// Upper bound applied to `NfeQuery::limit`, matching the paginated operations.
+const MAX_QUERY_LIMIT: i64 = 500;
+
+/// Columns an `NfeQuery` can be ordered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NfeSortColumn {
+    Id,
+    Numero,
+    DataEmissao,
+    ValorTotal,
+    CreatedAt,
+}
+
+/// Chainable filter set for NFE documents; filters left unset are not applied.
+#[derive(Debug, Clone, Default)]
+pub struct NfeQuery {
+    tenant_id: Option<String>,
+    status: Option<String>,
+    min_value: Option<Decimal>,
+    max_value: Option<Decimal>,
+    emitted_between: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    order_by: Vec<(NfeSortColumn, bool)>,
+    limit: Option<i64>,
+}
+
+impl NfeQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restricts results to a single tenant.
+    pub fn tenant(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    /// Restricts results to documents in the given status.
+    pub fn status(mut self, status: impl Into<String>) -> Self {
+        self.status = Some(status.into());
+        self
+    }
+
+    /// Only include documents whose `valor_total` is at least `value`.
+    pub fn min_value(mut self, value: Decimal) -> Self {
+        self.min_value = Some(value);
+        self
+    }
+
+    /// Only include documents whose `valor_total` is at most `value`.
+    pub fn max_value(mut self, value: Decimal) -> Self {
+        self.max_value = Some(value);
+        self
+    }
+
+    /// Only include documents emitted within `[start, end]` (inclusive).
+    pub fn emitted_between(mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        self.emitted_between = Some((start, end));
+        self
+    }
+
+    /// Appends an ordering; orderings are applied in the order they were added.
+    pub fn order_by(mut self, column: NfeSortColumn, ascending: bool) -> Self {
+        self.order_by.push((column, ascending));
+        self
+    }
+
+    /// Caps the number of returned rows, clamped to `1..=500`.
+    pub fn limit(mut self, limit: i64) -> Self {
+        self.limit = Some(limit.clamp(1, MAX_QUERY_LIMIT));
+        self
+    }
+
+    /// Builds the boxed Diesel query with only the configured filters applied.
+    pub fn build(self) -> nfe_documents::BoxedQuery<'static, Pg> {
+        let mut query = nfe_documents::table.into_boxed();
+
+        if let Some(tenant) = self.tenant_id {
+            query = query.filter(nfe_documents::tenant_id.eq(tenant));
+        }
+        if let Some(status) = self.status {
+            query = query.filter(nfe_documents::status.eq(status));
+        }
+        if let Some(min) = self.min_value {
+            query = query.filter(nfe_documents::valor_total.ge(min));
+        }
+        if let Some(max) = self.max_value {
+            query = query.filter(nfe_documents::valor_total.le(max));
+        }
+        if let Some((start, end)) = self.emitted_between {
+            query = query.filter(nfe_documents::data_emissao.between(start, end));
+        }
+
+        for (column, ascending) in self.order_by {
+            query = match (column, ascending) {
+                (NfeSortColumn::Id, true) => query.then_order_by(nfe_documents::id.asc()),
+                (NfeSortColumn::Id, false) => query.then_order_by(nfe_documents::id.desc()),
+                (NfeSortColumn::Numero, true) => query.then_order_by(nfe_documents::numero.asc()),
+                (NfeSortColumn::Numero, false) => query.then_order_by(nfe_documents::numero.desc()),
+                (NfeSortColumn::DataEmissao, true) => {
+                    query.then_order_by(nfe_documents::data_emissao.asc())
+                }
+                (NfeSortColumn::DataEmissao, false) => {
+                    query.then_order_by(nfe_documents::data_emissao.desc())
+                }
+                (NfeSortColumn::ValorTotal, true) => {
+                    query.then_order_by(nfe_documents::valor_total.asc())
+                }
+                (NfeSortColumn::ValorTotal, false) => {
+                    query.then_order_by(nfe_documents::valor_total.desc())
+                }
+                (NfeSortColumn::CreatedAt, true) => {
+                    query.then_order_by(nfe_documents::created_at.asc())
+                }
+                (NfeSortColumn::CreatedAt, false) => {
+                    query.then_order_by(nfe_documents::created_at.desc())
+                }
+            };
+        }
+
+        if let Some(limit) = self.limit {
+            query = query.limit(limit);
+        }
+
+        query
+    }
+
+    /// Executes the query and loads the matching documents.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Vec<NfeDocument>)` with the matching documents on success.
+    /// `Err(ServiceError::InternalServerError)` if the query fails.
+    pub fn load(self, conn: &mut Connection) -> Result<Vec<NfeDocument>, ServiceError> {
+        self.build().load::<NfeDocument>(conn).map_err(|err| {
+            log::error!("Failed to query NFE documents: {}", err);
+            ServiceError::internal_server_error("Failed to query NFE documents".to_string())
+                .with_context(|ctx| ctx.with_tag("nfe").with_detail(err.to_string()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use diesel::query_builder::debug_query;
+
+    #[test]
+    fn test_subset_of_filters_only_applies_set_predicates() {
+        let query = NfeQuery::new()
+            .tenant("tenant-a")
+            .status("authorized")
+            .min_value(Decimal::new(10000, 2))
+            .order_by(NfeSortColumn::DataEmissao, false)
+            .limit(25)
+            .build();
+
+        let sql = debug_query::<Pg, _>(&query).to_string();
+
+        assert!(
+            sql.contains("\"nfe_documents\".\"tenant_id\" = $1"),
+            "{}",
+            sql
+        );
+        assert!(sql.contains("\"nfe_documents\".\"status\" = $2"), "{}", sql);
+        assert!(
+            sql.contains("\"nfe_documents\".\"valor_total\" >= $3"),
+            "{}",
+            sql
+        );
+        assert!(
+            sql.contains("ORDER BY \"nfe_documents\".\"data_emissao\" DESC"),
+            "{}",
+            sql
+        );
+        assert!(sql.contains("LIMIT $4"), "{}", sql);
+        assert!(!sql.contains("\"valor_total\" <="), "{}", sql);
+        assert!(!sql.contains("BETWEEN"), "{}", sql);
+    }
+
+    #[test]
+    fn test_value_range_and_emission_window() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 1, 31, 23, 59, 59).unwrap();
+        let query = NfeQuery::new()
+            .max_value(Decimal::new(500, 0))
+            .emitted_between(start, end)
+            .build();
+
+        let sql = debug_query::<Pg, _>(&query).to_string();
+
+        assert!(
+            sql.contains("\"nfe_documents\".\"valor_total\" <= $1"),
+            "{}",
+            sql
+        );
+        assert!(
+            sql.contains("\"nfe_documents\".\"data_emissao\" BETWEEN $2 AND $3"),
+            "{}",
+            sql
+        );
+        assert!(!sql.contains("\"tenant_id\" ="), "{}", sql);
+        assert!(!sql.contains("ORDER BY"), "{}", sql);
+        assert!(!sql.contains("LIMIT"), "{}", sql);
+    }
+
+    #[test]
+    fn test_limit_is_clamped() {
+        let sql = debug_query::<Pg, _>(&NfeQuery::new().limit(10_000).build()).to_string();
+        assert!(sql.contains("binds: [500]"), "{}", sql);
+    }
+}
@@ -0,0 +1,206 @@
+//! Plain-text rendering of NFE documents
+//!
+//! `render_danfe_summary` produces a fixed-width block with the key facts of a
+//! document, for operators who need a quick look without generating the full
+//! DANFE PDF.
+
+use rust_decimal::Decimal;
+
+use crate::models::nfe_document::NfeDocument;
+
+/// Width of the label column in the rendered summary.
+const LABEL_WIDTH: usize = 24;
+
+/// Total width of the rendered block, used for the header and separators.
+const BLOCK_WIDTH: usize = 72;
+
+/// Shown in place of absent optional fields.
+const PLACEHOLDER: &str = "-";
+
+/// Fields encoded in a 44-digit NF-e access key that are not stored as columns.
+struct AccessKeyFields<'a> {
+    uf: &'a str,
+    year_month: &'a str,
+    cnpj: &'a str,
+}
+
+impl<'a> AccessKeyFields<'a> {
+    /// Splits an access key (with or without the `NFe` prefix) into its parts.
+    ///
+    /// Returns `None` unless the key is exactly 44 ASCII digits.
+    fn parse(nfe_id: &'a str) -> Option<Self> {
+        let key = nfe_id.strip_prefix("NFe").unwrap_or(nfe_id);
+        if key.len() != 44 || !key.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        Some(Self {
+            uf: &key[0..2],
+            year_month: &key[2..6],
+            cnpj: &key[6..20],
+        })
+    }
+
+    /// `AAMM` rendered as `MM/20AA`.
+    fn emission_period(&self) -> String {
+        format!("{}/20{}", &self.year_month[2..4], &self.year_month[0..2])
+    }
+
+    /// CNPJ rendered as `00.000.000/0000-00`.
+    fn formatted_cnpj(&self) -> String {
+        let cnpj = self.cnpj;
+        format!(
+            "{}.{}.{}/{}-{}",
+            &cnpj[0..2],
+            &cnpj[2..5],
+            &cnpj[5..8],
+            &cnpj[8..12],
+            &cnpj[12..14]
+        )
+    }
+}
+
+/// Renders a fixed-width, human-readable summary of `doc`.
+///
+/// Fields derived from the access key (UF, emission period, issuer CNPJ) show
+/// `-` when `nfe_id` is not a well-formed 44-digit key, as do absent optional
+/// fields such as the authorization protocol.
+///
+/// # Examples
+///
+/// ```
+/// let summary = render_danfe_summary(&doc);
+/// assert!(summary.contains("Valor total"));
+/// ```
+pub fn render_danfe_summary(doc: &NfeDocument) -> String {
+    let key_fields = AccessKeyFields::parse(&doc.nfe_id);
+
+    let rows = [
+        ("Chave de acesso", doc.nfe_id.clone()),
+        (
+            "UF",
+            key_fields
+                .as_ref()
+                .map_or_else(placeholder, |fields| fields.uf.to_string()),
+        ),
+        (
+            "Periodo de emissao",
+            key_fields
+                .as_ref()
+                .map_or_else(placeholder, AccessKeyFields::emission_period),
+        ),
+        (
+            "CNPJ emitente",
+            key_fields
+                .as_ref()
+                .map_or_else(placeholder, AccessKeyFields::formatted_cnpj),
+        ),
+        ("Modelo", doc.modelo.clone()),
+        ("Serie", doc.serie.clone()),
+        ("Numero", doc.numero.clone()),
+        (
+            "Data de emissao",
+            doc.data_emissao.format("%d/%m/%Y %H:%M:%S UTC").to_string(),
+        ),
+        ("Valor total", format_money(doc.valor_total)),
+        ("Status", doc.status.clone()),
+        (
+            "Protocolo autorizacao",
+            doc.protocolo_autorizacao
+                .clone()
+                .unwrap_or_else(placeholder),
+        ),
+    ];
+
+    let separator = "=".repeat(BLOCK_WIDTH);
+    let mut summary = format!(
+        "{}\n{:^width$}\n{}\n",
+        separator,
+        "DANFE - RESUMO",
+        separator,
+        width = BLOCK_WIDTH
+    );
+    for (label, value) in rows {
+        summary.push_str(&format!(
+            "{:<width$}{}\n",
+            format!("{}:", label),
+            value,
+            width = LABEL_WIDTH
+        ));
+    }
+    summary.push_str(&separator);
+    summary
+}
+
+fn placeholder() -> String {
+    PLACEHOLDER.to_string()
+}
+
+/// Formats a monetary value with exactly two decimal places.
+fn format_money(value: Decimal) -> String {
+    format!("R$ {:.2}", value.round_dp(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::nfe_document::test_support::nfe_document;
+    use chrono::{TimeZone, Utc};
+
+    fn sample_document() -> NfeDocument {
+        let emitted = Utc.with_ymd_and_hms(2024, 1, 15, 10, 30, 0).unwrap();
+        NfeDocument {
+            status: "authorized".to_string(),
+            valor_total: Decimal::new(12345, 1),
+            valor_produtos: Decimal::new(12345, 1),
+            protocolo_autorizacao: Some("135240000000001".to_string()),
+            ..nfe_document(emitted)
+        }
+    }
+
+    #[test]
+    fn test_render_danfe_summary_contains_labels_and_total() {
+        let summary = render_danfe_summary(&sample_document());
+
+        for label in [
+            "Chave de acesso:",
+            "UF:",
+            "Periodo de emissao:",
+            "CNPJ emitente:",
+            "Data de emissao:",
+            "Valor total:",
+            "Status:",
+            "Protocolo autorizacao:",
+        ] {
+            assert!(summary.contains(label), "missing {} in\n{}", label, summary);
+        }
+        assert!(summary.contains("R$ 1234.50"), "{}", summary);
+        assert!(summary.contains("12.345.678/0001-90"), "{}", summary);
+        assert!(summary.contains("01/2024"), "{}", summary);
+        assert!(summary.contains("15/01/2024 10:30:00 UTC"), "{}", summary);
+        assert!(summary.contains("135240000000001"), "{}", summary);
+        assert!(summary.lines().all(|line| line.len() <= BLOCK_WIDTH));
+    }
+
+    #[test]
+    fn test_render_danfe_summary_shows_placeholder_for_missing_fields() {
+        let mut doc = sample_document();
+        doc.nfe_id = "draft-1".to_string();
+        doc.protocolo_autorizacao = None;
+
+        let summary = render_danfe_summary(&doc);
+        let value_of = |label: &str| {
+            summary
+                .lines()
+                .find(|line| line.starts_with(label))
+                .map(|line| line[LABEL_WIDTH..].to_string())
+        };
+
+        assert_eq!(value_of("CNPJ emitente:").as_deref(), Some(PLACEHOLDER));
+        assert_eq!(value_of("UF:").as_deref(), Some(PLACEHOLDER));
+        assert_eq!(
+            value_of("Protocolo autorizacao:").as_deref(),
+            Some(PLACEHOLDER)
+        );
+    }
+}
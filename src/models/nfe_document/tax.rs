@@ -0,0 +1,179 @@
+//! Tax calculation for NFE documents
+//!
+//! Computes ICMS, IPI, PIS and COFINS amounts from their calculation bases and
+//! rates so `valor_impostos` can be derived instead of trusted from the inbound
+//! document. Rates are percentages (`18` means 18%) and every amount is rounded
+//! to two places with banker's rounding, as each tax is reported separately in
+//! the NF-e XML before being totalled.
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::models::nfe_document::{
+    money::{sum_money, MoneyError, MAX_MONEY_DECIMAL_PLACES},
+    UpdateNfeDocument,
+};
+
+/// Calculation base and rate of a single tax
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaxBase {
+    /// Amount the rate applies to (`vBC`)
+    pub base: Decimal,
+    /// Rate in percent (`pICMS`, `pIPI`, ...)
+    pub aliquota: Decimal,
+}
+
+/// Bases and rates of the taxes that make up `valor_impostos`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaxComponents {
+    pub icms: TaxBase,
+    pub ipi: TaxBase,
+    /// PIS and COFINS share one calculation base
+    pub pis_cofins_base: Decimal,
+    pub aliquota_pis: Decimal,
+    pub aliquota_cofins: Decimal,
+}
+
+/// Applies a percentage rate to `base`, rounding half to even at two places.
+fn apply_rate(base: Decimal, aliquota: Decimal) -> Result<Decimal, MoneyError> {
+    let amount = base
+        .checked_mul(aliquota)
+        .ok_or(MoneyError::MultiplicationOverflow {
+            value: base,
+            rate: aliquota,
+        })?
+        / Decimal::ONE_HUNDRED;
+
+    Ok(amount.round_dp_with_strategy(
+        MAX_MONEY_DECIMAL_PLACES as u32,
+        RoundingStrategy::MidpointNearestEven,
+    ))
+}
+
+/// ICMS due on `base` at `aliquota` percent.
+///
+/// # Examples
+///
+/// ```
+/// let icms = calculate_icms(Decimal::new(100000, 2), Decimal::new(18, 0)).unwrap();
+/// assert_eq!(icms, Decimal::new(18000, 2));
+/// ```
+pub fn calculate_icms(base: Decimal, aliquota: Decimal) -> Result<Decimal, MoneyError> {
+    apply_rate(base, aliquota)
+}
+
+/// IPI due on `base` at `aliquota` percent.
+pub fn calculate_ipi(base: Decimal, aliquota: Decimal) -> Result<Decimal, MoneyError> {
+    apply_rate(base, aliquota)
+}
+
+/// PIS and COFINS due on their shared `base`, returned as `(pis, cofins)`.
+///
+/// Each amount is rounded on its own, matching the separate `vPIS` and `vCOFINS` fields.
+pub fn calculate_pis_cofins(
+    base: Decimal,
+    aliquota_pis: Decimal,
+    aliquota_cofins: Decimal,
+) -> Result<(Decimal, Decimal), MoneyError> {
+    Ok((
+        apply_rate(base, aliquota_pis)?,
+        apply_rate(base, aliquota_cofins)?,
+    ))
+}
+
+/// Total tax of `components`: the sum of the individually rounded ICMS, IPI, PIS and
+/// COFINS amounts.
+pub fn sum_taxes(components: &TaxComponents) -> Result<Decimal, MoneyError> {
+    let icms = calculate_icms(components.icms.base, components.icms.aliquota)?;
+    let ipi = calculate_ipi(components.ipi.base, components.ipi.aliquota)?;
+    let (pis, cofins) = calculate_pis_cofins(
+        components.pis_cofins_base,
+        components.aliquota_pis,
+        components.aliquota_cofins,
+    )?;
+
+    sum_money(&[Some(icms), Some(ipi), Some(pis), Some(cofins)])
+}
+
+/// Sets `valor_impostos` on `doc` to the total computed from `components`.
+///
+/// `doc` is left unchanged if the calculation overflows.
+pub fn recompute_document_taxes(
+    doc: &mut UpdateNfeDocument,
+    components: &TaxComponents,
+) -> Result<(), MoneyError> {
+    doc.valor_impostos = Some(sum_taxes(components)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculators_round_half_to_even() {
+        assert_eq!(
+            calculate_icms(Decimal::new(100000, 2), Decimal::new(18, 0)),
+            Ok(Decimal::new(18000, 2))
+        );
+        // 123.45 * 12% = 14.814
+        assert_eq!(
+            calculate_icms(Decimal::new(12345, 2), Decimal::new(12, 0)),
+            Ok(Decimal::new(1481, 2))
+        );
+        // 100.50 * 5% = 5.025 rounds down to the even 5.02
+        assert_eq!(
+            calculate_ipi(Decimal::new(10050, 2), Decimal::new(5, 0)),
+            Ok(Decimal::new(502, 2))
+        );
+        // 100.70 * 5% = 5.035 rounds up to the even 5.04
+        assert_eq!(
+            calculate_ipi(Decimal::new(10070, 2), Decimal::new(5, 0)),
+            Ok(Decimal::new(504, 2))
+        );
+        // PIS 1.65% and COFINS 7.6% of 1234.56
+        assert_eq!(
+            calculate_pis_cofins(
+                Decimal::new(123456, 2),
+                Decimal::new(165, 2),
+                Decimal::new(76, 1)
+            ),
+            Ok((Decimal::new(2037, 2), Decimal::new(9383, 2)))
+        );
+    }
+
+    #[test]
+    fn test_recompute_document_taxes_sets_total() {
+        let components = TaxComponents {
+            icms: TaxBase {
+                base: Decimal::new(100000, 2),
+                aliquota: Decimal::new(18, 0),
+            },
+            ipi: TaxBase {
+                base: Decimal::new(10050, 2),
+                aliquota: Decimal::new(5, 0),
+            },
+            pis_cofins_base: Decimal::new(100000, 2),
+            aliquota_pis: Decimal::new(165, 2),
+            aliquota_cofins: Decimal::new(76, 1),
+        };
+        // 180.00 + 5.02 + 16.50 + 76.00
+        assert_eq!(sum_taxes(&components), Ok(Decimal::new(27752, 2)));
+
+        let mut doc: UpdateNfeDocument = serde_json::from_str("{}").unwrap();
+        recompute_document_taxes(&mut doc, &components).unwrap();
+        assert_eq!(doc.valor_impostos, Some(Decimal::new(27752, 2)));
+
+        let overflowing = TaxComponents {
+            icms: TaxBase {
+                base: Decimal::MAX,
+                aliquota: Decimal::new(18, 0),
+            },
+            ..components
+        };
+        assert!(matches!(
+            recompute_document_taxes(&mut doc, &overflowing),
+            Err(MoneyError::MultiplicationOverflow { .. })
+        ));
+        assert_eq!(doc.valor_impostos, Some(Decimal::new(27752, 2)));
+    }
+}
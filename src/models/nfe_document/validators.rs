@@ -1,11 +1,41 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
 use once_cell::sync::OnceCell;
+use rust_decimal::Decimal;
 
 use crate::{
     error::ServiceError,
-    models::nfe_document::{NewNfeDocument, UpdateNfeDocument},
+    functional::{
+        parallel_iterators::{ParallelConfig, ParallelIteratorExt, ParallelResult},
+        validation_engine::{Validate, ValidationContext, ValidationOutcome},
+        validation_rules::{OneOf, ValidationError, ValidationRule},
+    },
+    models::nfe_document::{
+        money::{subtract_money, sum_money},
+        NewNfeDocument, NfeDocument, UpdateNfeDocument,
+    },
     services::functional_patterns::{validation_rules, Validator},
 };
 
+/// Default clock-skew tolerance applied when checking that emission dates are not in the future
+pub const DEFAULT_CLOCK_SKEW_TOLERANCE_SECS: i64 = 300;
+
+/// SEFAZ document model codes accepted by the system (55 = NF-e, 65 = NFC-e)
+pub const ALLOWED_MODELOS: &[&str] = &["55", "65"];
+
+/// SEFAZ operation type codes (`tpNF`: 0 = entrada, 1 = saída)
+pub const ALLOWED_TIPOS_OPERACAO: &[&str] = &["0", "1"];
+
+/// SEFAZ emission type codes (`tpEmis`: 1 = normal, 2-7 = contingency modes, 9 = offline NFC-e)
+pub const ALLOWED_TIPOS_EMISSAO: &[&str] = &["1", "2", "3", "4", "5", "6", "7", "9"];
+
+/// SEFAZ purpose codes (`finNFe`: 1 = normal, 2 = complementar, 3 = ajuste, 4 = devolução)
+pub const ALLOWED_FINALIDADES: &[&str] = &["1", "2", "3", "4"];
+
+/// SEFAZ buyer presence codes (`indPres`: 0-5, 9 = outros)
+pub const ALLOWED_INDICADORES_PRESENCIAIS: &[&str] = &["0", "1", "2", "3", "4", "5", "9"];
+
 /// Validator for creating new NFE documents
 pub fn new_nfe_validator() -> Validator<NewNfeDocument> {
     Validator::new()
@@ -71,3 +101,823 @@ pub fn validate_update_nfe(dto: &UpdateNfeDocument) -> Result<(), ServiceError>
         .get_or_init(update_nfe_validator)
         .validate(dto)
 }
+
+/// Date fields checked by [`validate_dates`], shared by persisted and new documents
+pub trait NfeDates {
+    /// Emission date; `None` means the database default (`NOW()`) applies on insert
+    fn emissao(&self) -> Option<DateTime<Utc>>;
+    fn saida_entrada(&self) -> Option<DateTime<Utc>>;
+    fn autorizacao(&self) -> Option<DateTime<Utc>>;
+}
+
+impl NfeDates for NfeDocument {
+    fn emissao(&self) -> Option<DateTime<Utc>> {
+        Some(self.data_emissao)
+    }
+
+    fn saida_entrada(&self) -> Option<DateTime<Utc>> {
+        self.data_saida_entrada
+    }
+
+    fn autorizacao(&self) -> Option<DateTime<Utc>> {
+        self.data_autorizacao
+    }
+}
+
+impl NfeDates for NewNfeDocument {
+    fn emissao(&self) -> Option<DateTime<Utc>> {
+        self.data_emissao
+    }
+
+    fn saida_entrada(&self) -> Option<DateTime<Utc>> {
+        self.data_saida_entrada
+    }
+
+    fn autorizacao(&self) -> Option<DateTime<Utc>> {
+        self.data_autorizacao
+    }
+}
+
+/// Validate date consistency of an NFE document using the default clock-skew tolerance.
+///
+/// See [`validate_dates_with_tolerance`] for the rules applied.
+pub fn validate_dates<D: NfeDates>(doc: &D) -> Result<(), Vec<ValidationError>> {
+    validate_dates_with_tolerance(
+        doc,
+        Duration::seconds(DEFAULT_CLOCK_SKEW_TOLERANCE_SECS),
+        Utc::now(),
+    )
+}
+
+/// Validate date consistency of an NFE document, collecting every violation.
+///
+/// - `data_emissao` must not be later than `now + skew_tolerance` (`FUTURE_EMISSION`)
+/// - `data_saida_entrada`, when present, must not be earlier than `data_emissao`
+///   (`EXIT_BEFORE_EMISSION`)
+/// - `data_autorizacao`, when present, must not be earlier than `data_emissao`
+///   (`AUTHORIZATION_BEFORE_EMISSION`)
+///
+/// A missing emission date is checked as `now`, the value the database will assign.
+pub fn validate_dates_with_tolerance<D: NfeDates>(
+    doc: &D,
+    skew_tolerance: Duration,
+    now: DateTime<Utc>,
+) -> Result<(), Vec<ValidationError>> {
+    let mut errors = Vec::new();
+    let emissao = doc.emissao().unwrap_or(now);
+
+    if emissao > now + skew_tolerance {
+        errors.push(ValidationError::new(
+            "data_emissao",
+            "FUTURE_EMISSION",
+            &format!("data_emissao {} cannot be in the future", emissao),
+        ));
+    }
+
+    if let Some(saida) = doc.saida_entrada() {
+        if saida < emissao {
+            errors.push(ValidationError::new(
+                "data_saida_entrada",
+                "EXIT_BEFORE_EMISSION",
+                &format!(
+                    "data_saida_entrada {} cannot be before data_emissao {}",
+                    saida, emissao
+                ),
+            ));
+        }
+    }
+
+    if let Some(autorizacao) = doc.autorizacao() {
+        if autorizacao < emissao {
+            errors.push(ValidationError::new(
+                "data_autorizacao",
+                "AUTHORIZATION_BEFORE_EMISSION",
+                &format!(
+                    "data_autorizacao {} cannot be before data_emissao {}",
+                    autorizacao, emissao
+                ),
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validate the SEFAZ identifiers of a new NFE document, collecting every violation.
+///
+/// - `modelo`, when provided, must be one of [`ALLOWED_MODELOS`] (`INVALID_MODELO`)
+/// - `serie` must be 1 to 3 digits (`INVALID_SERIE`)
+/// - `numero` must be 1 to 9 digits (`INVALID_NUMERO`) without leading zeros,
+///   a single "0" being allowed (`NUMERO_LEADING_ZERO`)
+pub fn validate_identifiers(doc: &NewNfeDocument) -> Result<(), Vec<ValidationError>> {
+    let mut errors = Vec::new();
+
+    if let Some(modelo) = &doc.modelo {
+        if !ALLOWED_MODELOS.contains(&modelo.as_str()) {
+            errors.push(ValidationError::new(
+                "modelo",
+                "INVALID_MODELO",
+                &format!(
+                    "modelo '{}' is not a valid SEFAZ model code (expected one of {})",
+                    modelo,
+                    ALLOWED_MODELOS.join(", ")
+                ),
+            ));
+        }
+    }
+
+    if !is_digits_between(&doc.serie, 1, 3) {
+        errors.push(ValidationError::new(
+            "serie",
+            "INVALID_SERIE",
+            &format!("serie '{}' must be 1 to 3 digits", doc.serie),
+        ));
+    }
+
+    if !is_digits_between(&doc.numero, 1, 9) {
+        errors.push(ValidationError::new(
+            "numero",
+            "INVALID_NUMERO",
+            &format!("numero '{}' must be 1 to 9 digits", doc.numero),
+        ));
+    } else if doc.numero.len() > 1 && doc.numero.starts_with('0') {
+        errors.push(ValidationError::new(
+            "numero",
+            "NUMERO_LEADING_ZERO",
+            &format!("numero '{}' must not have leading zeros", doc.numero),
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validate the shape of an NF-e access key: exactly 44 ASCII digits, optionally
+/// prefixed with `NFe` as stored in `nfe_id` (`INVALID_ACCESS_KEY`).
+pub fn validate_access_key(access_key: &str) -> Result<(), ValidationError> {
+    let digits = access_key.strip_prefix("NFe").unwrap_or(access_key);
+    if digits.len() == 44 && digits.bytes().all(|b| b.is_ascii_digit()) {
+        Ok(())
+    } else {
+        Err(ValidationError::new(
+            "access_key",
+            "INVALID_ACCESS_KEY",
+            &format!("access key '{}' must be 44 digits", access_key),
+        ))
+    }
+}
+
+/// SEFAZ coded fields checked by [`validate_sefaz_codes`]; `None` means the value is not set
+/// by this document and is left unchecked
+pub trait NfeCodes {
+    fn tipo_operacao(&self) -> Option<&str>;
+    fn tipo_emissao(&self) -> Option<&str>;
+    fn finalidade(&self) -> Option<&str>;
+    fn indicador_presencial(&self) -> Option<&str>;
+}
+
+impl NfeCodes for NfeDocument {
+    fn tipo_operacao(&self) -> Option<&str> {
+        Some(&self.tipo_operacao)
+    }
+
+    fn tipo_emissao(&self) -> Option<&str> {
+        Some(&self.tipo_emissao)
+    }
+
+    fn finalidade(&self) -> Option<&str> {
+        Some(&self.finalidade)
+    }
+
+    fn indicador_presencial(&self) -> Option<&str> {
+        Some(&self.indicador_presencial)
+    }
+}
+
+/// Codes left as `None` take the database defaults on insert
+impl NfeCodes for NewNfeDocument {
+    fn tipo_operacao(&self) -> Option<&str> {
+        self.tipo_operacao.as_deref()
+    }
+
+    fn tipo_emissao(&self) -> Option<&str> {
+        self.tipo_emissao.as_deref()
+    }
+
+    fn finalidade(&self) -> Option<&str> {
+        self.finalidade.as_deref()
+    }
+
+    fn indicador_presencial(&self) -> Option<&str> {
+        self.indicador_presencial.as_deref()
+    }
+}
+
+impl NfeCodes for UpdateNfeDocument {
+    fn tipo_operacao(&self) -> Option<&str> {
+        self.tipo_operacao.as_deref()
+    }
+
+    fn tipo_emissao(&self) -> Option<&str> {
+        self.tipo_emissao.as_deref()
+    }
+
+    fn finalidade(&self) -> Option<&str> {
+        self.finalidade.as_deref()
+    }
+
+    fn indicador_presencial(&self) -> Option<&str> {
+        self.indicador_presencial.as_deref()
+    }
+}
+
+/// Validate the SEFAZ coded fields of an NFE document, collecting every violation.
+///
+/// - `tipo_operacao` must be one of [`ALLOWED_TIPOS_OPERACAO`] (`INVALID_TIPO_OPERACAO`)
+/// - `tipo_emissao` must be one of [`ALLOWED_TIPOS_EMISSAO`] (`INVALID_TIPO_EMISSAO`)
+/// - `finalidade` must be one of [`ALLOWED_FINALIDADES`] (`INVALID_FINALIDADE`)
+/// - `indicador_presencial` must be one of [`ALLOWED_INDICADORES_PRESENCIAIS`]
+///   (`INVALID_INDICADOR_PRESENCIAL`)
+pub fn validate_sefaz_codes<D: NfeCodes>(doc: &D) -> Result<(), Vec<ValidationError>> {
+    let checks = [
+        (
+            "tipo_operacao",
+            doc.tipo_operacao(),
+            ALLOWED_TIPOS_OPERACAO,
+            "INVALID_TIPO_OPERACAO",
+        ),
+        (
+            "tipo_emissao",
+            doc.tipo_emissao(),
+            ALLOWED_TIPOS_EMISSAO,
+            "INVALID_TIPO_EMISSAO",
+        ),
+        (
+            "finalidade",
+            doc.finalidade(),
+            ALLOWED_FINALIDADES,
+            "INVALID_FINALIDADE",
+        ),
+        (
+            "indicador_presencial",
+            doc.indicador_presencial(),
+            ALLOWED_INDICADORES_PRESENCIAIS,
+            "INVALID_INDICADOR_PRESENCIAL",
+        ),
+    ];
+
+    let errors: Vec<ValidationError> = checks
+        .into_iter()
+        .filter_map(|(field, value, allowed, code)| {
+            let rule =
+                OneOf::new(allowed.iter().map(|c| c.to_string()).collect()).with_error_code(code);
+            rule.validate(&value?.to_string(), field).err()
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validate the monetary totals of a new NFE document, collecting every violation.
+///
+/// - every monetary field must be non-negative (`NEGATIVE_VALUE`)
+/// - `valor_total` must equal `valor_produtos - valor_desconto + valor_frete +
+///   valor_seguro + valor_outras_despesas` (`TOTAL_MISMATCH`); `valor_impostos` is the
+///   informational tax burden already embedded in the product values
+/// - the component sum must stay within `Decimal` range (`TOTAL_OVERFLOW`)
+pub fn validate_totals(doc: &NewNfeDocument) -> Result<(), Vec<ValidationError>> {
+    let mut errors = Vec::new();
+
+    let amounts = [
+        ("valor_total", Some(doc.valor_total)),
+        ("valor_produtos", Some(doc.valor_produtos)),
+        ("valor_impostos", Some(doc.valor_impostos)),
+        ("valor_desconto", doc.valor_desconto),
+        ("valor_frete", doc.valor_frete),
+        ("valor_seguro", doc.valor_seguro),
+        ("valor_outras_despesas", doc.valor_outras_despesas),
+    ];
+    for (field, value) in amounts {
+        if let Some(value) = value.filter(|v| *v < Decimal::ZERO) {
+            errors.push(ValidationError::new(
+                field,
+                "NEGATIVE_VALUE",
+                &format!("{} {} cannot be negative", field, value),
+            ));
+        }
+    }
+
+    let expected = sum_money(&[
+        Some(doc.valor_produtos),
+        doc.valor_frete,
+        doc.valor_seguro,
+        doc.valor_outras_despesas,
+    ])
+    .and_then(|gross| subtract_money(gross, doc.valor_desconto));
+    match expected {
+        Ok(expected) if doc.valor_total != expected => {
+            errors.push(ValidationError::new(
+                "valor_total",
+                "TOTAL_MISMATCH",
+                &format!(
+                    "valor_total {} does not match the sum of its components {}",
+                    doc.valor_total, expected
+                ),
+            ));
+        }
+        Ok(_) => {}
+        Err(err) => {
+            errors.push(ValidationError::new(
+                "valor_total",
+                "TOTAL_OVERFLOW",
+                &err.to_string(),
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Run [`validate_totals`], [`validate_dates`] and [`validate_identifiers`] over a batch
+/// of documents on the parallel iterator engine.
+///
+/// Each document is returned with every error it produced, in input order;
+/// valid documents carry an empty error vector.
+pub fn validate_documents_parallel(
+    docs: Vec<NewNfeDocument>,
+    config: &ParallelConfig,
+) -> ParallelResult<Vec<(NewNfeDocument, Vec<ValidationError>)>> {
+    docs.into_iter().par_map(config, |doc| {
+        let errors = [
+            validate_totals(&doc),
+            validate_dates(&doc),
+            validate_identifiers(&doc),
+        ]
+        .into_iter()
+        .filter_map(Result::err)
+        .flatten()
+        .collect();
+        (doc, errors)
+    })
+}
+
+/// Splits a bulk import into documents ready for insertion and documents for the
+/// error report, validating in parallel via [`validate_documents_parallel`].
+///
+/// Input order is preserved within each partition.
+pub fn partition_valid_documents(
+    docs: Vec<NewNfeDocument>,
+    config: &ParallelConfig,
+) -> (
+    Vec<NewNfeDocument>,
+    Vec<(NewNfeDocument, Vec<ValidationError>)>,
+) {
+    let (valid, invalid): (Vec<_>, Vec<_>) = validate_documents_parallel(docs, config)
+        .data
+        .into_iter()
+        .partition(|(_, errors)| errors.is_empty());
+
+    (valid.into_iter().map(|(doc, _)| doc).collect(), invalid)
+}
+
+/// Aggregate counts of a validated import, see [`summarize_validation`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationSummary {
+    /// Documents in the batch
+    pub total: usize,
+    /// Documents without any validation error
+    pub passed: usize,
+    /// Documents with at least one validation error
+    pub failed: usize,
+    /// Occurrences of each error code across the batch
+    pub errors_by_code: HashMap<String, usize>,
+}
+
+/// Summarizes the output of [`validate_documents_parallel`] for an import report.
+///
+/// Every `ValidationError` blocks insertion, so all of them are counted under
+/// `errors_by_code`; a document reporting the same code twice counts twice.
+pub fn summarize_validation(
+    results: &[(NewNfeDocument, Vec<ValidationError>)],
+) -> ValidationSummary {
+    results
+        .iter()
+        .fold(ValidationSummary::default(), |mut summary, (_, errors)| {
+            summary.total += 1;
+            if errors.is_empty() {
+                summary.passed += 1;
+            } else {
+                summary.failed += 1;
+            }
+            for error in errors {
+                *summary
+                    .errors_by_code
+                    .entry(error.code.clone())
+                    .or_insert(0) += 1;
+            }
+            summary
+        })
+}
+
+/// Runs [`validate_totals`], [`validate_dates`] and [`validate_identifiers`], reporting
+/// each error under the field path of `ctx`.
+impl Validate for NewNfeDocument {
+    fn validate(&self, ctx: &ValidationContext) -> ValidationOutcome<()> {
+        let errors: Vec<ValidationError> = [
+            validate_totals(self),
+            validate_dates(self),
+            validate_identifiers(self),
+        ]
+        .into_iter()
+        .filter_map(Result::err)
+        .flatten()
+        .map(|error| ValidationError {
+            field: ctx.child(&error.field).field_path,
+            ..error
+        })
+        .collect();
+
+        if errors.is_empty() {
+            ValidationOutcome::success(())
+        } else {
+            ValidationOutcome::failure(errors)
+        }
+    }
+}
+
+/// Whether `value` consists solely of ASCII digits with a length in `min..=max`
+fn is_digits_between(value: &str, min: usize, max: usize) -> bool {
+    (min..=max).contains(&value.len()) && value.bytes().all(|b| b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::nfe_document::test_support::{new_document, nfe_document};
+    use rust_decimal::Decimal;
+
+    fn sample_new_document() -> NewNfeDocument {
+        NewNfeDocument {
+            numero: "123".to_string(),
+            modelo: Some("55".to_string()),
+            ..new_document("tenant1", "NFe35240112345678000190550010000000011000000010")
+        }
+    }
+
+    #[test]
+    fn test_validate_dates_rejects_future_emission() {
+        let doc = nfe_document(Utc::now() + Duration::days(1));
+
+        let errors = validate_dates(&doc).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, "FUTURE_EMISSION");
+    }
+
+    #[test]
+    fn test_validate_dates_allows_clock_skew() {
+        let now = Utc::now();
+        let doc = nfe_document(now + Duration::seconds(30));
+
+        assert!(validate_dates_with_tolerance(&doc, Duration::seconds(60), now).is_ok());
+        assert!(validate_dates_with_tolerance(&doc, Duration::zero(), now).is_err());
+    }
+
+    #[test]
+    fn test_validate_dates_rejects_exit_before_emission() {
+        let emissao = Utc::now() - Duration::days(2);
+        let mut doc = nfe_document(emissao);
+        doc.data_saida_entrada = Some(emissao - Duration::hours(1));
+        doc.data_autorizacao = Some(emissao - Duration::hours(2));
+
+        let errors = validate_dates(&doc).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].code, "EXIT_BEFORE_EMISSION");
+        assert_eq!(errors[1].code, "AUTHORIZATION_BEFORE_EMISSION");
+    }
+
+    #[test]
+    fn test_validate_dates_accepts_valid_document() {
+        let emissao = Utc::now() - Duration::days(2);
+        let mut doc = nfe_document(emissao);
+        doc.data_saida_entrada = Some(emissao + Duration::hours(3));
+        doc.data_autorizacao = Some(emissao + Duration::minutes(5));
+
+        assert!(validate_dates(&doc).is_ok());
+    }
+
+    #[test]
+    fn test_validate_identifiers_rejects_invalid_modelo() {
+        let mut doc = sample_new_document();
+        doc.modelo = Some("99".to_string());
+
+        let errors = validate_identifiers(&doc).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, "INVALID_MODELO");
+    }
+
+    #[test]
+    fn test_validate_identifiers_rejects_non_numeric_serie() {
+        let mut doc = sample_new_document();
+        doc.serie = "A1".to_string();
+        doc.numero = "0012".to_string();
+
+        let errors = validate_identifiers(&doc).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].code, "INVALID_SERIE");
+        assert_eq!(errors[1].code, "NUMERO_LEADING_ZERO");
+    }
+
+    #[test]
+    fn test_validate_identifiers_accepts_valid_set() {
+        let mut doc = sample_new_document();
+        assert!(validate_identifiers(&doc).is_ok());
+
+        doc.modelo = None;
+        doc.serie = "999".to_string();
+        doc.numero = "0".to_string();
+        assert!(validate_identifiers(&doc).is_ok());
+    }
+
+    #[test]
+    fn test_validate_access_key_checks_shape() {
+        let key = "35240112345678000190550010000000011000000010";
+        assert!(validate_access_key(key).is_ok());
+        assert!(validate_access_key(&format!("NFe{}", key)).is_ok());
+
+        for malformed in [
+            "",
+            &key[..43],
+            "3524011234567800019055001000000001100000001X",
+        ] {
+            let err = validate_access_key(malformed).unwrap_err();
+            assert_eq!(err.code, "INVALID_ACCESS_KEY");
+        }
+    }
+
+    #[test]
+    fn test_validate_sefaz_codes_rejects_invalid_finalidade() {
+        let mut doc = nfe_document(Utc::now());
+        doc.finalidade = "7".to_string();
+
+        let errors = validate_sefaz_codes(&doc).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "finalidade");
+        assert_eq!(errors[0].code, "INVALID_FINALIDADE");
+    }
+
+    #[test]
+    fn test_validate_sefaz_codes_accepts_valid_set() {
+        let mut doc = nfe_document(Utc::now());
+        assert!(validate_sefaz_codes(&doc).is_ok());
+
+        doc.tipo_operacao = "0".to_string();
+        doc.tipo_emissao = "9".to_string();
+        doc.finalidade = "4".to_string();
+        doc.indicador_presencial = "9".to_string();
+        assert!(validate_sefaz_codes(&doc).is_ok());
+        assert!(validate_sefaz_codes(&sample_new_document()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_sefaz_codes_rejects_invalid_new_document_finalidade() {
+        let mut doc = sample_new_document();
+        doc.finalidade = Some("7".to_string());
+
+        let errors = validate_sefaz_codes(&doc).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "finalidade");
+        assert_eq!(errors[0].code, "INVALID_FINALIDADE");
+    }
+
+    #[test]
+    fn test_validate_sefaz_codes_accepts_valid_new_document() {
+        let mut doc = sample_new_document();
+        doc.tipo_operacao = Some("0".to_string());
+        doc.tipo_emissao = Some("9".to_string());
+        doc.finalidade = Some("4".to_string());
+        doc.indicador_presencial = Some("9".to_string());
+        assert!(validate_sefaz_codes(&doc).is_ok());
+    }
+
+    #[test]
+    fn test_validate_totals_rejects_mismatch_and_negative_values() {
+        let mut doc = sample_new_document();
+        assert!(validate_totals(&doc).is_ok());
+
+        doc.valor_frete = Some(Decimal::new(1500, 2));
+        doc.valor_desconto = Some(Decimal::new(-100, 2));
+
+        let errors = validate_totals(&doc).unwrap_err();
+        let codes: Vec<&str> = errors.iter().map(|e| e.code.as_str()).collect();
+        assert_eq!(codes, vec!["NEGATIVE_VALUE", "TOTAL_MISMATCH"]);
+        assert_eq!(errors[0].field, "valor_desconto");
+    }
+
+    #[test]
+    fn test_validate_documents_parallel_mixed_batch() {
+        let valid = sample_new_document();
+
+        let mut bad_total = sample_new_document();
+        bad_total.numero = "124".to_string();
+        bad_total.valor_total = Decimal::new(5000, 2);
+
+        let mut bad_identifiers = sample_new_document();
+        bad_identifiers.modelo = Some("99".to_string());
+        bad_identifiers.serie = "A".to_string();
+
+        let mut bad_dates = sample_new_document();
+        bad_dates.data_emissao = Some(Utc::now() - Duration::days(1));
+        bad_dates.data_autorizacao = Some(Utc::now() - Duration::days(2));
+
+        let config = ParallelConfig {
+            min_parallel_size: 1,
+            ..ParallelConfig::default()
+        };
+        let result = validate_documents_parallel(
+            vec![
+                valid,
+                bad_total,
+                bad_identifiers,
+                bad_dates,
+                sample_new_document(),
+            ],
+            &config,
+        );
+
+        let codes: Vec<Vec<&str>> = result
+            .data
+            .iter()
+            .map(|(_, errors)| errors.iter().map(|e| e.code.as_str()).collect())
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                vec![],
+                vec!["TOTAL_MISMATCH"],
+                vec!["INVALID_MODELO", "INVALID_SERIE"],
+                vec!["AUTHORIZATION_BEFORE_EMISSION"],
+                vec![],
+            ]
+        );
+        assert_eq!(result.data[1].0.numero, "124");
+    }
+
+    #[test]
+    fn test_partition_valid_documents_preserves_order() {
+        let numbered = |numero: &str| {
+            let mut doc = sample_new_document();
+            doc.numero = numero.to_string();
+            doc
+        };
+        let mut bad_total = numbered("2");
+        bad_total.valor_total = Decimal::new(5000, 2);
+        let mut bad_serie = numbered("4");
+        bad_serie.serie = "A".to_string();
+
+        let config = ParallelConfig {
+            min_parallel_size: 1,
+            ..ParallelConfig::default()
+        };
+        let (valid, invalid) = partition_valid_documents(
+            vec![
+                numbered("1"),
+                bad_total,
+                numbered("3"),
+                bad_serie,
+                numbered("5"),
+            ],
+            &config,
+        );
+
+        let valid_numeros: Vec<&str> = valid.iter().map(|doc| doc.numero.as_str()).collect();
+        assert_eq!(valid_numeros, vec!["1", "3", "5"]);
+
+        let invalid_summary: Vec<(&str, Vec<&str>)> = invalid
+            .iter()
+            .map(|(doc, errors)| {
+                (
+                    doc.numero.as_str(),
+                    errors.iter().map(|e| e.code.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            invalid_summary,
+            vec![("2", vec!["TOTAL_MISMATCH"]), ("4", vec!["INVALID_SERIE"])]
+        );
+    }
+
+    #[test]
+    fn test_summarize_validation_counts_by_code() {
+        let numbered = |numero: &str| {
+            let mut doc = sample_new_document();
+            doc.numero = numero.to_string();
+            doc
+        };
+        let mut bad_total = numbered("2");
+        bad_total.valor_total = Decimal::new(5000, 2);
+        let mut bad_serie_and_numero = numbered("007");
+        bad_serie_and_numero.serie = "A".to_string();
+        let mut bad_serie = numbered("4");
+        bad_serie.serie = "B".to_string();
+
+        let config = ParallelConfig {
+            min_parallel_size: 1,
+            ..ParallelConfig::default()
+        };
+        let results = validate_documents_parallel(
+            vec![
+                numbered("1"),
+                bad_total,
+                bad_serie_and_numero,
+                numbered("5"),
+                bad_serie,
+            ],
+            &config,
+        )
+        .data;
+
+        let summary = summarize_validation(&results);
+        assert_eq!(summary.total, 5);
+        assert_eq!(summary.passed, 2);
+        assert_eq!(summary.failed, 3);
+        assert_eq!(
+            summary.errors_by_code,
+            HashMap::from([
+                ("TOTAL_MISMATCH".to_string(), 1),
+                ("INVALID_SERIE".to_string(), 2),
+                ("NUMERO_LEADING_ZERO".to_string(), 1),
+            ])
+        );
+
+        assert_eq!(summarize_validation(&[]), ValidationSummary::default());
+    }
+
+    #[test]
+    fn test_validate_totals_reports_overflow_instead_of_panicking() {
+        let mut doc = sample_new_document();
+        doc.valor_produtos = Decimal::MAX;
+        doc.valor_frete = Some(Decimal::MAX);
+        doc.valor_total = Decimal::MAX;
+
+        let errors = validate_totals(&doc).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, "TOTAL_OVERFLOW");
+    }
+
+    #[test]
+    fn test_engine_run_validates_new_document() {
+        use crate::functional::validation_engine::{validator_with_config, ValidationConfig};
+
+        let engine = validator_with_config::<NewNfeDocument>(ValidationConfig {
+            fail_fast: false,
+            max_errors: None,
+            max_errors_per_field: None,
+            parallel_validation: false,
+        });
+
+        let outcome = engine.run(&sample_new_document());
+        assert!(outcome.is_valid);
+        assert!(outcome.errors.is_empty());
+
+        let mut doc = sample_new_document();
+        doc.valor_total = Decimal::new(5000, 2);
+        doc.data_emissao = Some(Utc::now() - Duration::days(1));
+        doc.data_autorizacao = Some(Utc::now() - Duration::days(2));
+        doc.serie = "A".to_string();
+
+        let outcome = engine.run(&doc);
+        assert!(!outcome.is_valid);
+        let failures: Vec<(&str, &str)> = outcome
+            .errors
+            .iter()
+            .map(|e| (e.field.as_str(), e.code.as_str()))
+            .collect();
+        assert_eq!(
+            failures,
+            vec![
+                ("valor_total", "TOTAL_MISMATCH"),
+                ("data_autorizacao", "AUTHORIZATION_BEFORE_EMISSION"),
+                ("serie", "INVALID_SERIE"),
+            ]
+        );
+
+        let nested = doc.validate(&ValidationContext::new("documents[0]"));
+        assert_eq!(nested.errors[0].field, "documents[0].valor_total");
+    }
+}
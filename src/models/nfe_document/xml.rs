@@ -0,0 +1,679 @@
+//! NF-e XML ingestion
+//!
+//! Maps the fields of an `<NFe>` (optionally wrapped in `<nfeProc>`) onto a
+//! [`NewNfeDocument`]. Parsing is event-based: only the currently open element
+//! path and the text of the current element are buffered, so memory stays
+//! bounded regardless of how many items a document carries.
+
+use std::io::BufRead;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use rust_decimal::Decimal;
+
+use crate::models::nfe_document::NewNfeDocument;
+
+/// Errors raised while reading NF-e XML
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum XmlError {
+    #[error("Malformed XML at byte {position}: {message}")]
+    Malformed { position: u64, message: String },
+
+    #[error("Missing required element <{element}>")]
+    MissingElement { element: &'static str },
+
+    #[error("Invalid value '{value}' in <{element}> at byte {position}")]
+    InvalidValue {
+        element: &'static str,
+        value: String,
+        position: u64,
+    },
+
+    #[error("Unexpected element <{element}> at byte {position}")]
+    UnexpectedElement { element: String, position: u64 },
+}
+
+impl XmlError {
+    /// Byte offset into the input where the error was detected, if known.
+    pub fn position(&self) -> Option<u64> {
+        match self {
+            XmlError::Malformed { position, .. }
+            | XmlError::InvalidValue { position, .. }
+            | XmlError::UnexpectedElement { position, .. } => Some(*position),
+            XmlError::MissingElement { .. } => None,
+        }
+    }
+
+    /// 1-based line and column of [`position`](Self::position) within `xml`, the input
+    /// that produced this error.
+    ///
+    /// Columns count characters, not bytes. Returns `None` for errors without a position
+    /// or when the position lies outside `xml`.
+    pub fn line_column(&self, xml: &str) -> Option<(usize, usize)> {
+        let position = usize::try_from(self.position()?).ok()?;
+        let before = xml.get(..position)?;
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        Some((line, before[line_start..].chars().count() + 1))
+    }
+}
+
+const IDE_SERIE: (&str, &str) = ("ide", "serie");
+const IDE_NUMERO: (&str, &str) = ("ide", "nNF");
+const IDE_MODELO: (&str, &str) = ("ide", "mod");
+const IDE_EMISSAO: (&str, &str) = ("ide", "dhEmi");
+const IDE_SAIDA_ENTRADA: (&str, &str) = ("ide", "dhSaiEnt");
+const IDE_JUSTIFICATIVA: (&str, &str) = ("ide", "xJust");
+const TOTAL_NF: (&str, &str) = ("ICMSTot", "vNF");
+const TOTAL_PRODUTOS: (&str, &str) = ("ICMSTot", "vProd");
+const TOTAL_DESCONTO: (&str, &str) = ("ICMSTot", "vDesc");
+const TOTAL_FRETE: (&str, &str) = ("ICMSTot", "vFrete");
+const TOTAL_SEGURO: (&str, &str) = ("ICMSTot", "vSeg");
+const TOTAL_OUTRO: (&str, &str) = ("ICMSTot", "vOutro");
+const TOTAL_TRIBUTOS: (&str, &str) = ("ICMSTot", "vTotTrib");
+const COMPRA_PEDIDO: (&str, &str) = ("compra", "xPed");
+const COMPRA_CONTRATO: (&str, &str) = ("compra", "xCont");
+const ADIC_COMPLEMENTAR: (&str, &str) = ("infAdic", "infCpl");
+const ADIC_FISCO: (&str, &str) = ("infAdic", "infAdFisco");
+const PROT_NUMERO: (&str, &str) = ("infProt", "nProt");
+const PROT_RECEBIMENTO: (&str, &str) = ("infProt", "dhRecbto");
+
+/// Elements mapped onto document fields, as `(parent, element)` local names.
+///
+/// Matching on the parent keeps item-level tags such as `det/prod/vProd` from
+/// overwriting the document totals.
+const FIELD_PATHS: [(&str, &str); 19] = [
+    IDE_SERIE,
+    IDE_NUMERO,
+    IDE_MODELO,
+    IDE_EMISSAO,
+    IDE_SAIDA_ENTRADA,
+    IDE_JUSTIFICATIVA,
+    TOTAL_NF,
+    TOTAL_PRODUTOS,
+    TOTAL_DESCONTO,
+    TOTAL_FRETE,
+    TOTAL_SEGURO,
+    TOTAL_OUTRO,
+    TOTAL_TRIBUTOS,
+    COMPRA_PEDIDO,
+    COMPRA_CONTRATO,
+    ADIC_COMPLEMENTAR,
+    ADIC_FISCO,
+    PROT_NUMERO,
+    PROT_RECEBIMENTO,
+];
+
+/// Raw element text together with the byte offset where it started.
+#[derive(Debug, Clone)]
+struct RawValue {
+    text: String,
+    position: u64,
+}
+
+/// Accumulates the mapped fields of one NF-e from a stream of XML events.
+#[derive(Debug, Default)]
+struct NfeFieldCollector {
+    /// Local names of the currently open elements
+    path: Vec<String>,
+    /// Text of the innermost open element
+    text: String,
+    text_position: u64,
+    nfe_id: Option<RawValue>,
+    values: [Option<RawValue>; FIELD_PATHS.len()],
+}
+
+impl NfeFieldCollector {
+    /// Feeds one event; `position` is the reader's offset just after it.
+    fn handle(&mut self, event: &Event<'_>, position: u64) -> Result<(), XmlError> {
+        match event {
+            Event::Start(start) => self.open(start, position)?,
+            Event::Empty(start) => {
+                self.open(start, position)?;
+                self.close();
+            }
+            Event::Text(text) => {
+                let unescaped = text.unescape().map_err(|e| XmlError::Malformed {
+                    position,
+                    message: e.to_string(),
+                })?;
+                self.text.push_str(&unescaped);
+            }
+            Event::CData(cdata) => {
+                let decoded = cdata.decode().map_err(|e| XmlError::Malformed {
+                    position,
+                    message: e.to_string(),
+                })?;
+                self.text.push_str(&decoded);
+            }
+            Event::End(_) => self.close(),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn open(&mut self, start: &BytesStart<'_>, position: u64) -> Result<(), XmlError> {
+        let name = String::from_utf8_lossy(start.local_name().as_ref()).into_owned();
+
+        if self.path.is_empty() && name != "NFe" && name != "nfeProc" {
+            return Err(XmlError::UnexpectedElement {
+                element: name,
+                position,
+            });
+        }
+
+        if name == "infNFe" && self.nfe_id.is_none() {
+            let id = start
+                .try_get_attribute("Id")
+                .map_err(|e| XmlError::Malformed {
+                    position,
+                    message: e.to_string(),
+                })?;
+            if let Some(id) = id {
+                let value = id.unescape_value().map_err(|e| XmlError::Malformed {
+                    position,
+                    message: e.to_string(),
+                })?;
+                self.nfe_id = Some(RawValue {
+                    text: value.into_owned(),
+                    position,
+                });
+            }
+        }
+
+        self.path.push(name);
+        self.text.clear();
+        self.text_position = position;
+        Ok(())
+    }
+
+    fn close(&mut self) {
+        let depth = self.path.len();
+        if depth >= 2 {
+            let key = (self.path[depth - 2].as_str(), self.path[depth - 1].as_str());
+            if let Some(index) = FIELD_PATHS.iter().position(|path| *path == key) {
+                if self.values[index].is_none() {
+                    self.values[index] = Some(RawValue {
+                        text: self.text.trim().to_string(),
+                        position: self.text_position,
+                    });
+                }
+            }
+        }
+        self.path.pop();
+        self.text.clear();
+    }
+
+    /// Rejects end of input while elements are still open, which the reader
+    /// does not flag on its own.
+    fn end_of_input(&self, position: u64) -> Result<(), XmlError> {
+        if self.path.is_empty() {
+            return Ok(());
+        }
+        Err(XmlError::Malformed {
+            position,
+            message: format!("{} element(s) not closed at end of input", self.path.len()),
+        })
+    }
+
+    fn value(&self, path: (&str, &str)) -> Option<&RawValue> {
+        FIELD_PATHS
+            .iter()
+            .position(|candidate| *candidate == path)
+            .and_then(|index| self.values[index].as_ref())
+            .filter(|raw| !raw.text.is_empty())
+    }
+
+    fn text(&self, path: (&str, &str)) -> Option<String> {
+        self.value(path).map(|raw| raw.text.clone())
+    }
+
+    fn required_text(&self, path: (&'static str, &'static str)) -> Result<String, XmlError> {
+        self.text(path)
+            .ok_or(XmlError::MissingElement { element: path.1 })
+    }
+
+    fn decimal(&self, path: (&'static str, &'static str)) -> Result<Option<Decimal>, XmlError> {
+        self.value(path)
+            .map(|raw| {
+                Decimal::from_str(&raw.text).map_err(|_| XmlError::InvalidValue {
+                    element: path.1,
+                    value: raw.text.clone(),
+                    position: raw.position,
+                })
+            })
+            .transpose()
+    }
+
+    fn required_decimal(&self, path: (&'static str, &'static str)) -> Result<Decimal, XmlError> {
+        self.decimal(path)?
+            .ok_or(XmlError::MissingElement { element: path.1 })
+    }
+
+    fn datetime(
+        &self,
+        path: (&'static str, &'static str),
+    ) -> Result<Option<DateTime<Utc>>, XmlError> {
+        self.value(path)
+            .map(|raw| {
+                DateTime::parse_from_rfc3339(&raw.text)
+                    .map(|parsed| parsed.with_timezone(&Utc))
+                    .map_err(|_| XmlError::InvalidValue {
+                        element: path.1,
+                        value: raw.text.clone(),
+                        position: raw.position,
+                    })
+            })
+            .transpose()
+    }
+
+    fn finish(self, tenant_id: &str) -> Result<NewNfeDocument, XmlError> {
+        let nfe_id = self
+            .nfe_id
+            .as_ref()
+            .map(|raw| raw.text.clone())
+            .filter(|id| !id.is_empty())
+            .ok_or(XmlError::MissingElement { element: "infNFe" })?;
+
+        Ok(NewNfeDocument {
+            tenant_id: tenant_id.to_string(),
+            nfe_id,
+            serie: self.required_text(IDE_SERIE)?,
+            numero: self.required_text(IDE_NUMERO)?,
+            modelo: self.text(IDE_MODELO),
+            tipo_operacao: None,
+            tipo_emissao: None,
+            finalidade: None,
+            indicador_presencial: None,
+            data_emissao: self.datetime(IDE_EMISSAO)?,
+            data_saida_entrada: self.datetime(IDE_SAIDA_ENTRADA)?,
+            data_autorizacao: self.datetime(PROT_RECEBIMENTO)?,
+            data_cancelamento: None,
+            valor_total: self.required_decimal(TOTAL_NF)?,
+            valor_desconto: self.decimal(TOTAL_DESCONTO)?,
+            valor_frete: self.decimal(TOTAL_FRETE)?,
+            valor_seguro: self.decimal(TOTAL_SEGURO)?,
+            valor_outras_despesas: self.decimal(TOTAL_OUTRO)?,
+            valor_produtos: self.required_decimal(TOTAL_PRODUTOS)?,
+            valor_impostos: self.decimal(TOTAL_TRIBUTOS)?.unwrap_or_default(),
+            pedido_compra: self.text(COMPRA_PEDIDO),
+            contrato: self.text(COMPRA_CONTRATO),
+            informacoes_adicionais: self.text(ADIC_COMPLEMENTAR),
+            informacoes_fisco: self.text(ADIC_FISCO),
+            protocolo_autorizacao: self.text(PROT_NUMERO),
+            motivo_cancelamento: None,
+            justificativa_contingencia: self.text(IDE_JUSTIFICATIVA),
+        })
+    }
+}
+
+/// An `<NFe>` element of a batch that is still being read.
+struct BatchEntry {
+    collector: NfeFieldCollector,
+    /// Nesting depth of the `<NFe>` start tag within the batch
+    depth: usize,
+    /// First error raised while feeding this entry's events
+    error: Option<XmlError>,
+}
+
+impl BatchEntry {
+    fn new(depth: usize) -> Self {
+        Self {
+            collector: NfeFieldCollector::default(),
+            depth,
+            error: None,
+        }
+    }
+
+    fn feed(&mut self, event: &Event<'_>, position: u64) {
+        if self.error.is_none() {
+            if let Err(err) = self.collector.handle(event, position) {
+                self.error = Some(err);
+            }
+        }
+    }
+
+    fn finish(self, tenant_id: &str) -> Result<NewNfeDocument, XmlError> {
+        match self.error {
+            Some(err) => Err(err),
+            None => self.collector.finish(tenant_id),
+        }
+    }
+}
+
+fn malformed<R>(reader: &Reader<R>, error: quick_xml::Error) -> XmlError {
+    XmlError::Malformed {
+        position: reader.error_position(),
+        message: error.to_string(),
+    }
+}
+
+/// Parses a single NF-e held in memory.
+///
+/// # Examples
+///
+/// ```
+/// let doc = from_nfe_xml(&xml, "tenant1")?;
+/// assert_eq!(doc.tenant_id, "tenant1");
+/// ```
+pub fn from_nfe_xml(xml: &str, tenant_id: &str) -> Result<NewNfeDocument, XmlError> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut collector = NfeFieldCollector::default();
+    loop {
+        let event = reader.read_event().map_err(|e| malformed(&reader, e))?;
+        if matches!(event, Event::Eof) {
+            collector.end_of_input(reader.buffer_position())?;
+            break;
+        }
+        collector.handle(&event, reader.buffer_position())?;
+    }
+
+    collector.finish(tenant_id)
+}
+
+/// Parses a single NF-e from a buffered reader without loading it into memory.
+///
+/// Produces the same document as [`from_nfe_xml`]; errors carry the byte
+/// offset into the stream where parsing failed.
+///
+/// # Examples
+///
+/// ```
+/// let file = std::io::BufReader::new(std::fs::File::open("nfe.xml")?);
+/// let doc = from_nfe_xml_reader(file, "tenant1")?;
+/// ```
+pub fn from_nfe_xml_reader(
+    reader: impl BufRead,
+    tenant_id: &str,
+) -> Result<NewNfeDocument, XmlError> {
+    let mut reader = Reader::from_reader(reader);
+    reader.config_mut().trim_text(true);
+
+    let mut collector = NfeFieldCollector::default();
+    let mut buf = Vec::new();
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|e| malformed(&reader, e))?;
+        if matches!(event, Event::Eof) {
+            collector.end_of_input(reader.buffer_position())?;
+            break;
+        }
+        collector.handle(&event, reader.buffer_position())?;
+        buf.clear();
+    }
+
+    collector.finish(tenant_id)
+}
+
+/// Parses every `<NFe>` in a batch wrapper such as `<enviNFe>`.
+///
+/// Each document gets its own result in document order, so a document with
+/// missing or invalid fields does not abort the batch. The outer error is
+/// returned only when the XML itself is not well-formed.
+///
+/// # Examples
+///
+/// ```
+/// let results = parse_nfe_batch(&envi_nfe_xml, "tenant1")?;
+/// let accepted: Vec<_> = results.into_iter().filter_map(Result::ok).collect();
+/// ```
+pub fn parse_nfe_batch(
+    xml: &str,
+    tenant_id: &str,
+) -> Result<Vec<Result<NewNfeDocument, XmlError>>, XmlError> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut results = Vec::new();
+    let mut current: Option<BatchEntry> = None;
+    let mut depth = 0usize;
+    loop {
+        let event = reader.read_event().map_err(|e| malformed(&reader, e))?;
+        let position = reader.buffer_position();
+
+        match &event {
+            // The reader does not flag elements left open at end of input
+            Event::Eof if depth > 0 => {
+                return Err(XmlError::Malformed {
+                    position,
+                    message: format!("{} element(s) not closed at end of input", depth),
+                });
+            }
+            Event::Eof => break,
+            Event::Start(start) => {
+                depth += 1;
+                if current.is_none() && start.local_name().as_ref() == b"NFe" {
+                    current = Some(BatchEntry::new(depth));
+                }
+            }
+            Event::Empty(start) if current.is_none() && start.local_name().as_ref() == b"NFe" => {
+                let mut entry = BatchEntry::new(depth + 1);
+                entry.feed(&event, position);
+                results.push(entry.finish(tenant_id));
+                continue;
+            }
+            _ => {}
+        }
+
+        if let Some(entry) = current.as_mut() {
+            entry.feed(&event, position);
+        }
+
+        if let Event::End(_) = event {
+            if current.as_ref().is_some_and(|entry| entry.depth == depth) {
+                if let Some(entry) = current.take() {
+                    results.push(entry.finish(tenant_id));
+                }
+            }
+            depth -= 1;
+        }
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const NFE_FIXTURE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<nfeProc xmlns="http://www.portalfiscal.inf.br/nfe" versao="4.00">
+  <NFe>
+    <infNFe Id="NFe35240112345678000190550010000000011000000010" versao="4.00">
+      <ide>
+        <mod>55</mod>
+        <serie>1</serie>
+        <nNF>1</nNF>
+        <dhEmi>2024-01-15T10:30:00-03:00</dhEmi>
+      </ide>
+      <det nItem="1">
+        <prod><xProd>Parafuso &amp; porca</xProd><vProd>99.00</vProd></prod>
+      </det>
+      <total>
+        <ICMSTot>
+          <vProd>1234.50</vProd>
+          <vFrete>10.00</vFrete>
+          <vDesc>0.00</vDesc>
+          <vNF>1244.50</vNF>
+          <vTotTrib>150.25</vTotTrib>
+        </ICMSTot>
+      </total>
+      <compra><xPed>PO-42</xPed></compra>
+      <infAdic><infCpl><![CDATA[Entrega <urgente>]]></infCpl></infAdic>
+    </infNFe>
+  </NFe>
+  <protNFe versao="4.00">
+    <infProt>
+      <dhRecbto>2024-01-15T10:31:00-03:00</dhRecbto>
+      <nProt>135240000000001</nProt>
+    </infProt>
+  </protNFe>
+</nfeProc>"#;
+
+    #[test]
+    fn test_from_nfe_xml_reader_matches_string_parser() {
+        let from_string = from_nfe_xml(NFE_FIXTURE, "tenant1").unwrap();
+        let from_reader =
+            from_nfe_xml_reader(Cursor::new(NFE_FIXTURE.as_bytes()), "tenant1").unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&from_string).unwrap(),
+            serde_json::to_value(&from_reader).unwrap()
+        );
+        assert_eq!(
+            from_reader.nfe_id,
+            "NFe35240112345678000190550010000000011000000010"
+        );
+        assert_eq!(from_reader.serie, "1");
+        assert_eq!(from_reader.modelo.as_deref(), Some("55"));
+        assert_eq!(from_reader.valor_total, Decimal::new(124450, 2));
+        assert_eq!(from_reader.valor_produtos, Decimal::new(123450, 2));
+        assert_eq!(from_reader.valor_frete, Some(Decimal::new(1000, 2)));
+        assert_eq!(from_reader.valor_impostos, Decimal::new(15025, 2));
+        assert_eq!(from_reader.valor_seguro, None);
+        assert_eq!(from_reader.pedido_compra.as_deref(), Some("PO-42"));
+        assert_eq!(
+            from_reader.informacoes_adicionais.as_deref(),
+            Some("Entrega <urgente>")
+        );
+        assert_eq!(
+            from_reader.protocolo_autorizacao.as_deref(),
+            Some("135240000000001")
+        );
+        assert_eq!(
+            from_reader.data_emissao.unwrap().to_rfc3339(),
+            "2024-01-15T13:30:00+00:00"
+        );
+    }
+
+    /// Extracts the `<NFe>` element from the fixture for embedding in a batch.
+    fn fixture_nfe() -> &'static str {
+        let start = NFE_FIXTURE.find("<NFe>").unwrap();
+        let end = NFE_FIXTURE.find("</NFe>").unwrap() + "</NFe>".len();
+        &NFE_FIXTURE[start..end]
+    }
+
+    #[test]
+    fn test_parse_nfe_batch_returns_per_document_results() {
+        let good = fixture_nfe();
+        let malformed_entry = good.replace("<vNF>1244.50</vNF>", "<vNF>abc</vNF>");
+        let batch = format!(
+            r#"<enviNFe xmlns="http://www.portalfiscal.inf.br/nfe" versao="4.00"><idLote>1</idLote><indSinc>1</indSinc>{}{}</enviNFe>"#,
+            good, malformed_entry
+        );
+
+        let results = parse_nfe_batch(&batch, "tenant1").unwrap();
+
+        assert_eq!(results.len(), 2);
+        let parsed = results[0].as_ref().unwrap();
+        assert_eq!(
+            parsed.nfe_id,
+            "NFe35240112345678000190550010000000011000000010"
+        );
+        assert_eq!(parsed.valor_total, Decimal::new(124450, 2));
+        assert!(matches!(
+            &results[1],
+            Err(XmlError::InvalidValue { element: "vNF", value, .. }) if value == "abc"
+        ));
+    }
+
+    #[test]
+    fn test_parse_nfe_batch_rejects_malformed_wrapper() {
+        let batch = format!("<enviNFe><idLote>1</idLote>{}", fixture_nfe());
+        assert!(matches!(
+            parse_nfe_batch(&batch, "tenant1"),
+            Err(XmlError::Malformed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_from_nfe_xml_reader_reports_error_positions() {
+        let truncated = NFE_FIXTURE.replace("</ide>", "");
+        match from_nfe_xml_reader(Cursor::new(truncated.as_bytes()), "tenant1") {
+            Err(XmlError::Malformed { position, .. }) => assert!(position > 0),
+            other => panic!("expected malformed error, got {:?}", other),
+        }
+
+        let bad_total = NFE_FIXTURE.replace("<vNF>1244.50</vNF>", "<vNF>12x4</vNF>");
+        match from_nfe_xml_reader(Cursor::new(bad_total.as_bytes()), "tenant1") {
+            Err(XmlError::InvalidValue {
+                element,
+                value,
+                position,
+            }) => {
+                assert_eq!(element, "vNF");
+                assert_eq!(value, "12x4");
+                assert_eq!(&bad_total[position as usize..][..4], "12x4");
+            }
+            other => panic!("expected invalid value error, got {:?}", other),
+        }
+
+        let missing_serie = NFE_FIXTURE.replace("<serie>1</serie>", "");
+        assert_eq!(
+            from_nfe_xml_reader(Cursor::new(missing_serie.as_bytes()), "tenant1").unwrap_err(),
+            XmlError::MissingElement { element: "serie" }
+        );
+    }
+
+    #[test]
+    fn test_truncated_input_is_malformed() {
+        // Every mapped field is present; only the closing wrapper tags are missing
+        let truncated = &NFE_FIXTURE[..NFE_FIXTURE.find("</NFe>").unwrap()];
+
+        let expected = XmlError::Malformed {
+            position: truncated.len() as u64,
+            message: "2 element(s) not closed at end of input".to_string(),
+        };
+        assert_eq!(from_nfe_xml(truncated, "tenant1").unwrap_err(), expected);
+        assert_eq!(
+            from_nfe_xml_reader(Cursor::new(truncated.as_bytes()), "tenant1").unwrap_err(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_xml_error_display_and_line_column() {
+        let cte = "<?xml version=\"1.0\"?>\n<cteProc>\n  <CTe/>\n</cteProc>";
+        let err = from_nfe_xml(cte, "tenant1").unwrap_err();
+        assert_eq!(
+            err,
+            XmlError::UnexpectedElement {
+                element: "cteProc".to_string(),
+                position: 31,
+            }
+        );
+        assert_eq!(err.to_string(), "Unexpected element <cteProc> at byte 31");
+        assert_eq!(err.line_column(cte), Some((2, 10)));
+
+        let bad_total = NFE_FIXTURE.replace("<vNF>1244.50</vNF>", "<vNF>12x4</vNF>");
+        let err = from_nfe_xml(&bad_total, "tenant1").unwrap_err();
+        assert!(err.to_string().contains("'12x4' in <vNF>"), "{}", err);
+        let (line, column) = err.line_column(&bad_total).unwrap();
+        let source_line = bad_total.lines().nth(line - 1).unwrap();
+        assert!(
+            source_line[column - 1..].starts_with("12x4"),
+            "{}",
+            source_line
+        );
+
+        let malformed = XmlError::Malformed {
+            position: 70,
+            message: "unclosed tag".to_string(),
+        };
+        assert_eq!(
+            malformed.to_string(),
+            "Malformed XML at byte 70: unclosed tag"
+        );
+        assert_eq!(malformed.line_column("<a>\n<b>"), None);
+
+        let missing = XmlError::MissingElement { element: "nNF" };
+        assert_eq!(missing.to_string(), "Missing required element <nNF>");
+        assert_eq!(missing.line_column(NFE_FIXTURE), None);
+    }
+}
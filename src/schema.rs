@@ -20,6 +20,24 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    nfe_audit_log (id) {
+        id -> Int4,
+        #[max_length = 36]
+        tenant_id -> Varchar,
+        #[max_length = 50]
+        nfe_id -> Varchar,
+        #[max_length = 20]
+        from_status -> Varchar,
+        #[max_length = 20]
+        to_status -> Varchar,
+        #[max_length = 255]
+        changed_by -> Varchar,
+        reason -> Nullable<Text>,
+        changed_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     nfe_cofins (id) {
         id -> Int4,
@@ -37,6 +55,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    nfe_correction_events (id) {
+        id -> Int4,
+        #[max_length = 36]
+        tenant_id -> Varchar,
+        #[max_length = 50]
+        nfe_id -> Varchar,
+        sequencia -> Int4,
+        texto_correcao -> Text,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     nfe_documents (id) {
         id -> Int4,
@@ -85,6 +116,7 @@ diesel::table! {
         justificativa_contingencia -> Nullable<Text>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        version -> Int4,
     }
 }
 
@@ -531,7 +563,9 @@ diesel::joinable!(refresh_tokens -> users (user_id));
 diesel::allow_tables_to_appear_in_same_query!(
     configuration,
     login_history,
+    nfe_audit_log,
     nfe_cofins,
+    nfe_correction_events,
     nfe_documents,
     nfe_emitters,
     nfe_fiscal_info,